@@ -0,0 +1,312 @@
+use std::fmt;
+
+use actix_web::{
+    Error, HttpResponse, ResponseError,
+    body::{EitherBody, MessageBody},
+    dev::{ServiceRequest, ServiceResponse},
+    http::{
+        StatusCode,
+        header::{HeaderName, HeaderValue, RETRY_AFTER, WWW_AUTHENTICATE},
+    },
+    middleware::Next,
+};
+use api_types::error::{ErrorCode, ErrorResponse, FieldError};
+use contracts::auth::error::{
+    AuthError, HSMStoreError, PasswordHasherError, PasswordPolicyViolation,
+};
+use log::{error, info};
+use webauthn_rs::prelude::Uuid;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Error returned by the handlers. The response carries only the code and its fixed message;
+/// the underlying `AuthError` is logged with the request id and never sent to the client.
+#[derive(Debug)]
+pub struct ApiError {
+    code: ErrorCode,
+    fields: Vec<FieldError>,
+    /// Seconds sent in `Retry-After`.
+    retry_after: Option<u64>,
+    source: Option<AuthError>,
+}
+
+impl ApiError {
+    pub fn new(code: ErrorCode) -> Self {
+        Self {
+            code,
+            fields: Vec::new(),
+            retry_after: None,
+            source: None,
+        }
+    }
+
+    pub fn invalid_request(fields: Vec<FieldError>) -> Self {
+        Self {
+            fields,
+            ..Self::new(ErrorCode::InvalidRequest)
+        }
+    }
+
+    fn response(&self, request_id: Option<String>) -> HttpResponse {
+        let status = self.status_code();
+        let id = request_id.as_deref().unwrap_or("-");
+        if status.is_server_error() {
+            error!("[{}] {}", id, self);
+        } else {
+            info!("[{}] {}", id, self);
+        }
+
+        let mut response = HttpResponse::build(status);
+        if let Some(retry_after) = self.retry_after {
+            response.insert_header((RETRY_AFTER, retry_after));
+        }
+        response.json(ErrorResponse {
+            code: self.code,
+            message: message(self.code).to_string(),
+            fields: self.fields.clone(),
+            request_id,
+        })
+    }
+}
+
+impl From<AuthError> for ApiError {
+    fn from(err: AuthError) -> Self {
+        let code = match &err {
+            AuthError::InvalidUsernameOrPassword => ErrorCode::InvalidCredentials,
+            AuthError::TokenValidationFailed => ErrorCode::InvalidToken,
+            AuthError::InvalidRefreshToken => ErrorCode::InvalidRefreshToken,
+            AuthError::RefreshTokenReused => ErrorCode::RefreshTokenReused,
+            AuthError::InsufficientScope => ErrorCode::InsufficientScope,
+            AuthError::StepUpCredentialRequired => ErrorCode::StepUpCredentialRequired,
+            AuthError::UserNotFound => ErrorCode::UserNotFound,
+            AuthError::UsernameAlreadyTaken => ErrorCode::UsernameTaken,
+            AuthError::WeakPassword(_) => ErrorCode::WeakPassword,
+            AuthError::WebAuthnRegistrationNotFound
+            | AuthError::WebAuthnAuthenticationNotFound
+            | AuthError::TotpRegistrationNotFound => ErrorCode::ChallengeNotFound,
+            AuthError::TotpNotEnabled => ErrorCode::TotpNotEnabled,
+            AuthError::TotpCredentialNotFound => ErrorCode::TotpCredentialNotFound,
+            AuthError::InvalidTotpCode => ErrorCode::InvalidTotpCode,
+            AuthError::PasskeyNotFound => ErrorCode::PasskeyNotFound,
+            AuthError::WebauthnError(_) => ErrorCode::PasskeyVerificationFailed,
+            AuthError::MfaNotEnabled => ErrorCode::MfaNotEnabled,
+            AuthError::LastMfaFactor => ErrorCode::LastMfaFactor,
+            AuthError::InvalidRecoveryCode => ErrorCode::InvalidRecoveryCode,
+            AuthError::TooManyAttempts { .. } => ErrorCode::TooManyAttempts,
+            AuthError::PasswordHasherError(PasswordHasherError::Busy)
+            | AuthError::SetHsmStoreError(HSMStoreError::Full) => ErrorCode::ServiceBusy,
+            AuthError::MFATokenCreationFailed
+            | AuthError::RefreshTokenCreationFailed
+            | AuthError::AccessTokenCreationFailed
+            | AuthError::RefreshTokenStoreError(_)
+            | AuthError::TokenRevocationStoreError(_)
+            | AuthError::InternalError(_)
+            | AuthError::AttemptStoreError(_)
+            | AuthError::BreachedPasswordsError(_)
+            | AuthError::JwksFetchFailed
+            | AuthError::SigningKeyRotationFailed
+            | AuthError::GetHsmStoreError(_)
+            | AuthError::SetHsmStoreError(_)
+            | AuthError::PasswordHasherError(PasswordHasherError::HashingFailed(_))
+            | AuthError::SerdeError(_)
+            | AuthError::FindUserError(_)
+            | AuthError::SaveUserError(_)
+            | AuthError::TotpError(_) => ErrorCode::InternalError,
+        };
+
+        let retry_after = match &err {
+            AuthError::TooManyAttempts { retry_after } => Some(*retry_after),
+            AuthError::PasswordHasherError(PasswordHasherError::Busy)
+            | AuthError::SetHsmStoreError(HSMStoreError::Full) => Some(1),
+            _ => None,
+        };
+        let fields = match &err {
+            AuthError::WeakPassword(violations) => violations
+                .iter()
+                .map(|violation| FieldError {
+                    field: "password".to_string(),
+                    message: violation_message(violation),
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            fields,
+            retry_after,
+            source: Some(err),
+            ..Self::new(code)
+        }
+    }
+}
+
+impl From<actix_web_validator::Error> for ApiError {
+    fn from(err: actix_web_validator::Error) -> Self {
+        let fields = match &err {
+            actix_web_validator::Error::Validate(errors) => {
+                actix_web_validator::error::flatten_errors(errors)
+                    .into_iter()
+                    .map(|(_, field, err)| FieldError {
+                        field,
+                        message: err
+                            .message
+                            .as_ref()
+                            .map(|message| message.to_string())
+                            .unwrap_or_else(|| err.code.to_string()),
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        info!("Invalid request: {}", err);
+        Self::invalid_request(fields)
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.source {
+            Some(source) => write!(f, "{:?}: {}", self.code, source),
+            None => write!(f, "{:?}", self.code),
+        }
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        match self.code {
+            ErrorCode::InvalidRequest
+            | ErrorCode::WeakPassword
+            | ErrorCode::StepUpCredentialRequired
+            | ErrorCode::ChallengeNotFound => StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidCredentials
+            | ErrorCode::InvalidToken
+            | ErrorCode::InvalidRefreshToken
+            | ErrorCode::RefreshTokenReused
+            | ErrorCode::InvalidTotpCode
+            | ErrorCode::PasskeyVerificationFailed
+            | ErrorCode::InvalidRecoveryCode => StatusCode::UNAUTHORIZED,
+            ErrorCode::InsufficientScope => StatusCode::FORBIDDEN,
+            ErrorCode::UserNotFound
+            | ErrorCode::TotpNotEnabled
+            | ErrorCode::TotpCredentialNotFound
+            | ErrorCode::PasskeyNotFound
+            | ErrorCode::MfaNotEnabled => StatusCode::NOT_FOUND,
+            ErrorCode::UsernameTaken | ErrorCode::LastMfaFactor => StatusCode::CONFLICT,
+            ErrorCode::TooManyAttempts => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::ServiceBusy => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::InternalError | ErrorCode::Unknown => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        self.response(REQUEST_ID.try_with(Clone::clone).ok())
+    }
+}
+
+fn message(code: ErrorCode) -> &'static str {
+    match code {
+        ErrorCode::InvalidRequest => "Requisição inválida",
+        ErrorCode::InvalidCredentials => "Usuário ou senha inválidos",
+        ErrorCode::InvalidToken | ErrorCode::InvalidRefreshToken => "Sessão inválida ou expirada",
+        ErrorCode::RefreshTokenReused => "Sessão encerrada por reutilização do token",
+        ErrorCode::InsufficientScope => "Confirme sua identidade para gerenciar o MFA",
+        ErrorCode::StepUpCredentialRequired => "Informe a senha ou o código TOTP",
+        ErrorCode::UserNotFound => "Usuário não encontrado",
+        ErrorCode::UsernameTaken => "E-mail já cadastrado",
+        ErrorCode::WeakPassword => "A senha não atende aos requisitos de segurança",
+        ErrorCode::ChallengeNotFound => "Desafio expirado ou inexistente; comece novamente",
+        ErrorCode::TotpNotEnabled => "TOTP não está ativado",
+        ErrorCode::TotpCredentialNotFound => "Autenticador não encontrado",
+        ErrorCode::InvalidTotpCode => "Código inválido",
+        ErrorCode::PasskeyNotFound => "Passkey não encontrada",
+        ErrorCode::PasskeyVerificationFailed => "Falha na verificação da passkey",
+        ErrorCode::MfaNotEnabled => "Nenhum fator MFA está ativado",
+        ErrorCode::LastMfaFactor => "Não é possível remover o último fator MFA",
+        ErrorCode::InvalidRecoveryCode => "Código de recuperação inválido",
+        ErrorCode::TooManyAttempts => "Muitas tentativas. Tente novamente mais tarde",
+        ErrorCode::ServiceBusy => "Servidor ocupado. Tente novamente em instantes",
+        ErrorCode::InternalError | ErrorCode::Unknown => "Erro interno",
+    }
+}
+
+fn violation_message(violation: &PasswordPolicyViolation) -> String {
+    match violation {
+        PasswordPolicyViolation::TooShort { min_length } => {
+            format!("A senha deve ter no mínimo {} caracteres", min_length)
+        }
+        PasswordPolicyViolation::TooLong { max_length } => {
+            format!("A senha deve ter no máximo {} caracteres", max_length)
+        }
+        PasswordPolicyViolation::MissingUppercase => {
+            "A senha deve ter uma letra maiúscula".to_string()
+        }
+        PasswordPolicyViolation::MissingLowercase => {
+            "A senha deve ter uma letra minúscula".to_string()
+        }
+        PasswordPolicyViolation::MissingDigit => "A senha deve ter um número".to_string(),
+        PasswordPolicyViolation::MissingSpecial => {
+            "A senha deve ter um caractere especial".to_string()
+        }
+        PasswordPolicyViolation::ContainsPersonalInfo => {
+            "A senha não pode conter seu nome ou e-mail".to_string()
+        }
+        PasswordPolicyViolation::Breached => {
+            "Esta senha apareceu em vazamentos de dados; escolha outra".to_string()
+        }
+    }
+}
+
+/// Tags the request with the `X-Request-Id` it came with, or a new one, and echoes it in the
+/// response. Error bodies built while handling the request carry the same id, and errors raised
+/// outside the handlers (e.g. a missing bearer token) are rewritten into an `ErrorResponse`.
+pub async fn request_id<B: MessageBody + 'static>(
+    req: ServiceRequest,
+    next: Next<B>,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
+    let id = req
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| {
+            !value.is_empty()
+                && value.len() <= 64
+                && value
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        })
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let header = HeaderValue::from_str(&id).expect("request id is a valid header value");
+
+    let res = REQUEST_ID.scope(id.clone(), next.call(req)).await?;
+    let foreign_error = res
+        .response()
+        .error()
+        .is_some_and(|err| err.as_error::<ApiError>().is_none());
+    let mut res = if foreign_error {
+        let status = res.status();
+        let code = if status == StatusCode::UNAUTHORIZED {
+            ErrorCode::InvalidToken
+        } else if status.is_server_error() {
+            ErrorCode::InternalError
+        } else {
+            ErrorCode::InvalidRequest
+        };
+        let mut response = ApiError::new(code).response(Some(id));
+        *response.status_mut() = status;
+        if let Some(challenge) = res.headers().get(WWW_AUTHENTICATE) {
+            response
+                .headers_mut()
+                .insert(WWW_AUTHENTICATE, challenge.clone());
+        }
+        res.into_response(response).map_into_right_body()
+    } else {
+        res.map_into_left_body()
+    };
+    res.headers_mut().insert(REQUEST_ID_HEADER, header);
+    Ok(res)
+}
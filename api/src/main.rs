@@ -1,55 +1,81 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    str::FromStr,
+    sync::Arc,
+};
+
+mod error;
+#[cfg(test)]
+mod tests;
 
 use actix_cors::Cors;
 use actix_web::{
-    App, Error, HttpMessage, HttpResponse, HttpServer, Responder,
+    App, Error, HttpMessage, HttpRequest, HttpResponse, HttpServer, ResponseError,
     body::{EitherBody, MessageBody},
+    delete,
     dev::{ServiceRequest, ServiceResponse},
     get, http,
     middleware::{Logger, Next, from_fn},
-    post, web,
+    patch, post, web,
 };
 use actix_web_httpauth::extractors::bearer::{self, BearerAuth};
 use actix_web_validator::Json;
 use api_types::{
-    error::ErrorResponse,
+    error::{ErrorCode, FieldError},
     login::{LoginRequest, LoginResponse},
-    mfa::MfaRegistrationResponse,
+    logout::LogoutRequest,
+    mfa::{MfaRegistrationResponse, MfaStepUpRequest, MfaStepUpResponse},
+    recovery::{RecoveryCodesResponse, RecoveryLoginRequest, RecoveryLoginResponse},
     signup::{SignupRequest, SignupResponse},
-    totp::{TotpSetupResponse, TotpVerifyRequest, TotpVerifyResponse},
+    token::{RefreshTokenRequest, RefreshTokenResponse},
+    totp::{
+        TotpCredentialResponse, TotpLoginRequest, TotpRegistrationFinishResponse,
+        TotpRenameRequest, TotpSetupResponse, TotpVerifyRequest, TotpVerifyResponse,
+    },
+    webauthn::{
+        PasskeyLoginFinishRequest, PasskeyLoginStartResponse, PasskeyRenameRequest,
+        PasskeyResponse, WebauthnAuthenticationFinishRequest, WebauthnAuthenticationFinishResponse,
+        WebauthnRegistrationFinishRequest,
+    },
 };
+use app_config::Config;
 use contracts::auth::{
+    error::AuthError,
     login::LoginInput,
-    mfa::MfaRegistrationInput,
-    passkey::PasskeyStartRegistrationInput,
+    logout::{LogoutAllInput, LogoutInput},
+    mfa::{MfaRegistrationInput, MfaRemoveTotpInput, MfaStepUpInput},
+    passkey::{
+        PasskeyFinishAuthenticationInput, PasskeyFinishLoginInput, PasskeyFinishRegistrationInput,
+        PasskeyListInput, PasskeyRemoveInput, PasskeyRenameInput, PasskeyStartAuthenticationInput,
+        PasskeyStartRegistrationInput,
+    },
+    recovery::{RecoveryRegenerateCodesInput, RecoveryVerifyLoginInput},
     signup::SignupInput,
-    totp::{TOTPFinishRegistrationInput, TOTPStartRegistrationInput},
+    token::{RefreshTokenInput, ValidateTokenInput, ValidateTokenOutput},
+    totp::{
+        TOTPFinishRegistrationInput, TOTPListCredentialsInput, TOTPRenameCredentialInput,
+        TOTPStartRegistrationInput, TOTPVerifyLoginInput,
+    },
 };
 use env_logger::{Env, init_from_env};
-use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode, decode_header, jwk::JwkSet};
+use error::{ApiError, REQUEST_ID_HEADER, request_id};
 use log::info;
-use serde::{Deserialize, Serialize};
 use webauthn_rs::prelude::Uuid;
 
 #[post("/signup")]
-async fn greet(data: web::Data<AppState>, body: Json<SignupRequest>) -> impl Responder {
-    match data
-        .auth
+async fn greet(
+    data: web::Data<AppState>,
+    body: Json<SignupRequest>,
+) -> Result<HttpResponse, ApiError> {
+    data.auth
         .signup(SignupInput {
             name: body.name.clone(),
             username: body.email.clone(),
             password: body.password.clone(),
         })
-        .await
-    {
-        Ok(_) => HttpResponse::Ok().json(SignupResponse {}),
-        Err(e) => {
-            info!("Signup error: {}", e);
-            HttpResponse::BadRequest().json(ErrorResponse {
-                message: "Erro ao cadastrar usuário".to_string(),
-            })
-        }
-    }
+        .await?;
+    Ok(HttpResponse::Ok().json(SignupResponse {}))
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -61,87 +87,291 @@ pub struct LoginResult {
 }
 
 #[post("/login")]
-async fn login(data: web::Data<AppState>, body: Json<LoginRequest>) -> impl Responder {
-    match data
+async fn login(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    body: Json<LoginRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let result = data
         .auth
         .login(LoginInput {
             username: body.email.clone(),
             password: body.password.clone(),
+            client_ip: data.client_ip(&req),
         })
-        .await
-    {
-        Ok(result) => HttpResponse::Ok().json(LoginResponse {
-            mfa_verification_token: result.mfa_verification_token,
-            access_token: result.access_token,
-            refresh_token: result.refresh_token,
-            allowed_methods: result.allowed_methods,
-        }),
-        Err(e) => {
-            info!("Login error: {}", e);
-            HttpResponse::Unauthorized().json(ErrorResponse {
-                message: "Usuário ou senha inválidos".to_string(),
-            })
-        }
-    }
+        .await?;
+    Ok(HttpResponse::Ok().json(LoginResponse {
+        mfa_verification_token: result.mfa_verification_token,
+        access_token: result.access_token,
+        refresh_token: result.refresh_token,
+        allowed_methods: result.allowed_methods,
+    }))
+}
+
+#[post("/token/refresh")]
+async fn refresh_token(
+    data: web::Data<AppState>,
+    body: Json<RefreshTokenRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let output = data
+        .auth
+        .refresh_token(RefreshTokenInput {
+            refresh_token: body.refresh_token.clone(),
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(RefreshTokenResponse {
+        access_token: output.access_token,
+        refresh_token: output.refresh_token,
+    }))
+}
+
+#[post("/logout")]
+async fn logout(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+    body: Option<web::Json<LogoutRequest>>,
+) -> Result<HttpResponse, ApiError> {
+    data.auth
+        .logout(LogoutInput {
+            access_token: auth.token().to_string(),
+            refresh_token: body.and_then(|body| body.into_inner().refresh_token),
+        })
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/logout/all")]
+async fn logout_all(data: web::Data<AppState>, auth: BearerAuth) -> Result<HttpResponse, ApiError> {
+    data.auth
+        .logout_all(LogoutAllInput {
+            access_token: auth.token().to_string(),
+        })
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
 }
 
 #[get("/.well-known/jwks.json")]
-async fn jwks(data: web::Data<AppState>) -> impl Responder {
-    match data.auth.get_jwks().await {
-        Ok(jwks_json) => HttpResponse::Ok()
-            .content_type("application/json")
-            .body(jwks_json),
-        Err(e) => {
-            info!("Jwks fetch error: {}", e);
-            HttpResponse::InternalServerError().json(ErrorResponse {
-                message: "Erro ao buscar JWKS".to_string(),
-            })
-        }
-    }
+async fn jwks(data: web::Data<AppState>) -> Result<HttpResponse, ApiError> {
+    let jwks_json = data.auth.get_jwks().await?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(jwks_json))
 }
 
 #[get("/mfa")]
-async fn mfa_registration(data: web::Data<AppState>, auth: BearerAuth) -> impl Responder {
-    match data
+async fn mfa_registration(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+) -> Result<HttpResponse, ApiError> {
+    let output = data
         .auth
         .get_mfa_registration(MfaRegistrationInput {
             access_token: auth.token().to_string(),
         })
-        .await
-    {
-        Ok(output) => HttpResponse::Ok().json(MfaRegistrationResponse {
-            mfa_registration: output.mfa_registration,
-            allowed_methods: output.allowed_methods,
-            expires_in: output.expires_in,
-        }),
-        Err(e) => {
-            info!("MFA registration error: {}", e);
-            HttpResponse::Unauthorized().json(ErrorResponse {
-                message: "Acesso nao autorizado".to_string(),
+        .await?;
+    Ok(HttpResponse::Ok().json(MfaRegistrationResponse {
+        mfa_registration: output.mfa_registration,
+        allowed_methods: output.allowed_methods,
+        expires_in: output.expires_in,
+    }))
+}
+
+#[post("/mfa/step-up")]
+async fn mfa_step_up(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+    body: Json<MfaStepUpRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    let output = data
+        .auth
+        .step_up_mfa(MfaStepUpInput {
+            access_token: auth.token().to_string(),
+            password: body.password,
+            totp_code: body.code,
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(MfaStepUpResponse {
+        access_token: output.access_token,
+        expires_in: output.expires_in,
+    }))
+}
+
+#[get("/totp")]
+async fn totp_list(data: web::Data<AppState>, auth: BearerAuth) -> Result<HttpResponse, ApiError> {
+    let credentials = data
+        .auth
+        .list_totp_credentials(TOTPListCredentialsInput {
+            access_token: auth.token().to_string(),
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(
+        credentials
+            .into_iter()
+            .map(|credential| TotpCredentialResponse {
+                id: credential.id.to_string(),
+                label: credential.label,
+                created_at: credential.created_at,
+                last_used_at: credential.last_used_at,
             })
-        }
-    }
+            .collect::<Vec<_>>(),
+    ))
+}
+
+#[patch("/totp/{id}")]
+async fn totp_rename(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+    path: web::Path<Uuid>,
+    body: Json<TotpRenameRequest>,
+) -> Result<HttpResponse, ApiError> {
+    data.auth
+        .rename_totp_credential(TOTPRenameCredentialInput {
+            access_token: auth.token().to_string(),
+            credential_id: path.into_inner(),
+            label: body.into_inner().label,
+        })
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[delete("/totp")]
+async fn totp_remove(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+) -> Result<HttpResponse, ApiError> {
+    data.auth
+        .remove_totp(MfaRemoveTotpInput {
+            access_token: auth.token().to_string(),
+            credential_id: None,
+        })
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[delete("/totp/{id}")]
+async fn totp_remove_one(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    data.auth
+        .remove_totp(MfaRemoveTotpInput {
+            access_token: auth.token().to_string(),
+            credential_id: Some(path.into_inner()),
+        })
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/mfa/passkeys")]
+async fn passkey_list(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+) -> Result<HttpResponse, ApiError> {
+    let passkeys = data
+        .auth
+        .list_passkeys(PasskeyListInput {
+            access_token: auth.token().to_string(),
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(
+        passkeys
+            .into_iter()
+            .map(|passkey| PasskeyResponse {
+                id: passkey.id.to_string(),
+                name: passkey.name,
+                created_at: passkey.created_at,
+                last_used_at: passkey.last_used_at,
+                aaguid: passkey.aaguid.map(|aaguid| aaguid.to_string()),
+            })
+            .collect::<Vec<_>>(),
+    ))
+}
+
+#[patch("/mfa/passkeys/{id}")]
+async fn passkey_rename(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+    path: web::Path<Uuid>,
+    body: Json<PasskeyRenameRequest>,
+) -> Result<HttpResponse, ApiError> {
+    data.auth
+        .rename_passkey(PasskeyRenameInput {
+            access_token: auth.token().to_string(),
+            passkey_id: path.into_inner(),
+            name: body.into_inner().name,
+        })
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[delete("/mfa/passkeys/{id}")]
+async fn passkey_remove(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, ApiError> {
+    data.auth
+        .remove_passkey(PasskeyRemoveInput {
+            access_token: auth.token().to_string(),
+            passkey_id: path.into_inner(),
+        })
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/recovery/verify")]
+async fn recovery_verify(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    body: Json<RecoveryLoginRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    let output = data
+        .auth
+        .verify_recovery_code(RecoveryVerifyLoginInput {
+            mfa_token: body.mfa_verification,
+            code: body.code,
+            client_ip: data.client_ip(&req),
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(RecoveryLoginResponse {
+        access_token: output.access_token,
+        refresh_token: output.refresh_token,
+    }))
+}
+
+#[post("/recovery/codes")]
+async fn recovery_codes_regenerate(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+) -> Result<HttpResponse, ApiError> {
+    let output = data
+        .auth
+        .regenerate_recovery_codes(RecoveryRegenerateCodesInput {
+            access_token: auth.token().to_string(),
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(RecoveryCodesResponse {
+        recovery_codes: output.recovery_codes,
+    }))
 }
 
 #[post("/totp/start")]
-async fn totp_registration_start(data: web::Data<AppState>, auth: BearerAuth) -> impl Responder {
-    match data
+async fn totp_registration_start(
+    data: web::Data<AppState>,
+    auth: BearerAuth,
+) -> Result<HttpResponse, ApiError> {
+    let result = data
         .auth
         .start_totp_registration(TOTPStartRegistrationInput {
             mfa_token: auth.token().to_string(),
         })
-        .await
-    {
-        Ok(result) => HttpResponse::Ok().json(TotpSetupResponse {
-            qr_code_url: result.auth_url,
-        }),
-        Err(e) => {
-            info!("Totp registration start error: {}", e);
-            HttpResponse::BadRequest().json(ErrorResponse {
-                message: "Erro ao iniciar registro TOTP".to_string(),
-            })
-        }
-    }
+        .await?;
+    Ok(HttpResponse::Ok().json(TotpSetupResponse {
+        qr_code_url: result.auth_url,
+    }))
 }
 
 #[post("/totp/finish")]
@@ -149,65 +379,188 @@ async fn totp_registration_finish(
     data: web::Data<AppState>,
     auth: BearerAuth,
     body: Json<TotpVerifyRequest>,
-) -> impl Responder {
-    match data
+) -> Result<HttpResponse, ApiError> {
+    let output = data
         .auth
         .finish_totp_registration(TOTPFinishRegistrationInput {
             mfa_token: auth.token().to_string(),
             code: body.code.clone(),
+            label: body.label.clone(),
         })
-        .await
-    {
-        Ok(output) => HttpResponse::Ok().json(TotpVerifyResponse {
-            refresh_token: output.refresh_token,
-            access_token: output.access_token,
-        }),
-        Err(e) => {
-            info!("Totp registration finish error: {}", e);
-            HttpResponse::BadRequest().json(ErrorResponse {
-                message: "Erro ao terminar registro TOTP".to_string(),
-            })
-        }
-    }
+        .await?;
+    Ok(HttpResponse::Ok().json(TotpRegistrationFinishResponse {
+        refresh_token: output.refresh_token,
+        access_token: output.access_token,
+        recovery_codes: output.recovery_codes,
+    }))
+}
+
+#[post("/totp/verify")]
+async fn totp_verify(
+    req: HttpRequest,
+    data: web::Data<AppState>,
+    body: Json<TotpLoginRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let output = data
+        .auth
+        .verify_totp_login(TOTPVerifyLoginInput {
+            mfa_token: body.mfa_verification.clone(),
+            code: body.code.clone(),
+            client_ip: data.client_ip(&req),
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(TotpVerifyResponse {
+        refresh_token: output.refresh_token,
+        access_token: output.access_token,
+    }))
+}
+
+/// Subject of the token validated by `protected_route`.
+fn claims_user_id(claims: &ValidateTokenOutput) -> Result<Uuid, ApiError> {
+    Uuid::from_str(&claims.sub).map_err(|_| ApiError::new(ErrorCode::InvalidToken))
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Claims {
-    // aud: String, // Optional. Audience
-    pub exp: usize, // Required (validate_exp defaults to true in validation). Expiration time (as UTC timestamp)
-    // iat: usize, // Optional. Issued at (as UTC timestamp)
-    // iss: String, // Optional. Issuer
-    // nbf: usize,  // Optional. Not Before (as UTC timestamp)
-    pub sub: String, // Optional. Subject (whom token refers to)
-    pub token_type: String,
+fn parse_credential<T: serde::de::DeserializeOwned>(
+    credential: serde_json::Value,
+) -> Result<T, ApiError> {
+    serde_json::from_value(credential).map_err(|_| {
+        ApiError::invalid_request(vec![FieldError {
+            field: "credential".to_string(),
+            message: "Credencial inválida".to_string(),
+        }])
+    })
 }
 
 #[post("/webauthn/start")]
 async fn webauthn_registration_start(
     data: web::Data<AppState>,
-    claims: web::ReqData<Claims>,
-) -> impl Responder {
-    match data
+    claims: web::ReqData<ValidateTokenOutput>,
+) -> Result<HttpResponse, ApiError> {
+    let result = data
         .auth
         .start_passkey_registration(PasskeyStartRegistrationInput {
-            user_id: Uuid::from_str(&claims.sub).unwrap(),
+            user_id: claims_user_id(&claims)?,
         })
-        .await
-    {
-        Ok(result) => HttpResponse::Ok().body(result.challenge),
-        Err(e) => {
-            info!("Webauthn registration start error: {}", e);
-            HttpResponse::BadRequest().json(ErrorResponse {
-                message: "Erro ao iniciar registro TOTP".to_string(),
-            })
-        }
-    }
+        .await?;
+    Ok(HttpResponse::Ok().body(result.challenge))
+}
+
+#[post("/webauthn/finish")]
+async fn webauthn_registration_finish(
+    data: web::Data<AppState>,
+    claims: web::ReqData<ValidateTokenOutput>,
+    body: Json<WebauthnRegistrationFinishRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let user_id = claims_user_id(&claims)?;
+    let body = body.into_inner();
+    let output = data
+        .auth
+        .finish_passkey_registration(PasskeyFinishRegistrationInput {
+            user_id,
+            register_public_key_credential: parse_credential(body.credential)?,
+            name: body.name,
+        })
+        .await?;
+    Ok(HttpResponse::Ok().json(RecoveryCodesResponse {
+        recovery_codes: output.recovery_codes,
+    }))
+}
+
+#[post("/start")]
+async fn webauthn_authentication_start(
+    data: web::Data<AppState>,
+    claims: web::ReqData<ValidateTokenOutput>,
+) -> Result<HttpResponse, ApiError> {
+    let result = data
+        .auth
+        .start_passkey_authentication(PasskeyStartAuthenticationInput {
+            user_id: claims_user_id(&claims)?,
+        })
+        .await?;
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(result.challenge))
+}
+
+#[post("/finish")]
+async fn webauthn_authentication_finish(
+    data: web::Data<AppState>,
+    claims: web::ReqData<ValidateTokenOutput>,
+    body: web::Json<WebauthnAuthenticationFinishRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let output = data
+        .auth
+        .finish_passkey_authentication(PasskeyFinishAuthenticationInput {
+            user_id: claims_user_id(&claims)?,
+            public_key_credential: parse_credential(body.into_inner().credential)?,
+        })
+        .await?;
+    Ok(
+        HttpResponse::Ok().json(WebauthnAuthenticationFinishResponse {
+            access_token: output.access_token,
+            refresh_token: output.refresh_token,
+        }),
+    )
+}
+
+#[post("/passkey/login/start")]
+async fn passkey_login_start(data: web::Data<AppState>) -> Result<HttpResponse, ApiError> {
+    let result = data.auth.start_passkey_login().await?;
+    let options = serde_json::from_str(&result.challenge).map_err(AuthError::SerdeError)?;
+
+    Ok(HttpResponse::Ok().json(PasskeyLoginStartResponse {
+        login_id: result.login_id.to_string(),
+        options,
+    }))
+}
+
+#[post("/passkey/login/finish")]
+async fn passkey_login_finish(
+    data: web::Data<AppState>,
+    body: web::Json<PasskeyLoginFinishRequest>,
+) -> Result<HttpResponse, ApiError> {
+    let body = body.into_inner();
+    let login_id = Uuid::from_str(&body.login_id).map_err(|_| {
+        ApiError::invalid_request(vec![FieldError {
+            field: "login_id".to_string(),
+            message: "Identificador de login inválido".to_string(),
+        }])
+    })?;
+
+    let output = data
+        .auth
+        .finish_passkey_login(PasskeyFinishLoginInput {
+            login_id,
+            public_key_credential: parse_credential(body.credential)?,
+        })
+        .await?;
+    Ok(
+        HttpResponse::Ok().json(WebauthnAuthenticationFinishResponse {
+            access_token: output.access_token,
+            refresh_token: output.refresh_token,
+        }),
+    )
 }
 
 async fn protected_mfa_registration_route(
     req: ServiceRequest,
     next: Next<impl MessageBody>,
 ) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    protected_route(req, next, "mfa_registration").await
+}
+
+async fn protected_mfa_verification_route(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<EitherBody<impl MessageBody>>, Error> {
+    protected_route(req, next, "mfa_verification").await
+}
+
+async fn protected_route<B: MessageBody>(
+    req: ServiceRequest,
+    next: Next<B>,
+    token_type: &str,
+) -> Result<ServiceResponse<EitherBody<B>>, Error> {
     let data = req
         .app_data::<web::Data<AppState>>()
         .ok_or_else(|| actix_web::error::ErrorInternalServerError("AppState missing"))?;
@@ -215,119 +568,197 @@ async fn protected_mfa_registration_route(
     // Exemplo de extração do token (ajuste conforme sua lógica)
     let auth_header = req.headers().get("Authorization");
 
-    if let Some(auth_val) = auth_header {
+    let error = if let Some(auth_val) = auth_header {
         let token = auth_val.to_str().unwrap_or("").replace("Bearer ", "");
 
-        match validate_mfa_registration_token(&data, &token).await {
+        match validate_token(data, &token, token_type).await {
             Ok(claims) => {
                 // SUCESSO: Usamos .map_into_left_body()
                 req.extensions_mut().insert(claims);
                 let res = next.call(req).await?;
-                Ok(res.map_into_left_body())
-            }
-            Err(e) => {
-                // ERRO DE VALIDAÇÃO: Usamos .map_into_right_body()
-                let res = HttpResponse::Unauthorized().body(format!("Unauthorized: {}", e));
-                let s_res = req.into_response(res);
-                Ok(s_res.map_into_right_body())
+                return Ok(res.map_into_left_body());
             }
+            // ERRO DE VALIDAÇÃO
+            Err(e) => ApiError::from(e),
         }
     } else {
-        // SEM HEADER: Usamos .map_into_right_body()
-        let res = HttpResponse::Unauthorized().finish();
-        let s_res = req.into_response(res);
-        Ok(s_res.map_into_right_body())
-    }
+        // SEM HEADER
+        ApiError::new(ErrorCode::InvalidToken)
+    };
+
+    // Usamos .map_into_right_body() para a resposta de erro
+    let s_res = req.into_response(error.error_response());
+    Ok(s_res.map_into_right_body())
 }
 
-async fn validate_mfa_registration_token(
+async fn validate_token(
     data: &web::Data<AppState>,
     token: &str,
-) -> Result<Claims, String> {
-    let jwks_json = data
-        .auth
-        .get_jwks()
+    token_type: &str,
+) -> Result<ValidateTokenOutput, AuthError> {
+    data.auth
+        .validate_token(ValidateTokenInput {
+            token: token.to_string(),
+            token_type: token_type.to_string(),
+        })
         .await
-        .map_err(|err| format!("jwks fetch failed: {}", err))?;
-    let jwks_set: JwkSet =
-        serde_json::from_str(&jwks_json).map_err(|err| format!("invalid jwks: {}", err))?;
-
-    let header = decode_header(token).map_err(|err| format!("invalid token header: {}", err))?;
-    let kid = header
-        .kid
-        .ok_or_else(|| "missing kid in token header".to_string())?;
-    let jwk = jwks_set
-        .find(&kid)
-        .ok_or_else(|| format!("no matching jwk for kid {}", kid))?;
-    let decoding_key =
-        DecodingKey::from_jwk(jwk).map_err(|err| format!("invalid decoding key: {}", err))?;
-
-    let validation = Validation::new(Algorithm::EdDSA);
-    let claims = decode::<Claims>(token, &decoding_key, &validation)
-        .map_err(|err| format!("invalid token: {}", err))?
-        .claims;
-
-    if claims.token_type != "mfa_registration" {
-        return Err(format!("unexpected token type {}", claims.token_type));
-    }
-
-    Ok(claims)
 }
 
 struct AppState {
     auth: Arc<dyn contracts::auth::Component>,
+    trusted_proxies: Vec<IpAddr>,
+}
+
+impl AppState {
+    fn client_ip(&self, req: &HttpRequest) -> Option<IpAddr> {
+        client_ip(req, &self.trusted_proxies)
+    }
+}
+
+/// Address the request is throttled by. When the peer is one of `trusted_proxies`, the
+/// `X-Forwarded-For` entries are walked from the right, where each proxy appended the address it
+/// received the request from, and the first one that is not a trusted proxy is the client. The
+/// entries further left are whatever the client sent and are never used.
+fn client_ip(req: &HttpRequest, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let mut client = req.peer_addr()?.ip();
+    let forwarded = req
+        .headers()
+        .get_all(http::header::X_FORWARDED_FOR)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect::<Vec<_>>();
+    for entry in forwarded.into_iter().rev() {
+        if !trusted_proxies.contains(&client) {
+            break;
+        }
+        let entry = entry.trim();
+        let Some(addr) = entry
+            .parse::<IpAddr>()
+            .ok()
+            .or_else(|| entry.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        else {
+            break;
+        };
+        client = addr;
+    }
+    Some(client)
+}
+
+/// Command line of the api: `--config <path>` and `--dump-config`.
+struct Args {
+    config: Option<PathBuf>,
+    dump_config: bool,
+}
+
+impl Args {
+    fn parse() -> std::io::Result<Self> {
+        let usage = || std::io::Error::other("Usage: api [--config <path>] [--dump-config]");
+        let mut args = Args {
+            config: None,
+            dump_config: false,
+        };
+        let mut iter = std::env::args().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--config" => args.config = Some(iter.next().ok_or_else(usage)?.into()),
+                "--dump-config" => args.dump_config = true,
+                _ => match arg.strip_prefix("--config=") {
+                    Some(path) => args.config = Some(path.into()),
+                    None => return Err(usage()),
+                },
+            }
+        }
+        Ok(args)
+    }
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     init_from_env(Env::default().default_filter_or("info"));
 
-    let auth = Arc::new(auth::AuthComponent::new());
+    let args = Args::parse()?;
+    let config = Config::load(args.config.as_deref())?;
+    if args.dump_config {
+        print!("{}", config.dump()?);
+        return Ok(());
+    }
+
+    let allowed_origins = config.cors_origins().to_vec();
+    let trusted_proxies = config.server.trusted_proxies.clone();
+    let auth = Arc::new(
+        auth::AuthComponent::builder(config.auth_config()?)
+            .build()
+            .await
+            .map_err(std::io::Error::other)?,
+    );
+    info!("Listening on {}", config.server.bind);
 
     HttpServer::new(move || {
-        let cors = Cors::default()
-            .allowed_origin("http://localhost:8000")
-            .allowed_methods(vec!["GET", "POST", "DELETE", "PUT"])
+        let cors = allowed_origins
+            .iter()
+            .fold(Cors::default(), |cors, origin| cors.allowed_origin(origin))
+            .allowed_methods(vec!["GET", "POST", "DELETE", "PUT", "PATCH"])
             .allowed_headers(vec![
                 http::header::AUTHORIZATION,
                 http::header::ACCEPT,
                 http::header::CONTENT_TYPE,
             ])
+            .expose_headers(vec![REQUEST_ID_HEADER, http::header::RETRY_AFTER])
             .max_age(3600);
 
         App::new()
             .wrap(cors)
-            .wrap(Logger::default())
-            .app_data(web::Data::new(AppState { auth: auth.clone() }))
+            .wrap(from_fn(request_id))
+            .wrap(Logger::new(
+                r#"%a "%r" %s %b "%{Referer}i" "%{User-Agent}i" %T %{x-request-id}o"#,
+            ))
+            .app_data(web::Data::new(AppState {
+                auth: auth.clone(),
+                trusted_proxies: trusted_proxies.clone(),
+            }))
             .app_data(bearer::Config::default())
             .app_data(
-                actix_web_validator::JsonConfig::default().error_handler(|err, _req| {
-                    let response = ErrorResponse {
-                        message: err.to_string(),
-                    };
-
-                    actix_web::error::InternalError::from_response(
-                        err,
-                        HttpResponse::BadRequest().json(response),
-                    )
-                    .into()
-                }),
+                actix_web_validator::JsonConfig::default()
+                    .error_handler(|err, _req| ApiError::from(err).into()),
             )
             .service(
                 web::scope("/mfa/registration")
                     .wrap(from_fn(protected_mfa_registration_route))
                     .service(totp_registration_start)
                     .service(totp_registration_finish)
-                    .service(webauthn_registration_start),
+                    .service(webauthn_registration_start)
+                    .service(webauthn_registration_finish),
+            )
+            .service(
+                web::scope("/webauthn/authentication")
+                    .wrap(from_fn(protected_mfa_verification_route))
+                    .service(webauthn_authentication_start)
+                    .service(webauthn_authentication_finish),
             )
+            .service(logout)
+            .service(logout_all)
+            .service(mfa_step_up)
+            .service(totp_list)
+            .service(totp_rename)
+            .service(totp_remove)
+            .service(totp_remove_one)
+            .service(passkey_list)
+            .service(passkey_rename)
+            .service(passkey_remove)
+            .service(passkey_login_start)
+            .service(passkey_login_finish)
+            .service(recovery_verify)
+            .service(recovery_codes_regenerate)
             .service(greet)
             .service(login)
+            .service(refresh_token)
+            .service(totp_verify)
             .service(mfa_registration)
             .service(jwks)
             .service(totp_registration_start)
             .service(totp_registration_finish)
     })
-    .bind(("127.0.0.1", 8080))?
+    .bind(config.server.bind.as_str())?
     .run()
     .await
 }
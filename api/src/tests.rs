@@ -0,0 +1,213 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    App, ResponseError,
+    http::{StatusCode, header::RETRY_AFTER},
+    middleware::from_fn,
+    test, web,
+};
+use api_types::error::ErrorResponse;
+use auth::{AuthComponent, AuthConfig, ThrottleConfig};
+use contracts::auth::{
+    Component,
+    error::{AuthError, AuthTokenError, HSMStoreError, PasswordHasherError},
+    signup::SignupInput,
+};
+
+use crate::{
+    AppState, client_ip,
+    error::{ApiError, request_id},
+    login,
+};
+
+const ROUNDS: usize = 5;
+
+/// A wrong password for a registered e-mail and any password for an unknown e-mail must get the
+/// same response, in about the same time.
+#[actix_web::test]
+async fn login_does_not_reveal_registered_emails() {
+    let auth = AuthComponent::builder(AuthConfig {
+        // Every attempt below must reach the password check.
+        throttle: ThrottleConfig {
+            free_attempts: 100,
+            lockout_threshold: 100,
+            ..ThrottleConfig::default()
+        },
+        ..AuthConfig::default()
+    })
+    .build()
+    .await
+    .unwrap();
+    auth.signup(SignupInput {
+        name: "Ana".to_string(),
+        username: "ana@exemplo.com".to_string(),
+        password: "Password123!".to_string(),
+    })
+    .await
+    .unwrap();
+
+    let app = test::init_service(
+        App::new()
+            .wrap(from_fn(request_id))
+            .app_data(web::Data::new(AppState {
+                auth: Arc::new(auth),
+                trusted_proxies: Vec::new(),
+            }))
+            .service(login),
+    )
+    .await;
+
+    let mut registered = Vec::new();
+    let mut unknown = Vec::new();
+    for _ in 0..ROUNDS {
+        for (email, results) in [
+            ("ana@exemplo.com", &mut registered),
+            ("bia@exemplo.com", &mut unknown),
+        ] {
+            let req = test::TestRequest::post()
+                .uri("/login")
+                .set_json(serde_json::json!({ "email": email, "password": "WrongPassword1!" }))
+                .to_request();
+            let started = Instant::now();
+            let res = test::call_service(&app, req).await;
+            let status = res.status();
+            let body: ErrorResponse = test::read_body_json(res).await;
+            results.push((started.elapsed(), status, body));
+        }
+    }
+
+    for ((_, registered_status, registered_body), (_, unknown_status, unknown_body)) in
+        registered.iter().zip(&unknown)
+    {
+        assert_eq!(*registered_status, StatusCode::UNAUTHORIZED);
+        assert_eq!(registered_status, unknown_status);
+        assert_eq!(registered_body.code, unknown_body.code);
+        assert_eq!(registered_body.message, unknown_body.message);
+        assert_eq!(registered_body.fields.len(), unknown_body.fields.len());
+    }
+
+    let registered = median(registered.iter().map(|(elapsed, ..)| *elapsed));
+    let unknown = median(unknown.iter().map(|(elapsed, ..)| *elapsed));
+    let (fast, slow) = (registered.min(unknown), registered.max(unknown));
+    assert!(
+        fast * 2 > slow,
+        "registered {:?} vs unknown {:?}",
+        registered,
+        unknown
+    );
+}
+
+/// A bad token is the client's fault; failing to read the keys or the revocation list is not.
+#[actix_web::test]
+async fn token_errors_map_to_invalid_token_or_internal_error() {
+    for (err, status) in [
+        (AuthTokenError::InvalidToken, StatusCode::UNAUTHORIZED),
+        (AuthTokenError::TokenExpired, StatusCode::UNAUTHORIZED),
+        (AuthTokenError::InvalidSignature, StatusCode::UNAUTHORIZED),
+        (AuthTokenError::TokenRevoked, StatusCode::UNAUTHORIZED),
+        (
+            AuthTokenError::RevocationCheckFailed,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+        (
+            AuthTokenError::JwksFetchError,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+        (
+            AuthTokenError::TokenCreationFailure,
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ),
+    ] {
+        let name = format!("{:?}", err);
+        let api_error = ApiError::from(AuthError::from(err));
+        assert_eq!(api_error.status_code(), status, "{}", name);
+    }
+}
+
+/// A finish call without a live ceremony, such as a replayed one, is a bad request.
+#[actix_web::test]
+async fn missing_ceremony_state_is_a_bad_request() {
+    for err in [
+        AuthError::WebAuthnRegistrationNotFound,
+        AuthError::WebAuthnAuthenticationNotFound,
+        AuthError::TotpRegistrationNotFound,
+    ] {
+        let name = format!("{:?}", err);
+        let api_error = ApiError::from(err);
+        assert_eq!(api_error.status_code(), StatusCode::BAD_REQUEST, "{}", name);
+    }
+}
+
+/// A full hashing pool or too many pending passkey logins ask the client to come back shortly
+/// instead of queueing it.
+#[actix_web::test]
+async fn a_busy_server_is_service_unavailable() {
+    for err in [
+        AuthError::PasswordHasherError(PasswordHasherError::Busy),
+        AuthError::SetHsmStoreError(HSMStoreError::Full),
+    ] {
+        let name = format!("{:?}", err);
+        let response = ApiError::from(err).error_response();
+
+        assert_eq!(
+            response.status(),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "{}",
+            name
+        );
+        assert_eq!(
+            response.headers().get(RETRY_AFTER).unwrap(),
+            "1",
+            "{}",
+            name
+        );
+    }
+}
+
+/// Only a trusted proxy's `X-Forwarded-For` is read, and only the entries the trusted proxies
+/// appended; whatever the client put further left is ignored.
+#[actix_web::test]
+async fn client_ip_is_the_address_seen_by_the_trusted_proxies() {
+    let ip = |addr: &str| addr.parse::<IpAddr>().unwrap();
+    let (proxy, inner_proxy) = ("10.0.0.1", "10.0.0.2");
+    let trusted = [ip(proxy), ip(inner_proxy)];
+    let client = |peer: &str, forwarded: Option<&str>| {
+        let mut req = test::TestRequest::default().peer_addr(SocketAddr::new(ip(peer), 40000));
+        if let Some(forwarded) = forwarded {
+            req = req.insert_header(("X-Forwarded-For", forwarded));
+        }
+        client_ip(&req.to_http_request(), &trusted)
+    };
+
+    for (peer, forwarded, expected) in [
+        ("198.51.100.9", Some("203.0.113.7"), "198.51.100.9"),
+        (proxy, None, proxy),
+        (proxy, Some("203.0.113.7"), "203.0.113.7"),
+        (proxy, Some("192.0.2.66, 203.0.113.7"), "203.0.113.7"),
+        (
+            proxy,
+            Some("192.0.2.66, 203.0.113.7, 10.0.0.2"),
+            "203.0.113.7",
+        ),
+        (proxy, Some("203.0.113.7:4321"), "203.0.113.7"),
+        (proxy, Some("192.0.2.66, unknown"), proxy),
+    ] {
+        assert_eq!(
+            client(peer, forwarded),
+            Some(ip(expected)),
+            "{} {:?}",
+            peer,
+            forwarded
+        );
+    }
+}
+
+fn median(timings: impl Iterator<Item = Duration>) -> Duration {
+    let mut timings: Vec<Duration> = timings.collect();
+    timings.sort();
+    timings[timings.len() / 2]
+}
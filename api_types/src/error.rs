@@ -2,5 +2,51 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct ErrorResponse {
+    /// Stable identifier of the error; `message` is for display and may change.
+    #[serde(default)]
+    pub code: ErrorCode,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+    /// Same value as the `X-Request-Id` response header, to correlate with the server logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidRequest,
+    InvalidCredentials,
+    InvalidToken,
+    InvalidRefreshToken,
+    RefreshTokenReused,
+    InsufficientScope,
+    StepUpCredentialRequired,
+    UserNotFound,
+    UsernameTaken,
+    /// The password breaks the server's password policy; `fields` lists each rule.
+    WeakPassword,
+    ChallengeNotFound,
+    TotpNotEnabled,
+    TotpCredentialNotFound,
+    InvalidTotpCode,
+    PasskeyNotFound,
+    PasskeyVerificationFailed,
+    MfaNotEnabled,
+    LastMfaFactor,
+    InvalidRecoveryCode,
+    TooManyAttempts,
+    ServiceBusy,
+    InternalError,
+    /// A code this version of the client does not know yet.
+    #[default]
+    #[serde(other)]
+    Unknown,
 }
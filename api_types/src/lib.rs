@@ -1,6 +1,9 @@
 pub mod error;
 pub mod login;
+pub mod logout;
 pub mod mfa;
+pub mod recovery;
 pub mod signup;
+pub mod token;
 pub mod totp;
 pub mod webauthn;
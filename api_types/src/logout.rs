@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct LogoutRequest {
+    /// Also revokes this refresh token and every token rotated from the same login.
+    pub refresh_token: Option<String>,
+}
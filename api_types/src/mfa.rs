@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct MfaRegistrationResponse {
@@ -6,3 +7,22 @@ pub struct MfaRegistrationResponse {
     pub allowed_methods: Vec<String>,
     pub expires_in: usize,
 }
+
+/// Either the password or a current TOTP code.
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct MfaStepUpRequest {
+    pub password: Option<String>,
+    #[validate(length(
+        min = 6,
+        max = 8,
+        message = "O código deve ter de 6 a 8 dígitos",
+        code = "invalid_code_length"
+    ))]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MfaStepUpResponse {
+    pub access_token: String,
+    pub expires_in: usize,
+}
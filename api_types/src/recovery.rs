@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct RecoveryLoginRequest {
+    #[validate(length(min = 1, message = "Token de verificação obrigatório"))]
+    pub mfa_verification: String,
+    #[validate(length(min = 1, message = "Código de recuperação obrigatório"))]
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RecoveryLoginResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// Plain recovery codes; they are shown only once.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
@@ -24,7 +24,7 @@ pub struct SignupRequest {
 fn validate_password(password: &str) -> Result<(), ValidationError> {
     let has_uppercase = password.chars().any(|c| c.is_uppercase());
     let has_special = password.chars().any(|c| !c.is_alphanumeric());
-    let has_min_length = password.len() >= 8;
+    let has_min_length = password.chars().count() >= 8;
 
     if has_uppercase && has_special && has_min_length {
         Ok(())
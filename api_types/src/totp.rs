@@ -10,11 +10,28 @@ pub struct TotpSetupResponse {
 pub struct TotpVerifyRequest {
     #[validate(length(
         min = 6,
-        max = 6,
-        message = "O código deve ter 6 dígitos",
+        max = 8,
+        message = "O código deve ter de 6 a 8 dígitos",
         code = "name_too_short"
     ))]
     pub code: String,
+    /// Name shown in the authenticator list; defaults to "Autenticador N".
+    #[validate(length(min = 1, max = 64, message = "O nome deve ter de 1 a 64 caracteres"))]
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct TotpLoginRequest {
+    #[validate(length(min = 1, message = "Token de verificação obrigatório"))]
+    pub mfa_verification: String,
+    #[validate(length(
+        min = 6,
+        max = 8,
+        message = "O código deve ter de 6 a 8 dígitos",
+        code = "invalid_code_length"
+    ))]
+    pub code: String,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -22,3 +39,26 @@ pub struct TotpVerifyResponse {
     pub access_token: String,
     pub refresh_token: String,
 }
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TotpRegistrationFinishResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Empty unless this enrolment generated the user's recovery codes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TotpCredentialResponse {
+    pub id: String,
+    pub label: String,
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct TotpRenameRequest {
+    #[validate(length(min = 1, max = 64, message = "O nome deve ter de 1 a 64 caracteres"))]
+    pub label: String,
+}
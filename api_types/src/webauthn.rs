@@ -1 +1,53 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
 
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct WebauthnRegistrationFinishRequest {
+    /// `PublicKeyCredential` returned by `navigator.credentials.create`, serialized as JSON.
+    pub credential: serde_json::Value,
+    /// Name shown in the passkey list; defaults to "Passkey N".
+    #[validate(length(min = 1, max = 64, message = "O nome deve ter de 1 a 64 caracteres"))]
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebauthnAuthenticationFinishRequest {
+    /// `PublicKeyCredential` returned by `navigator.credentials.get`, serialized as JSON.
+    pub credential: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WebauthnAuthenticationFinishResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PasskeyLoginStartResponse {
+    pub login_id: String,
+    /// Options for `navigator.credentials.get`.
+    pub options: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PasskeyLoginFinishRequest {
+    pub login_id: String,
+    /// `PublicKeyCredential` returned by `navigator.credentials.get`, serialized as JSON.
+    pub credential: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PasskeyResponse {
+    pub id: String,
+    pub name: String,
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+    pub aaguid: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate)]
+pub struct PasskeyRenameRequest {
+    #[validate(length(min = 1, max = 64, message = "O nome deve ter de 1 a 64 caracteres"))]
+    pub name: String,
+}
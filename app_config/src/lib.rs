@@ -0,0 +1,279 @@
+use std::{net::IpAddr, path::Path, str::FromStr};
+
+use auth::{
+    AuthConfig, PasswordHashingConfig, PasswordPolicyConfig, ThrottleConfig, TokenConfig,
+    TotpConfig, UserRepositoryConfig, WebauthnConfig,
+};
+use serde::{Deserialize, Serialize};
+
+/// Effective configuration of the api and the console: defaults, then the TOML file, then the
+/// environment.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub tokens: TokenConfig,
+    pub totp: TotpConfig,
+    pub webauthn: WebauthnConfig,
+    pub throttle: ThrottleConfig,
+    pub password_hashing: PasswordHashingConfig,
+    pub password_policy: PasswordPolicyConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind: String,
+    /// Origins allowed by CORS. When unset, the WebAuthn origins, since the UI is served from
+    /// the same origins the passkeys are bound to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cors_origins: Option<Vec<String>>,
+    /// Addresses of the reverse proxies in front of the api. Requests they pass on are throttled
+    /// by the address the proxies append to `X-Forwarded-For`; the header is ignored otherwise.
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:8080".to_string(),
+            cors_origins: None,
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// `memory`, `sqlite:...` or `postgres://...`.
+    pub url: String,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            url: "memory".to_string(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads `path` if given and applies the environment overrides on top of it.
+    pub fn load(path: Option<&Path>) -> std::io::Result<Self> {
+        let mut config = match path {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path).map_err(|err| {
+                    std::io::Error::new(
+                        err.kind(),
+                        format!("Failed to read {}: {}", path.display(), err),
+                    )
+                })?;
+                toml::from_str(&contents).map_err(|err| {
+                    std::io::Error::other(format!("Invalid {}: {}", path.display(), err))
+                })?
+            }
+            None => Config::default(),
+        };
+        config.apply_env()?;
+        Ok(config)
+    }
+
+    fn apply_env(&mut self) -> std::io::Result<()> {
+        env_override("SERVER_BIND", &mut self.server.bind)?;
+        if let Some(origins) = env_list("SERVER_CORS_ORIGINS") {
+            self.server.cors_origins = Some(origins);
+        }
+        if let Some(proxies) = env_list("SERVER_TRUSTED_PROXIES") {
+            self.server.trusted_proxies = proxies
+                .iter()
+                .map(|proxy| proxy.parse())
+                .collect::<Result<_, _>>()
+                .map_err(|err| {
+                    std::io::Error::other(format!("Invalid SERVER_TRUSTED_PROXIES: {}", err))
+                })?;
+        }
+        env_override("DATABASE_URL", &mut self.database.url)?;
+
+        let tokens = &mut self.tokens;
+        env_override("JWT_ISSUER", &mut tokens.issuer)?;
+        env_override("JWT_AUDIENCE", &mut tokens.audience)?;
+        env_override("JWT_KEYS_DIR", &mut tokens.keys_dir)?;
+        env_override("JWT_ACCESS_TTL", &mut tokens.access_ttl)?;
+        env_override("JWT_REFRESH_TTL", &mut tokens.refresh_ttl)?;
+        env_override("JWT_ELEVATED_TTL", &mut tokens.elevated_ttl)?;
+        env_override("JWT_MFA_VERIFICATION_TTL", &mut tokens.mfa_verification_ttl)?;
+        env_override("JWT_MFA_REGISTRATION_TTL", &mut tokens.mfa_registration_ttl)?;
+
+        let totp = &mut self.totp;
+        env_override("TOTP_ISSUER", &mut totp.issuer)?;
+        env_override("TOTP_ALGORITHM", &mut totp.algorithm)?;
+        env_override("TOTP_DIGITS", &mut totp.digits)?;
+        env_override("TOTP_STEP", &mut totp.step)?;
+        env_override("TOTP_SKEW", &mut totp.skew)?;
+
+        let webauthn = &mut self.webauthn;
+        env_override("WEBAUTHN_RP_ID", &mut webauthn.rp_id)?;
+        env_override("WEBAUTHN_RP_NAME", &mut webauthn.rp_name)?;
+        if let Some(origins) = env_list("WEBAUTHN_ORIGINS") {
+            webauthn.origins = origins;
+        }
+        env_override("WEBAUTHN_ATTESTATION", &mut webauthn.attestation)?;
+        if let Some(files) = env_list("WEBAUTHN_ATTESTATION_CA_FILES") {
+            webauthn.attestation_ca_files = files;
+        }
+        env_override(
+            "WEBAUTHN_USER_VERIFICATION",
+            &mut webauthn.user_verification,
+        )?;
+        env_override("WEBAUTHN_TIMEOUT", &mut webauthn.timeout)?;
+        env_override(
+            "WEBAUTHN_MAX_PENDING_LOGINS",
+            &mut webauthn.max_pending_logins,
+        )?;
+
+        let throttle = &mut self.throttle;
+        env_override("THROTTLE_FREE_ATTEMPTS", &mut throttle.free_attempts)?;
+        env_override("THROTTLE_BASE_DELAY", &mut throttle.base_delay)?;
+        env_override("THROTTLE_MAX_DELAY", &mut throttle.max_delay)?;
+        env_override(
+            "THROTTLE_LOCKOUT_THRESHOLD",
+            &mut throttle.lockout_threshold,
+        )?;
+        env_override("THROTTLE_LOCKOUT_DURATION", &mut throttle.lockout_duration)?;
+        env_override("THROTTLE_IP_ALLOWANCE", &mut throttle.ip_allowance)?;
+
+        let password_hashing = &mut self.password_hashing;
+        env_override(
+            "PASSWORD_HASHING_ALGORITHM",
+            &mut password_hashing.algorithm,
+        )?;
+        env_override(
+            "PASSWORD_HASHING_ARGON2_MEMORY",
+            &mut password_hashing.argon2_memory,
+        )?;
+        env_override(
+            "PASSWORD_HASHING_ARGON2_ITERATIONS",
+            &mut password_hashing.argon2_iterations,
+        )?;
+        env_override(
+            "PASSWORD_HASHING_ARGON2_PARALLELISM",
+            &mut password_hashing.argon2_parallelism,
+        )?;
+        env_override(
+            "PASSWORD_HASHING_BCRYPT_COST",
+            &mut password_hashing.bcrypt_cost,
+        )?;
+        env_override(
+            "PASSWORD_HASHING_LEGACY_BCRYPT_HASHES",
+            &mut password_hashing.legacy_bcrypt_hashes,
+        )?;
+        env_override("PASSWORD_HASHING_WORKERS", &mut password_hashing.workers)?;
+        env_override(
+            "PASSWORD_HASHING_QUEUE_LIMIT",
+            &mut password_hashing.queue_limit,
+        )?;
+
+        let password_policy = &mut self.password_policy;
+        env_override(
+            "PASSWORD_POLICY_MIN_LENGTH",
+            &mut password_policy.min_length,
+        )?;
+        env_override(
+            "PASSWORD_POLICY_MAX_LENGTH",
+            &mut password_policy.max_length,
+        )?;
+        env_override(
+            "PASSWORD_POLICY_REQUIRE_UPPERCASE",
+            &mut password_policy.require_uppercase,
+        )?;
+        env_override(
+            "PASSWORD_POLICY_REQUIRE_LOWERCASE",
+            &mut password_policy.require_lowercase,
+        )?;
+        env_override(
+            "PASSWORD_POLICY_REQUIRE_DIGIT",
+            &mut password_policy.require_digit,
+        )?;
+        env_override(
+            "PASSWORD_POLICY_REQUIRE_SPECIAL",
+            &mut password_policy.require_special,
+        )?;
+        env_override(
+            "PASSWORD_POLICY_REJECT_PERSONAL_INFO",
+            &mut password_policy.reject_personal_info,
+        )?;
+        if let Ok(path) = std::env::var("PASSWORD_POLICY_BREACHED_PASSWORDS_FILE") {
+            password_policy.breached_passwords_file = Some(path).filter(|path| !path.is_empty());
+        }
+
+        Ok(())
+    }
+
+    pub fn cors_origins(&self) -> &[String] {
+        self.server
+            .cors_origins
+            .as_deref()
+            .unwrap_or(&self.webauthn.origins)
+    }
+
+    pub fn auth_config(&self) -> std::io::Result<AuthConfig> {
+        Ok(AuthConfig {
+            user_repository: UserRepositoryConfig::from_url(&self.database.url)
+                .map_err(std::io::Error::other)?,
+            tokens: self.tokens.clone(),
+            totp: self.totp.clone(),
+            webauthn: self.webauthn.clone(),
+            throttle: self.throttle.clone(),
+            password_hashing: self.password_hashing.clone(),
+            password_policy: self.password_policy.clone(),
+        })
+    }
+
+    /// The configuration as TOML, with the database password masked.
+    pub fn dump(&self) -> std::io::Result<String> {
+        let mut config = self.clone();
+        config.database.url = redact_password(&config.database.url);
+        toml::to_string_pretty(&config).map_err(std::io::Error::other)
+    }
+}
+
+/// Replaces `value` with the parsed variable `name`, when it is set.
+fn env_override<T>(name: &str, value: &mut T) -> std::io::Result<()>
+where
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    if let Ok(raw) = std::env::var(name) {
+        *value = raw
+            .parse()
+            .map_err(|err| std::io::Error::other(format!("Invalid {}: {}", name, err)))?;
+    }
+    Ok(())
+}
+
+/// Comma separated variable `name`, without empty items.
+fn env_list(name: &str) -> Option<Vec<String>> {
+    std::env::var(name).ok().map(|list| {
+        list.split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+fn redact_password(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let authority_end = rest.find('/').unwrap_or(rest.len());
+    let Some(at) = rest[..authority_end].rfind('@') else {
+        return url.to_string();
+    };
+    match rest[..at].split_once(':') {
+        Some((user, _)) => format!("{}://{}:***{}", scheme, user, &rest[at..]),
+        None => url.to_string(),
+    }
+}
@@ -0,0 +1,173 @@
+//! Throughput of `/login` password checks under concurrency, on a single-threaded executor like
+//! each actix worker, with bcrypt run inline on the executor (as before the `PasswordHasher`
+//! port) and on the blocking pool. While the logins run, a probe standing in for any other
+//! request sleeps in a loop and records how late it wakes up.
+//!
+//! ```sh
+//! cargo bench -p auth --bench login_throughput
+//! ```
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use auth::{
+    AuthComponent, AuthConfig, PasswordAlgorithm, PasswordHashingConfig, ThrottleConfig,
+    ports::password_hasher::PasswordHasher,
+};
+use contracts::auth::{
+    Component,
+    error::{AuthError, PasswordHasherError},
+    login::LoginInput,
+    signup::SignupInput,
+};
+
+const CONCURRENCY: usize = 16;
+const LOGINS: usize = 32;
+const PROBE_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The previous behaviour: bcrypt called directly from the async use case.
+struct InlineBcrypt;
+
+#[async_trait]
+impl PasswordHasher for InlineBcrypt {
+    async fn hash(&self, password: String) -> Result<String, PasswordHasherError> {
+        bcrypt::hash(password, bcrypt::DEFAULT_COST)
+            .map_err(|err| PasswordHasherError::HashingFailed(err.to_string()))
+    }
+
+    async fn verify(&self, password: String, hash: String) -> Result<bool, PasswordHasherError> {
+        bcrypt::verify(password, &hash)
+            .map_err(|err| PasswordHasherError::HashingFailed(err.to_string()))
+    }
+
+    fn needs_rehash(&self, _hash: &str) -> bool {
+        false
+    }
+
+    async fn dummy_hashes(&self) -> Result<Vec<String>, PasswordHasherError> {
+        Ok(vec![self.hash("dummy password".to_string()).await?])
+    }
+
+    fn same_algorithm(&self, _a: &str, _b: &str) -> bool {
+        true
+    }
+}
+
+struct Report {
+    elapsed: Duration,
+    probe_delays: Vec<Duration>,
+}
+
+fn main() {
+    let variants: [(&str, Option<Arc<dyn PasswordHasher>>); 2] = [
+        ("inline bcrypt", Some(Arc::new(InlineBcrypt))),
+        ("blocking pool", None),
+    ];
+    println!(
+        "{} wrong-password logins, {} at a time, bcrypt cost {}, {} pool workers",
+        LOGINS,
+        CONCURRENCY,
+        bcrypt::DEFAULT_COST,
+        PasswordHashingConfig::default().workers
+    );
+    println!(
+        "{:<14} {:>10} {:>12} {:>12}",
+        "", "logins/s", "probe p50", "probe max"
+    );
+
+    for (name, password_hasher) in variants {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let mut report = runtime.block_on(run(password_hasher));
+        report.probe_delays.sort();
+        println!(
+            "{:<14} {:>10.2} {:>12.1?} {:>12.1?}",
+            name,
+            LOGINS as f64 / report.elapsed.as_secs_f64(),
+            report.probe_delays[report.probe_delays.len() / 2],
+            report.probe_delays.last().unwrap()
+        );
+    }
+}
+
+async fn run(password_hasher: Option<Arc<dyn PasswordHasher>>) -> Report {
+    let mut builder = AuthComponent::builder(AuthConfig {
+        throttle: ThrottleConfig {
+            free_attempts: u32::MAX,
+            lockout_threshold: u32::MAX,
+            ..ThrottleConfig::default()
+        },
+        // Same algorithm and cost on both sides, so only where the hashing runs differs.
+        password_hashing: PasswordHashingConfig {
+            algorithm: PasswordAlgorithm::Bcrypt,
+            queue_limit: LOGINS,
+            ..PasswordHashingConfig::default()
+        },
+        ..AuthConfig::default()
+    });
+    if let Some(password_hasher) = password_hasher {
+        builder = builder.password_hasher(password_hasher);
+    }
+    let auth = Arc::new(builder.build().await.unwrap());
+    auth.signup(SignupInput {
+        name: "Ana".to_string(),
+        username: "ana@exemplo.com".to_string(),
+        password: "Password123!".to_string(),
+    })
+    .await
+    .unwrap();
+
+    let done = Arc::new(AtomicBool::new(false));
+    let probe = tokio::spawn({
+        let done = done.clone();
+        async move {
+            let mut delays = Vec::new();
+            while !done.load(Ordering::Relaxed) {
+                let started = Instant::now();
+                tokio::time::sleep(PROBE_INTERVAL).await;
+                delays.push(started.elapsed().saturating_sub(PROBE_INTERVAL));
+            }
+            delays
+        }
+    });
+
+    let started = Instant::now();
+    let next = Arc::new(AtomicUsize::new(0));
+    let clients: Vec<_> = (0..CONCURRENCY)
+        .map(|_| {
+            let auth = auth.clone();
+            let next = next.clone();
+            tokio::spawn(async move {
+                while next.fetch_add(1, Ordering::Relaxed) < LOGINS {
+                    // A wrong password costs the same bcrypt check without needing signing keys.
+                    let result = auth
+                        .login(LoginInput {
+                            username: "ana@exemplo.com".to_string(),
+                            password: "WrongPassword1!".to_string(),
+                            client_ip: None,
+                        })
+                        .await;
+                    assert!(matches!(result, Err(AuthError::InvalidUsernameOrPassword)));
+                }
+            })
+        })
+        .collect();
+    for client in clients {
+        client.await.unwrap();
+    }
+    let elapsed = started.elapsed();
+
+    done.store(true, Ordering::Relaxed);
+    Report {
+        elapsed,
+        probe_delays: probe.await.unwrap(),
+    }
+}
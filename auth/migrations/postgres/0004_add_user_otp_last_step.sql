@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS otp_last_step BIGINT;
@@ -0,0 +1,15 @@
+ALTER TABLE users ADD COLUMN IF NOT EXISTS totp_credentials JSONB NOT NULL DEFAULT '[]'::jsonb;
+
+UPDATE users
+SET totp_credentials = jsonb_build_array(jsonb_build_object(
+    'id', gen_random_uuid(),
+    'label', 'TOTP',
+    'secret', otp_secret,
+    'created_at', extract(epoch FROM now())::bigint,
+    'last_used_at', NULL,
+    'last_step', otp_last_step
+))
+WHERE otp_secret IS NOT NULL;
+
+ALTER TABLE users DROP COLUMN IF EXISTS otp_last_step;
+ALTER TABLE users DROP COLUMN IF EXISTS otp_secret;
@@ -0,0 +1,13 @@
+UPDATE users
+SET pass_keys = (
+    SELECT jsonb_agg(jsonb_build_object(
+        'id', gen_random_uuid(),
+        'name', 'Passkey',
+        'created_at', extract(epoch FROM now())::bigint,
+        'last_used_at', NULL,
+        'aaguid', NULL,
+        'passkey', value
+    ))
+    FROM jsonb_array_elements(users.pass_keys)
+)
+WHERE pass_keys <> '[]'::jsonb;
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS failed_attempts (
+    attempt_key TEXT PRIMARY KEY,
+    failures INTEGER NOT NULL,
+    last_failure_at BIGINT NOT NULL,
+    locked_until BIGINT,
+    expires_at BIGINT NOT NULL
+);
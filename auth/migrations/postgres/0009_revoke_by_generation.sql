@@ -0,0 +1,5 @@
+ALTER TABLE user_token_revocations ADD COLUMN generation BIGINT NOT NULL DEFAULT 0;
+
+UPDATE user_token_revocations SET generation = 1;
+
+ALTER TABLE user_token_revocations DROP COLUMN issued_before;
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN otp_last_step INTEGER;
@@ -0,0 +1,15 @@
+ALTER TABLE users ADD COLUMN totp_credentials TEXT NOT NULL DEFAULT '[]';
+
+UPDATE users
+SET totp_credentials = json_array(json_object(
+    'id', lower(hex(randomblob(16))),
+    'label', 'TOTP',
+    'secret', otp_secret,
+    'created_at', CAST(strftime('%s', 'now') AS INTEGER),
+    'last_used_at', NULL,
+    'last_step', otp_last_step
+))
+WHERE otp_secret IS NOT NULL;
+
+ALTER TABLE users DROP COLUMN otp_last_step;
+ALTER TABLE users DROP COLUMN otp_secret;
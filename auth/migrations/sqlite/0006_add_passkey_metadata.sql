@@ -0,0 +1,13 @@
+UPDATE users
+SET pass_keys = (
+    SELECT json_group_array(json_object(
+        'id', lower(hex(randomblob(16))),
+        'name', 'Passkey',
+        'created_at', CAST(strftime('%s', 'now') AS INTEGER),
+        'last_used_at', NULL,
+        'aaguid', NULL,
+        'passkey', json(value)
+    ))
+    FROM json_each(users.pass_keys)
+)
+WHERE pass_keys <> '[]';
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS failed_attempts (
+    attempt_key TEXT PRIMARY KEY NOT NULL,
+    failures INTEGER NOT NULL,
+    last_failure_at INTEGER NOT NULL,
+    locked_until INTEGER,
+    expires_at INTEGER NOT NULL
+);
@@ -1,73 +1,344 @@
+#[cfg(feature = "postgres")]
+use crate::infra::postgres::{
+    self, attempt_store::PostgresAttemptStore, refresh_token_store::PostgresRefreshTokenStore,
+    token_revocation_store::PostgresTokenRevocationStore, user_repository::PostgresUserRepository,
+};
+#[cfg(feature = "sqlite")]
+use crate::infra::sqlite::{
+    self, attempt_store::SqliteAttemptStore, refresh_token_store::SqliteRefreshTokenStore,
+    token_revocation_store::SqliteTokenRevocationStore, user_repository::SqliteUserRepository,
+};
 use crate::{
+    config::{AuthConfig, ConfigError, UserRepositoryConfig},
     infra::{
+        breached_passwords::FileBreachedPasswords,
         jwt_auth_tokens::JwtAuthTokens,
-        memory::{hsm_store::MemoryHsmStore, user_repository},
+        memory::{
+            attempt_store::MemoryAttemptStore, hsm_store::MemoryHsmStore,
+            refresh_token_store::MemoryRefreshTokenStore,
+            token_revocation_store::MemoryTokenRevocationStore, user_repository,
+        },
+        password_hasher::PhcPasswordHasher,
         totp::Totp,
+        webauthn_passkeys::WebauthnPasskeys,
+    },
+    ports::{
+        attempt_store::AttemptStore, breached_passwords::BreachedPasswords,
+        for_auth_tokens::ForAuthTokens, for_passkeys::ForPasskeys, for_totp::ForTotp,
+        hsm_store::HSMStore, password_hasher::PasswordHasher,
+        refresh_token_store::RefreshTokenStore, token_revocation_store::TokenRevocationStore,
+        user_repository::UserRepository,
     },
-    ports::user_repository::UserRepository,
     usecases::{
+        attempt_limiter::AttemptLimiter,
         finish_passkey_authentication::FinishPasskeyAuthenticationUseCase,
+        finish_passkey_login::FinishPasskeyLoginUseCase,
         finish_passkey_registration::FinishPasskeyRegistrationUseCase,
         finish_totp_registration::FinishTOTPRegistrationUseCase, get_jwks::GetJwksUseCase,
-        get_mfa_registration::GetMfaRegistrationUseCase, login::LoginUseCase,
-        signup::SignupUseCase,
-        start_passkey_authentication::StartPasskeyAuthenticationUseCase,
+        get_mfa_registration::GetMfaRegistrationUseCase, list_passkeys::ListPasskeysUseCase,
+        list_totp_credentials::ListTotpCredentialsUseCase, login::LoginUseCase,
+        logout::LogoutUseCase, logout_all::LogoutAllUseCase, password_policy::PasswordPolicy,
+        refresh_token::RefreshTokenUseCase,
+        regenerate_recovery_codes::RegenerateRecoveryCodesUseCase,
+        remove_passkey::RemovePasskeyUseCase, remove_totp::RemoveTotpUseCase,
+        rename_passkey::RenamePasskeyUseCase, rename_totp_credential::RenameTotpCredentialUseCase,
+        rotate_signing_keys::RotateSigningKeysUseCase, session_tokens::SessionTokenIssuer,
+        signup::SignupUseCase, start_passkey_authentication::StartPasskeyAuthenticationUseCase,
+        start_passkey_login::StartPasskeyLoginUseCase,
         start_passkey_registration::StartPasskeyRegistrationUseCase,
-        start_totp_registration::StartTOTPRegistrationUseCase,
+        start_totp_registration::StartTOTPRegistrationUseCase, step_up_mfa::StepUpMfaUseCase,
+        totp_verifier::TotpVerifier, unlock_attempts::UnlockAttemptsUseCase,
+        validate_token::ValidateTokenUseCase, verify_recovery_code::VerifyRecoveryCodeUseCase,
+        verify_totp_login::VerifyTotpLoginUseCase,
     },
 };
 use contracts::{
+    self,
+    auth::{
         self,
-        auth::{
-            self,
-            error::AuthError,
-            login::{LoginInput, LoginOutput},
-            mfa::{MfaRegistrationInput, MfaRegistrationOutput},
-            passkey::{
-                PasskeyFinishAuthenticationInput, PasskeyFinishRegistrationInput,
-                PasskeyStartAuthenticationInput, PasskeyStartAuthenticationOutput,
-                PasskeyStartRegistrationInput, PasskeyStartRegistrationOutput,
+        error::AuthError,
+        login::{LoginInput, LoginOutput},
+        logout::{LogoutAllInput, LogoutInput},
+        mfa::{
+            MfaRegistrationInput, MfaRegistrationOutput, MfaRemoveTotpInput, MfaStepUpInput,
+            MfaStepUpOutput,
+        },
+        passkey::{
+            PasskeyFinishAuthenticationInput, PasskeyFinishAuthenticationOutput,
+            PasskeyFinishLoginInput, PasskeyFinishRegistrationInput,
+            PasskeyFinishRegistrationOutput, PasskeyListInput, PasskeyOutput, PasskeyRemoveInput,
+            PasskeyRenameInput, PasskeyStartAuthenticationInput, PasskeyStartAuthenticationOutput,
+            PasskeyStartLoginOutput, PasskeyStartRegistrationInput, PasskeyStartRegistrationOutput,
+        },
+        recovery::{
+            RecoveryCodesOutput, RecoveryRegenerateCodesInput, RecoveryVerifyLoginInput,
+            RecoveryVerifyLoginOutput,
         },
         signup::{SignupInput, SignupOutput},
+        throttle::UnlockAttemptsInput,
+        token::{
+            RefreshTokenInput, RefreshTokenOutput, RotateSigningKeysOutput, ValidateTokenInput,
+            ValidateTokenOutput,
+        },
         totp::{
-            TOTPFinishRegistrationInput, TOTPFinishRegistrationOutput, TOTPStartRegistrationInput,
-            TOTPStartRegistrationOutput,
+            TOTPCredentialOutput, TOTPFinishRegistrationInput, TOTPFinishRegistrationOutput,
+            TOTPListCredentialsInput, TOTPRenameCredentialInput, TOTPStartRegistrationInput,
+            TOTPStartRegistrationOutput, TOTPVerifyLoginInput, TOTPVerifyLoginOutput,
         },
     },
 };
 use std::sync::Arc;
-use webauthn_rs::{WebauthnBuilder, prelude::Url};
 
 pub struct AuthComponent {
     login_usecase: Arc<LoginUseCase>,
     signup_usecase: Arc<SignupUseCase>,
+    logout_usecase: Arc<LogoutUseCase>,
+    logout_all_usecase: Arc<LogoutAllUseCase>,
+    refresh_token_usecase: Arc<RefreshTokenUseCase>,
     start_totp_registration_usecase: Arc<StartTOTPRegistrationUseCase>,
     finish_totp_registration_usecase: Arc<FinishTOTPRegistrationUseCase>,
+    verify_totp_login_usecase: Arc<VerifyTotpLoginUseCase>,
+    list_totp_credentials_usecase: Arc<ListTotpCredentialsUseCase>,
+    rename_totp_credential_usecase: Arc<RenameTotpCredentialUseCase>,
     start_passkey_registration_usecase: Arc<StartPasskeyRegistrationUseCase>,
     finish_passkey_registration_usecase: Arc<FinishPasskeyRegistrationUseCase>,
     start_passkey_authentication_usecase: Arc<StartPasskeyAuthenticationUseCase>,
     finish_passkey_authentication_usecase: Arc<FinishPasskeyAuthenticationUseCase>,
+    start_passkey_login_usecase: Arc<StartPasskeyLoginUseCase>,
+    finish_passkey_login_usecase: Arc<FinishPasskeyLoginUseCase>,
+    list_passkeys_usecase: Arc<ListPasskeysUseCase>,
+    rename_passkey_usecase: Arc<RenamePasskeyUseCase>,
+    remove_passkey_usecase: Arc<RemovePasskeyUseCase>,
+    verify_recovery_code_usecase: Arc<VerifyRecoveryCodeUseCase>,
+    regenerate_recovery_codes_usecase: Arc<RegenerateRecoveryCodesUseCase>,
+    step_up_mfa_usecase: Arc<StepUpMfaUseCase>,
+    remove_totp_usecase: Arc<RemoveTotpUseCase>,
     get_mfa_registration_usecase: Arc<GetMfaRegistrationUseCase>,
+    validate_token_usecase: Arc<ValidateTokenUseCase>,
     get_jwks_usecase: Arc<GetJwksUseCase>,
+    rotate_signing_keys_usecase: Arc<RotateSigningKeysUseCase>,
+    unlock_attempts_usecase: Arc<UnlockAttemptsUseCase>,
 }
 
 impl AuthComponent {
-    pub fn new() -> impl auth::Component {
-        let user_repository: Arc<dyn UserRepository> =
-            Arc::new(user_repository::MemoryUserRepository::new());
-        let jwt_auth = Arc::new(JwtAuthTokens {});
-        let totp = Arc::new(Totp {});
-        let hsm_store = Arc::new(MemoryHsmStore::new());
-        let webauthn = Arc::new(
-            WebauthnBuilder::new("localhost", &Url::parse("http://localhost:3000").unwrap())
-                .unwrap()
-                .build()
-                .unwrap(),
-        );
-
-        AuthComponent {
-            login_usecase: Arc::new(LoginUseCase::new(user_repository.clone(), jwt_auth.clone())),
-            signup_usecase: Arc::new(SignupUseCase::new(user_repository.clone())),
+    pub fn builder(config: AuthConfig) -> AuthComponentBuilder {
+        AuthComponentBuilder::new(config)
+    }
+}
+
+/// Validates the configuration and wires the adapters and use cases of an [`AuthComponent`].
+///
+/// Every port defaults to the adapter selected by the configuration; the setters replace it with
+/// a custom implementation. The stores from `user_repository` in the configuration are only
+/// connected when the user repository or one of the token revocation, refresh token and attempt
+/// stores is not replaced.
+pub struct AuthComponentBuilder {
+    config: AuthConfig,
+    user_repository: Option<Arc<dyn UserRepository>>,
+    token_revocation_store: Option<Arc<dyn TokenRevocationStore>>,
+    refresh_token_store: Option<Arc<dyn RefreshTokenStore>>,
+    auth_tokens: Option<Arc<dyn ForAuthTokens>>,
+    totp: Option<Arc<dyn ForTotp>>,
+    passkeys: Option<Arc<dyn ForPasskeys>>,
+    hsm_store: Option<Arc<dyn HSMStore>>,
+    attempt_store: Option<Arc<dyn AttemptStore>>,
+    password_hasher: Option<Arc<dyn PasswordHasher>>,
+    breached_passwords: Option<Arc<dyn BreachedPasswords>>,
+}
+
+impl AuthComponentBuilder {
+    pub fn new(config: AuthConfig) -> Self {
+        Self {
+            config,
+            user_repository: None,
+            token_revocation_store: None,
+            refresh_token_store: None,
+            auth_tokens: None,
+            totp: None,
+            passkeys: None,
+            hsm_store: None,
+            attempt_store: None,
+            password_hasher: None,
+            breached_passwords: None,
+        }
+    }
+
+    pub fn user_repository(mut self, user_repository: Arc<dyn UserRepository>) -> Self {
+        self.user_repository = Some(user_repository);
+        self
+    }
+
+    pub fn token_revocation_store(
+        mut self,
+        token_revocation_store: Arc<dyn TokenRevocationStore>,
+    ) -> Self {
+        self.token_revocation_store = Some(token_revocation_store);
+        self
+    }
+
+    pub fn refresh_token_store(mut self, refresh_token_store: Arc<dyn RefreshTokenStore>) -> Self {
+        self.refresh_token_store = Some(refresh_token_store);
+        self
+    }
+
+    /// Replaces the JWT adapter; the issuer, audience and keys directory of the configuration
+    /// are then unused.
+    pub fn auth_tokens(mut self, auth_tokens: Arc<dyn ForAuthTokens>) -> Self {
+        self.auth_tokens = Some(auth_tokens);
+        self
+    }
+
+    /// Replaces the TOTP adapter; the TOTP configuration is then unused.
+    pub fn totp(mut self, totp: Arc<dyn ForTotp>) -> Self {
+        self.totp = Some(totp);
+        self
+    }
+
+    /// Replaces the WebAuthn adapter; the WebAuthn configuration is then unused.
+    pub fn passkeys(mut self, passkeys: Arc<dyn ForPasskeys>) -> Self {
+        self.passkeys = Some(passkeys);
+        self
+    }
+
+    pub fn hsm_store(mut self, hsm_store: Arc<dyn HSMStore>) -> Self {
+        self.hsm_store = Some(hsm_store);
+        self
+    }
+
+    pub fn attempt_store(mut self, attempt_store: Arc<dyn AttemptStore>) -> Self {
+        self.attempt_store = Some(attempt_store);
+        self
+    }
+
+    /// Replaces the Argon2id/bcrypt adapter; the password hashing configuration is then unused.
+    pub fn password_hasher(mut self, password_hasher: Arc<dyn PasswordHasher>) -> Self {
+        self.password_hasher = Some(password_hasher);
+        self
+    }
+
+    /// Replaces the list read from `breached_passwords_file` of the password policy.
+    pub fn breached_passwords(mut self, breached_passwords: Arc<dyn BreachedPasswords>) -> Self {
+        self.breached_passwords = Some(breached_passwords);
+        self
+    }
+
+    pub async fn build(self) -> Result<AuthComponent, ConfigError> {
+        let config = self.config;
+        config.tokens.validate()?;
+        config.throttle.validate()?;
+        config.password_policy.validate()?;
+        let max_token_lifetime = config.tokens.max_ttl();
+
+        let (user_repository, token_revocation_store, refresh_token_store, attempt_store) = match (
+            self.user_repository,
+            self.token_revocation_store,
+            self.refresh_token_store,
+            self.attempt_store,
+        ) {
+            (
+                Some(user_repository),
+                Some(token_revocation_store),
+                Some(refresh_token_store),
+                Some(attempt_store),
+            ) => (
+                user_repository,
+                token_revocation_store,
+                refresh_token_store,
+                attempt_store,
+            ),
+            (user_repository, token_revocation_store, refresh_token_store, attempt_store) => {
+                let stores = connect_stores(config.user_repository).await?;
+                (
+                    user_repository.unwrap_or(stores.user_repository),
+                    token_revocation_store.unwrap_or(stores.token_revocation_store),
+                    refresh_token_store.unwrap_or(stores.refresh_token_store),
+                    attempt_store.unwrap_or(stores.attempt_store),
+                )
+            }
+        };
+        let jwt_auth = match self.auth_tokens {
+            Some(auth_tokens) => auth_tokens,
+            None => Arc::new(JwtAuthTokens::new(
+                config.tokens.issuer,
+                config.tokens.audience,
+                config.tokens.keys_dir,
+                token_revocation_store.clone(),
+            )),
+        };
+        let totp = match self.totp {
+            Some(totp) => totp,
+            None => Arc::new(Totp::new(config.totp)?),
+        };
+        let totp_verifier = Arc::new(TotpVerifier::new(totp.clone(), user_repository.clone()));
+        let password_hasher = match self.password_hasher {
+            Some(password_hasher) => password_hasher,
+            None => Arc::new(PhcPasswordHasher::new(config.password_hashing)?),
+        };
+        let breached_passwords =
+            match (
+                self.breached_passwords,
+                &config.password_policy.breached_passwords_file,
+            ) {
+                (Some(breached_passwords), _) => Some(breached_passwords),
+                (None, Some(path)) => Some(Arc::new(FileBreachedPasswords::load(path).await?)
+                    as Arc<dyn BreachedPasswords>),
+                (None, None) => None,
+            };
+        let password_policy = Arc::new(PasswordPolicy::new(
+            config.password_policy,
+            breached_passwords,
+        ));
+        let hsm_store = self.hsm_store.unwrap_or_else(|| {
+            Arc::new(MemoryHsmStore::with_max_expiring(
+                config.webauthn.max_pending_logins,
+            ))
+        });
+
+        let session_token_issuer = Arc::new(SessionTokenIssuer::new(
+            jwt_auth.clone(),
+            refresh_token_store.clone(),
+            config.tokens.access_ttl,
+            config.tokens.refresh_ttl,
+        ));
+        let attempt_limiter = Arc::new(AttemptLimiter::new(attempt_store.clone(), config.throttle));
+        let passkeys = match self.passkeys {
+            Some(passkeys) => passkeys,
+            None => Arc::new(WebauthnPasskeys::new(&config.webauthn).await?),
+        };
+
+        Ok(AuthComponent {
+            login_usecase: Arc::new(
+                LoginUseCase::new(
+                    user_repository.clone(),
+                    jwt_auth.clone(),
+                    password_hasher.clone(),
+                    session_token_issuer.clone(),
+                    attempt_limiter.clone(),
+                    config.tokens.mfa_verification_ttl,
+                )
+                .await
+                .map_err(|err| ConfigError::InvalidPasswordHashing(err.to_string()))?,
+            ),
+            signup_usecase: Arc::new(SignupUseCase::new(
+                user_repository.clone(),
+                password_hasher.clone(),
+                password_policy,
+            )),
+            logout_usecase: Arc::new(LogoutUseCase::new(
+                jwt_auth.clone(),
+                token_revocation_store.clone(),
+                refresh_token_store.clone(),
+            )),
+            logout_all_usecase: Arc::new(LogoutAllUseCase::new(
+                jwt_auth.clone(),
+                token_revocation_store,
+            )),
+            refresh_token_usecase: Arc::new(RefreshTokenUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+                refresh_token_store,
+                session_token_issuer.clone(),
+            )),
             start_totp_registration_usecase: Arc::new(StartTOTPRegistrationUseCase::new(
                 user_repository.clone(),
                 jwt_auth.clone(),
@@ -79,38 +350,153 @@ impl AuthComponent {
                 jwt_auth.clone(),
                 totp.clone(),
                 hsm_store.clone(),
+                session_token_issuer.clone(),
+                attempt_limiter.clone(),
+            )),
+            verify_totp_login_usecase: Arc::new(VerifyTotpLoginUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+                totp_verifier.clone(),
+                session_token_issuer.clone(),
+                attempt_limiter.clone(),
+            )),
+            list_totp_credentials_usecase: Arc::new(ListTotpCredentialsUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+            )),
+            rename_totp_credential_usecase: Arc::new(RenameTotpCredentialUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
             )),
             start_passkey_registration_usecase: Arc::new(StartPasskeyRegistrationUseCase::new(
                 user_repository.clone(),
-                webauthn.clone(),
+                passkeys.clone(),
                 hsm_store.clone(),
             )),
             finish_passkey_registration_usecase: Arc::new(FinishPasskeyRegistrationUseCase::new(
                 user_repository.clone(),
-                webauthn.clone(),
+                passkeys.clone(),
                 hsm_store.clone(),
             )),
             start_passkey_authentication_usecase: Arc::new(StartPasskeyAuthenticationUseCase::new(
                 user_repository.clone(),
                 hsm_store.clone(),
-                webauthn.clone(),
+                passkeys.clone(),
             )),
             finish_passkey_authentication_usecase: Arc::new(
                 FinishPasskeyAuthenticationUseCase::new(
                     user_repository.clone(),
-                    hsm_store,
-                    webauthn,
+                    hsm_store.clone(),
+                    passkeys.clone(),
+                    session_token_issuer.clone(),
                 ),
             ),
+            start_passkey_login_usecase: Arc::new(StartPasskeyLoginUseCase::new(
+                hsm_store.clone(),
+                passkeys.clone(),
+            )),
+            finish_passkey_login_usecase: Arc::new(FinishPasskeyLoginUseCase::new(
+                user_repository.clone(),
+                hsm_store,
+                passkeys,
+                session_token_issuer.clone(),
+            )),
+            list_passkeys_usecase: Arc::new(ListPasskeysUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+            )),
+            rename_passkey_usecase: Arc::new(RenamePasskeyUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+            )),
+            remove_passkey_usecase: Arc::new(RemovePasskeyUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+            )),
+            verify_recovery_code_usecase: Arc::new(VerifyRecoveryCodeUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+                session_token_issuer,
+                attempt_limiter.clone(),
+            )),
+            regenerate_recovery_codes_usecase: Arc::new(RegenerateRecoveryCodesUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+            )),
+            step_up_mfa_usecase: Arc::new(StepUpMfaUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+                password_hasher,
+                totp_verifier,
+                attempt_limiter,
+                config.tokens.elevated_ttl,
+            )),
+            remove_totp_usecase: Arc::new(RemoveTotpUseCase::new(
+                user_repository.clone(),
+                jwt_auth.clone(),
+            )),
             get_mfa_registration_usecase: Arc::new(GetMfaRegistrationUseCase::new(
                 user_repository.clone(),
                 jwt_auth.clone(),
+                config.tokens.mfa_registration_ttl,
             )),
-            get_jwks_usecase: Arc::new(GetJwksUseCase::new(jwt_auth)),
-        }
+            validate_token_usecase: Arc::new(ValidateTokenUseCase::new(jwt_auth.clone())),
+            get_jwks_usecase: Arc::new(GetJwksUseCase::new(jwt_auth.clone())),
+            rotate_signing_keys_usecase: Arc::new(RotateSigningKeysUseCase::new(
+                jwt_auth,
+                max_token_lifetime,
+            )),
+            unlock_attempts_usecase: Arc::new(UnlockAttemptsUseCase::new(
+                user_repository,
+                attempt_store,
+            )),
+        })
     }
 }
 
+/// Stores kept in the database selected by `user_repository` in the configuration.
+struct Stores {
+    user_repository: Arc<dyn UserRepository>,
+    token_revocation_store: Arc<dyn TokenRevocationStore>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    attempt_store: Arc<dyn AttemptStore>,
+}
+
+async fn connect_stores(config: UserRepositoryConfig) -> Result<Stores, ConfigError> {
+    Ok(match config {
+        UserRepositoryConfig::Memory => Stores {
+            user_repository: Arc::new(user_repository::MemoryUserRepository::new()),
+            token_revocation_store: Arc::new(MemoryTokenRevocationStore::new()),
+            refresh_token_store: Arc::new(MemoryRefreshTokenStore::new()),
+            attempt_store: Arc::new(MemoryAttemptStore::new()),
+        },
+        #[cfg(feature = "sqlite")]
+        UserRepositoryConfig::Sqlite { url } => {
+            let pool = sqlite::connect(&url)
+                .await
+                .map_err(ConfigError::UserRepository)?;
+            Stores {
+                user_repository: Arc::new(SqliteUserRepository::new(pool.clone())),
+                token_revocation_store: Arc::new(SqliteTokenRevocationStore::new(pool.clone())),
+                refresh_token_store: Arc::new(SqliteRefreshTokenStore::new(pool.clone())),
+                attempt_store: Arc::new(SqliteAttemptStore::new(pool)),
+            }
+        }
+        #[cfg(feature = "postgres")]
+        UserRepositoryConfig::Postgres { url } => {
+            let pool = postgres::connect(&url)
+                .await
+                .map_err(ConfigError::UserRepository)?;
+            Stores {
+                user_repository: Arc::new(PostgresUserRepository::new(pool.clone())),
+                token_revocation_store: Arc::new(PostgresTokenRevocationStore::new(pool.clone())),
+                refresh_token_store: Arc::new(PostgresRefreshTokenStore::new(pool.clone())),
+                attempt_store: Arc::new(PostgresAttemptStore::new(pool)),
+            }
+        }
+    })
+}
+
 #[async_trait::async_trait]
 impl auth::Component for AuthComponent {
     async fn login(&self, input: LoginInput) -> Result<LoginOutput, AuthError> {
@@ -121,6 +507,21 @@ impl auth::Component for AuthComponent {
         self.signup_usecase.execute(input).await
     }
 
+    async fn logout(&self, input: LogoutInput) -> Result<(), AuthError> {
+        self.logout_usecase.execute(input).await
+    }
+
+    async fn logout_all(&self, input: LogoutAllInput) -> Result<(), AuthError> {
+        self.logout_all_usecase.execute(input).await
+    }
+
+    async fn refresh_token(
+        &self,
+        input: RefreshTokenInput,
+    ) -> Result<RefreshTokenOutput, AuthError> {
+        self.refresh_token_usecase.execute(input).await
+    }
+
     async fn start_totp_registration(
         &self,
         input: TOTPStartRegistrationInput,
@@ -135,6 +536,27 @@ impl auth::Component for AuthComponent {
         self.finish_totp_registration_usecase.execute(input).await
     }
 
+    async fn verify_totp_login(
+        &self,
+        input: TOTPVerifyLoginInput,
+    ) -> Result<TOTPVerifyLoginOutput, AuthError> {
+        self.verify_totp_login_usecase.execute(input).await
+    }
+
+    async fn list_totp_credentials(
+        &self,
+        input: TOTPListCredentialsInput,
+    ) -> Result<Vec<TOTPCredentialOutput>, AuthError> {
+        self.list_totp_credentials_usecase.execute(input).await
+    }
+
+    async fn rename_totp_credential(
+        &self,
+        input: TOTPRenameCredentialInput,
+    ) -> Result<(), AuthError> {
+        self.rename_totp_credential_usecase.execute(input).await
+    }
+
     async fn start_passkey_registration(
         &self,
         input: PasskeyStartRegistrationInput,
@@ -145,7 +567,7 @@ impl auth::Component for AuthComponent {
     async fn finish_passkey_registration(
         &self,
         input: PasskeyFinishRegistrationInput,
-    ) -> Result<(), AuthError> {
+    ) -> Result<PasskeyFinishRegistrationOutput, AuthError> {
         self.finish_passkey_registration_usecase
             .execute(input)
             .await
@@ -163,12 +585,60 @@ impl auth::Component for AuthComponent {
     async fn finish_passkey_authentication(
         &self,
         input: PasskeyFinishAuthenticationInput,
-    ) -> Result<(), AuthError> {
+    ) -> Result<PasskeyFinishAuthenticationOutput, AuthError> {
         self.finish_passkey_authentication_usecase
             .execute(input)
             .await
     }
 
+    async fn start_passkey_login(&self) -> Result<PasskeyStartLoginOutput, AuthError> {
+        self.start_passkey_login_usecase.execute().await
+    }
+
+    async fn finish_passkey_login(
+        &self,
+        input: PasskeyFinishLoginInput,
+    ) -> Result<PasskeyFinishAuthenticationOutput, AuthError> {
+        self.finish_passkey_login_usecase.execute(input).await
+    }
+
+    async fn list_passkeys(
+        &self,
+        input: PasskeyListInput,
+    ) -> Result<Vec<PasskeyOutput>, AuthError> {
+        self.list_passkeys_usecase.execute(input).await
+    }
+
+    async fn rename_passkey(&self, input: PasskeyRenameInput) -> Result<(), AuthError> {
+        self.rename_passkey_usecase.execute(input).await
+    }
+
+    async fn remove_passkey(&self, input: PasskeyRemoveInput) -> Result<(), AuthError> {
+        self.remove_passkey_usecase.execute(input).await
+    }
+
+    async fn verify_recovery_code(
+        &self,
+        input: RecoveryVerifyLoginInput,
+    ) -> Result<RecoveryVerifyLoginOutput, AuthError> {
+        self.verify_recovery_code_usecase.execute(input).await
+    }
+
+    async fn regenerate_recovery_codes(
+        &self,
+        input: RecoveryRegenerateCodesInput,
+    ) -> Result<RecoveryCodesOutput, AuthError> {
+        self.regenerate_recovery_codes_usecase.execute(input).await
+    }
+
+    async fn step_up_mfa(&self, input: MfaStepUpInput) -> Result<MfaStepUpOutput, AuthError> {
+        self.step_up_mfa_usecase.execute(input).await
+    }
+
+    async fn remove_totp(&self, input: MfaRemoveTotpInput) -> Result<(), AuthError> {
+        self.remove_totp_usecase.execute(input).await
+    }
+
     async fn get_mfa_registration(
         &self,
         input: MfaRegistrationInput,
@@ -176,7 +646,22 @@ impl auth::Component for AuthComponent {
         self.get_mfa_registration_usecase.execute(input).await
     }
 
+    async fn validate_token(
+        &self,
+        input: ValidateTokenInput,
+    ) -> Result<ValidateTokenOutput, AuthError> {
+        self.validate_token_usecase.execute(input).await
+    }
+
     async fn get_jwks(&self) -> Result<String, AuthError> {
         self.get_jwks_usecase.execute().await
     }
+
+    async fn rotate_signing_keys(&self) -> Result<RotateSigningKeysOutput, AuthError> {
+        self.rotate_signing_keys_usecase.execute().await
+    }
+
+    async fn unlock_attempts(&self, input: UnlockAttemptsInput) -> Result<(), AuthError> {
+        self.unlock_attempts_usecase.execute(input).await
+    }
 }
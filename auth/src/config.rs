@@ -0,0 +1,459 @@
+use std::str::FromStr;
+
+use contracts::auth::error::UserRepositoryError;
+use serde::{Deserialize, Serialize};
+
+pub use crate::entities::totp_credential::TotpAlgorithm;
+
+#[derive(Debug, Clone, Default)]
+pub struct AuthConfig {
+    pub user_repository: UserRepositoryConfig,
+    pub tokens: TokenConfig,
+    pub totp: TotpConfig,
+    pub webauthn: WebauthnConfig,
+    pub throttle: ThrottleConfig,
+    pub password_hashing: PasswordHashingConfig,
+    pub password_policy: PasswordPolicyConfig,
+}
+
+/// Signing keys and lifetimes of the issued tokens, in seconds. `iss` and `aud` are stamped on
+/// every token; tokens carrying other values are rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenConfig {
+    pub issuer: String,
+    pub audience: String,
+    /// Directory with the Ed25519 key pairs and their `keys.json` manifest.
+    pub keys_dir: String,
+    pub access_ttl: u64,
+    pub refresh_ttl: u64,
+    /// Access token with the `mfa:manage` scope returned by step-up.
+    pub elevated_ttl: u64,
+    pub mfa_verification_ttl: u64,
+    pub mfa_registration_ttl: u64,
+}
+
+impl TokenConfig {
+    fn ttls(&self) -> [u64; 5] {
+        [
+            self.access_ttl,
+            self.refresh_ttl,
+            self.elevated_ttl,
+            self.mfa_verification_ttl,
+            self.mfa_registration_ttl,
+        ]
+    }
+
+    /// Longest lifetime of any token we issue; retired signing keys are kept at least this long.
+    pub fn max_ttl(&self) -> u64 {
+        self.ttls().into_iter().max().unwrap_or_default()
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        if self.keys_dir.is_empty() {
+            return Err(ConfigError::InvalidTokens(
+                "keys dir must not be empty".to_string(),
+            ));
+        }
+        if self.ttls().contains(&0) {
+            return Err(ConfigError::InvalidTokens(
+                "token lifetimes must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for TokenConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "auth".to_string(),
+            audience: "api".to_string(),
+            keys_dir: "./ed25519".to_string(),
+            access_ttl: 600,           // 10 minutes
+            refresh_ttl: 604800,       // 7 days
+            elevated_ttl: 300,         // 5 minutes
+            mfa_verification_ttl: 300, // 5 minutes
+            mfa_registration_ttl: 180, // 3 minutes
+        }
+    }
+}
+
+/// Limits on password and second factor guesses. Failures are counted per username, per client
+/// address and per user for second factors. Past `free_attempts` every new try waits
+/// `base_delay` seconds, doubled on each further failure up to `max_delay`; at
+/// `lockout_threshold` the key is locked for `lockout_duration` seconds. Counters are forgotten
+/// `lockout_duration` seconds after the last failure.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThrottleConfig {
+    pub free_attempts: u32,
+    pub base_delay: u64,
+    pub max_delay: u64,
+    pub lockout_threshold: u32,
+    pub lockout_duration: u64,
+    /// Multiplies `free_attempts` and `lockout_threshold` for client addresses, which may be
+    /// shared by many users.
+    pub ip_allowance: u32,
+}
+
+impl ThrottleConfig {
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        if self.lockout_threshold == 0 || self.ip_allowance == 0 {
+            return Err(ConfigError::InvalidThrottle(
+                "lockout threshold and ip allowance must be positive".to_string(),
+            ));
+        }
+        if self.lockout_duration == 0 {
+            return Err(ConfigError::InvalidThrottle(
+                "lockout duration must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            free_attempts: 3,
+            base_delay: 1,
+            max_delay: 60,
+            lockout_threshold: 10,
+            lockout_duration: 900, // 15 minutes
+            ip_allowance: 10,
+        }
+    }
+}
+
+/// Algorithm and cost of new password hashes and the pool they are computed on. Stored hashes of
+/// any supported algorithm are verified; after a login the password is rehashed when its hash
+/// uses another algorithm or other parameters. At most `workers` hashes run at once; up to
+/// `queue_limit` more wait for a worker and further requests fail right away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordHashingConfig {
+    pub algorithm: PasswordAlgorithm,
+    /// Memory per Argon2id hash, in KiB.
+    pub argon2_memory: u32,
+    pub argon2_iterations: u32,
+    pub argon2_parallelism: u32,
+    pub bcrypt_cost: u32,
+    /// Whether bcrypt hashes may remain from before the switch to Argon2id. Every login then also
+    /// spends the time of a bcrypt check at `bcrypt_cost`, so that users still on bcrypt take as
+    /// long as the others and as unknown usernames.
+    pub legacy_bcrypt_hashes: bool,
+    pub workers: usize,
+    pub queue_limit: usize,
+}
+
+impl PasswordHashingConfig {
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        self.argon2_params()?;
+        if !(4..=31).contains(&self.bcrypt_cost) {
+            return Err(ConfigError::InvalidPasswordHashing(format!(
+                "bcrypt cost must be between 4 and 31, got {}",
+                self.bcrypt_cost
+            )));
+        }
+        if self.workers == 0 {
+            return Err(ConfigError::InvalidPasswordHashing(
+                "workers must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn argon2_params(&self) -> Result<argon2::Params, ConfigError> {
+        argon2::Params::new(
+            self.argon2_memory,
+            self.argon2_iterations,
+            self.argon2_parallelism,
+            None,
+        )
+        .map_err(|err| ConfigError::InvalidPasswordHashing(format!("argon2: {}", err)))
+    }
+}
+
+impl Default for PasswordHashingConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: PasswordAlgorithm::Argon2id,
+            // OWASP minimum for Argon2id: 19 MiB, 2 iterations, 1 lane.
+            argon2_memory: 19 * 1024,
+            argon2_iterations: 2,
+            argon2_parallelism: 1,
+            bcrypt_cost: bcrypt::DEFAULT_COST,
+            legacy_bcrypt_hashes: false,
+            workers: std::thread::available_parallelism().map_or(1, usize::from),
+            queue_limit: 64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordAlgorithm {
+    Argon2id,
+    Bcrypt,
+}
+
+impl FromStr for PasswordAlgorithm {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "argon2id" => Ok(PasswordAlgorithm::Argon2id),
+            "bcrypt" => Ok(PasswordAlgorithm::Bcrypt),
+            _ => Err(ConfigError::InvalidPasswordHashing(format!(
+                "unsupported algorithm {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Rules for new passwords, enforced whatever the client checked. Lengths count characters, not
+/// bytes. Passwords whose SHA-1 is listed in `breached_passwords_file` are rejected; the file has
+/// one hex SHA-1 per line, optionally followed by `:count` as in the Pwned Passwords downloads,
+/// and is read once at startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PasswordPolicyConfig {
+    pub min_length: usize,
+    pub max_length: usize,
+    pub require_uppercase: bool,
+    pub require_lowercase: bool,
+    pub require_digit: bool,
+    /// Any character that is not a letter or a digit.
+    pub require_special: bool,
+    /// Rejects passwords containing a word of the user's name or the local part of the e-mail.
+    pub reject_personal_info: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breached_passwords_file: Option<String>,
+}
+
+impl PasswordPolicyConfig {
+    pub(crate) fn validate(&self) -> Result<(), ConfigError> {
+        if self.min_length == 0 {
+            return Err(ConfigError::InvalidPasswordPolicy(
+                "min length must be positive".to_string(),
+            ));
+        }
+        if self.max_length < self.min_length {
+            return Err(ConfigError::InvalidPasswordPolicy(format!(
+                "max length {} is below min length {}",
+                self.max_length, self.min_length
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            max_length: 128,
+            require_uppercase: true,
+            require_lowercase: false,
+            require_digit: false,
+            require_special: true,
+            reject_personal_info: true,
+            breached_passwords_file: None,
+        }
+    }
+}
+
+/// Parameters of newly enrolled TOTP secrets, also written to the otpauth URL. Each credential
+/// keeps the algorithm, digits and step it was enrolled with, so changing them here only affects
+/// new enrolments; `skew` applies to every credential.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TotpConfig {
+    pub issuer: String,
+    pub algorithm: TotpAlgorithm,
+    pub digits: usize,
+    /// Length of a time step in seconds.
+    pub step: u64,
+    /// Steps accepted before and after the current one, to absorb clock drift.
+    pub skew: u8,
+}
+
+impl Default for TotpConfig {
+    fn default() -> Self {
+        Self {
+            issuer: "auth".to_string(),
+            algorithm: TotpAlgorithm::Sha1,
+            digits: 6,
+            step: 30,
+            skew: 1,
+        }
+    }
+}
+
+impl FromStr for TotpAlgorithm {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "SHA1" => Ok(TotpAlgorithm::Sha1),
+            "SHA256" => Ok(TotpAlgorithm::Sha256),
+            "SHA512" => Ok(TotpAlgorithm::Sha512),
+            _ => Err(ConfigError::InvalidTotp(format!(
+                "unsupported algorithm {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Relying party for passkeys. `rp_id` must be the host of every origin or a parent domain of it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WebauthnConfig {
+    pub rp_id: String,
+    pub rp_name: String,
+    /// Origins the browser may report, e.g. `https://app.example.com`.
+    pub origins: Vec<String>,
+    pub attestation: AttestationPreference,
+    /// PEM files of the attestation CAs whose authenticators may register; required exactly when
+    /// `attestation` is not `none`.
+    pub attestation_ca_files: Vec<String>,
+    /// Usernameless login always requires user verification, whatever this says: the passkey is
+    /// the only factor there.
+    pub user_verification: UserVerification,
+    /// Seconds the user has to answer the authenticator.
+    pub timeout: u64,
+    /// Usernameless logins started and not yet finished or expired; further starts are refused
+    /// as busy, since anyone may start one.
+    pub max_pending_logins: usize,
+}
+
+impl Default for WebauthnConfig {
+    fn default() -> Self {
+        Self {
+            rp_id: "localhost".to_string(),
+            rp_name: "auth".to_string(),
+            origins: vec!["http://localhost:8000".to_string()],
+            attestation: AttestationPreference::None,
+            attestation_ca_files: Vec::new(),
+            user_verification: UserVerification::Required,
+            timeout: 300,
+            max_pending_logins: 10_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttestationPreference {
+    None,
+    Indirect,
+    Direct,
+}
+
+impl FromStr for AttestationPreference {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "none" => Ok(AttestationPreference::None),
+            "indirect" => Ok(AttestationPreference::Indirect),
+            "direct" => Ok(AttestationPreference::Direct),
+            _ => Err(ConfigError::InvalidWebauthn(format!(
+                "unsupported attestation preference {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Whether the authenticator must verify the user (PIN, biometrics) and not only their presence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserVerification {
+    Required,
+    Preferred,
+    Discouraged,
+}
+
+impl FromStr for UserVerification {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "required" => Ok(UserVerification::Required),
+            "preferred" => Ok(UserVerification::Preferred),
+            "discouraged" => Ok(UserVerification::Discouraged),
+            _ => Err(ConfigError::InvalidWebauthn(format!(
+                "unsupported user verification policy {}",
+                s
+            ))),
+        }
+    }
+}
+
+/// Storage backend for users; persistent backends also keep the token revocation list, the
+/// refresh token families and the failed login attempts.
+#[derive(Debug, Clone, Default)]
+pub enum UserRepositoryConfig {
+    #[default]
+    Memory,
+    #[cfg(feature = "sqlite")]
+    Sqlite { url: String },
+    #[cfg(feature = "postgres")]
+    Postgres { url: String },
+}
+
+impl UserRepositoryConfig {
+    /// Picks the repository from a connection URL: `memory`, `sqlite:<path>` or `postgres://...`.
+    pub fn from_url(url: &str) -> Result<Self, ConfigError> {
+        if url == "memory" {
+            return Ok(UserRepositoryConfig::Memory);
+        }
+
+        #[cfg(feature = "sqlite")]
+        if url.starts_with("sqlite:") {
+            return Ok(UserRepositoryConfig::Sqlite {
+                url: url.to_string(),
+            });
+        }
+
+        #[cfg(feature = "postgres")]
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            return Ok(UserRepositoryConfig::Postgres {
+                url: url.to_string(),
+            });
+        }
+
+        Err(ConfigError::UnsupportedUserRepository(url.to_string()))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Unsupported user repository URL: {0}")]
+    UnsupportedUserRepository(String),
+
+    #[error("Failed to initialize user repository: {0}")]
+    UserRepository(UserRepositoryError),
+
+    #[error("Invalid token configuration: {0}")]
+    InvalidTokens(String),
+
+    #[error("Invalid TOTP configuration: {0}")]
+    InvalidTotp(String),
+
+    #[error("Invalid WebAuthn configuration: {0}")]
+    InvalidWebauthn(String),
+
+    #[error("Invalid throttle configuration: {0}")]
+    InvalidThrottle(String),
+
+    #[error("Invalid password hashing configuration: {0}")]
+    InvalidPasswordHashing(String),
+
+    #[error("Invalid password policy configuration: {0}")]
+    InvalidPasswordPolicy(String),
+}
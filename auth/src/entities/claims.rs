@@ -1,12 +1,53 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Scope of the short-lived access token that may enrol or remove MFA factors.
+pub const MFA_MANAGE_SCOPE: &str = "mfa:manage";
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Claims {
-    // aud: String, // Optional. Audience
-    pub exp: usize, // Required (validate_exp defaults to true in validation). Expiration time (as UTC timestamp)
-    // iat: usize, // Optional. Issued at (as UTC timestamp)
-    // iss: String, // Optional. Issuer
-    // nbf: usize,  // Optional. Not Before (as UTC timestamp)
-    pub sub: String, // Optional. Subject (whom token refers to)
+    pub iss: String, // Issuer, stamped by ForAuthTokens::create_token
+    pub aud: String, // Audience, stamped by ForAuthTokens::create_token
+    pub exp: usize,  // Expiration time (as UTC timestamp)
+    pub iat: usize,  // Issued at (as UTC timestamp)
+    pub nbf: usize,  // Not Before (as UTC timestamp)
+    pub sub: String, // Subject (whom token refers to)
+    pub jti: String, // JWT ID
     pub token_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scope: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>, // Refresh token family, shared by every rotation of a login
+    #[serde(default, rename = "gen")]
+    pub generation: u64, // Logout-all generation of the user, stamped by ForAuthTokens::create_token
+}
+
+impl Claims {
+    /// Claims valid from now for `ttl` seconds, with a fresh `jti`.
+    pub fn new(token_type: &str, sub: String, ttl: u64) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should go forward")
+            .as_secs();
+
+        Self {
+            iss: String::new(),
+            aud: String::new(),
+            exp: (now + ttl) as usize,
+            iat: now as usize,
+            nbf: now as usize,
+            sub,
+            jti: Uuid::new_v4().to_string(),
+            token_type: token_type.to_string(),
+            scope: Vec::new(),
+            family: None,
+            generation: 0,
+        }
+    }
+
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scope.iter().any(|s| s == scope)
+    }
 }
@@ -0,0 +1,39 @@
+use std::net::IpAddr;
+
+use uuid::Uuid;
+
+/// What a failed guess is counted against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttemptKey {
+    /// Password guesses for a username, whether or not the account exists.
+    Username(String),
+    /// Every guess coming from a client address.
+    Ip(IpAddr),
+    /// Second factor guesses (TOTP, recovery codes, step-up) for a user. Keyed on the user
+    /// rather than the MFA token, which a caller holding the password can mint again.
+    SecondFactor(Uuid),
+}
+
+impl AttemptKey {
+    /// Key under which the attempts are stored.
+    pub fn id(&self) -> String {
+        match self {
+            AttemptKey::Username(username) => format!("username:{}", username),
+            AttemptKey::Ip(ip) => format!("ip:{}", ip),
+            AttemptKey::SecondFactor(user_id) => format!("mfa:{}", user_id),
+        }
+    }
+
+    /// Whether a successful attempt clears the counter. Addresses are not cleared, otherwise
+    /// logging into an own account between guesses would reset the limit.
+    pub fn resets_on_success(&self) -> bool {
+        !matches!(self, AttemptKey::Ip(_))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FailedAttempts {
+    pub failures: u32,
+    pub last_failure_at: u64,
+    pub locked_until: Option<u64>,
+}
@@ -1,3 +1,9 @@
 pub mod claims;
+pub mod failed_attempts;
+pub mod passkey_credential;
+pub mod recovery_code;
+pub mod refresh_token;
+pub mod signing_key_set;
 pub mod totp;
+pub mod totp_credential;
 pub mod user;
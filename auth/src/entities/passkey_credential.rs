@@ -0,0 +1,43 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use webauthn_rs::prelude::Passkey;
+
+/// A registered passkey plus what the user needs to tell it apart from the others.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasskeyCredential {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    /// Authenticator model reported at registration; `None` when the browser hides it.
+    #[serde(default)]
+    pub aaguid: Option<Uuid>,
+    pub passkey: Passkey,
+}
+
+impl PasskeyCredential {
+    pub fn new(name: String, passkey: Passkey, aaguid: Option<Uuid>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name,
+            created_at: now(),
+            last_used_at: None,
+            aaguid,
+            passkey,
+        }
+    }
+
+    pub fn mark_used(&mut self) {
+        self.last_used_at = Some(now());
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs()
+}
@@ -0,0 +1,44 @@
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+// No 0/O or 1/I, so codes survive being read back from paper.
+const ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const GROUP_LEN: usize = 5;
+
+/// A fresh batch of recovery codes: `plain` is shown once to the user, `hashed` is stored.
+pub struct RecoveryCodes {
+    pub plain: Vec<String>,
+    pub hashed: Vec<String>,
+}
+
+impl RecoveryCodes {
+    pub fn generate() -> Self {
+        let mut rng = rand::rng();
+        let plain: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let mut group = || -> String {
+                    (0..GROUP_LEN)
+                        .map(|_| ALPHABET[rng.random_range(0..ALPHABET.len())] as char)
+                        .collect()
+                };
+                format!("{}-{}", group(), group())
+            })
+            .collect();
+        let hashed = plain.iter().map(|code| hash_recovery_code(code)).collect();
+
+        Self { plain, hashed }
+    }
+}
+
+/// Codes carry 50 random bits, so a plain SHA-256 is enough; input is normalized first so
+/// case, spaces and the separator do not matter.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    format!("{:x}", Sha256::digest(normalized.as_bytes()))
+}
@@ -0,0 +1,9 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RefreshTokenStatus {
+    /// First use of the token; it is now marked as used.
+    Valid { family_id: String },
+    /// The token had already been used, so it was most likely stolen.
+    Reused { family_id: String },
+    /// The token was never registered, has expired or its family was revoked.
+    Unknown,
+}
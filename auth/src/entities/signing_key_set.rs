@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle of the token signing keys, identified by `kid`.
+///
+/// `active` signs new tokens, `next` is already published so verifiers pick it up before it is
+/// promoted, and `retired` keys are only published until every token they signed has expired.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SigningKeySet {
+    pub active: String,
+    #[serde(default)]
+    pub next: Option<String>,
+    #[serde(default)]
+    pub retired: Vec<RetiredKey>,
+}
+
+/// Outcome of a rotation: the new key set and the retired kids that were deleted.
+#[derive(Debug, Clone)]
+pub struct KeyRotation {
+    pub key_set: SigningKeySet,
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetiredKey {
+    pub kid: String,
+    /// Unix timestamp after which tokens signed by this key are no longer accepted.
+    pub verify_until: u64,
+}
+
+impl SigningKeySet {
+    /// Keys that may verify a token at `now`, i.e. the ones published in the JWKS.
+    pub fn verification_kids(&self, now: u64) -> Vec<&str> {
+        let mut kids = vec![self.active.as_str()];
+        kids.extend(self.next.as_deref());
+        kids.extend(
+            self.retired
+                .iter()
+                .filter(|key| key.verify_until > now)
+                .map(|key| key.kid.as_str()),
+        );
+        kids
+    }
+
+    /// Promotes `next` to active, retiring the current active key for `retire_for` seconds, and
+    /// stages `new_next`. Without a staged key only `new_next` is staged.
+    pub fn rotate(&mut self, new_next: String, now: u64, retire_for: u64) {
+        if let Some(next) = self.next.take() {
+            let previous = std::mem::replace(&mut self.active, next);
+            self.retired.push(RetiredKey {
+                kid: previous,
+                verify_until: now + retire_for,
+            });
+        }
+        self.next = Some(new_next);
+    }
+
+    /// Drops retired keys whose deadline has passed, returning their kids.
+    pub fn prune(&mut self, now: u64) -> Vec<String> {
+        let (expired, retired) = std::mem::take(&mut self.retired)
+            .into_iter()
+            .partition(|key| key.verify_until <= now);
+        self.retired = retired;
+        expired.into_iter().map(|key: RetiredKey| key.kid).collect()
+    }
+}
@@ -1,3 +1,4 @@
 pub const TOTP_REG_STATE: &str = "totp/reg/state";
 pub const WEBAUTHN_REG_STATE: &str = "webauthn/reg/state";
 pub const WEBAUTHN_AUTH_STATE: &str = "webauthn/auth/state";
+pub const WEBAUTHN_LOGIN_STATE: &str = "webauthn/login/state";
@@ -0,0 +1,78 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// One enrolled authenticator app; a user may keep several, e.g. a backup device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TotpCredential {
+    pub id: Uuid,
+    pub label: String,
+    pub secret: String,
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    /// Last time step accepted, so a code cannot be replayed within its window.
+    #[serde(default)]
+    pub last_step: Option<u64>,
+    /// Parameters the authenticator was enrolled with. Credentials enrolled before they were
+    /// stored have none and are verified with the configured ones.
+    #[serde(default)]
+    pub parameters: Option<TotpParameters>,
+}
+
+/// How an authenticator derives its codes, as written to the otpauth URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TotpParameters {
+    pub algorithm: TotpAlgorithm,
+    pub digits: usize,
+    /// Length of a time step in seconds.
+    pub step: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+impl TotpAlgorithm {
+    /// Name used in the `algorithm` parameter of otpauth URLs.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TotpAlgorithm::Sha1 => "SHA1",
+            TotpAlgorithm::Sha256 => "SHA256",
+            TotpAlgorithm::Sha512 => "SHA512",
+        }
+    }
+}
+
+impl TotpCredential {
+    /// A credential enrolled with `parameters` whose enrolment code matched `step`.
+    pub fn new(label: String, secret: String, parameters: TotpParameters, step: u64) -> Self {
+        let now = now();
+        Self {
+            id: Uuid::new_v4(),
+            label,
+            secret,
+            created_at: now,
+            last_used_at: Some(now),
+            last_step: Some(step),
+            parameters: Some(parameters),
+        }
+    }
+
+    pub fn mark_used(&mut self, step: u64) {
+        self.last_used_at = Some(now());
+        self.last_step = Some(step);
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs()
+}
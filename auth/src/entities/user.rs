@@ -1,5 +1,8 @@
+use crate::entities::{
+    passkey_credential::PasskeyCredential, recovery_code::RecoveryCodes,
+    totp_credential::TotpCredential,
+};
 use uuid::Uuid;
-use webauthn_rs::prelude::Passkey;
 
 #[derive(Clone, Debug)]
 pub struct User {
@@ -7,8 +10,12 @@ pub struct User {
     pub name: String,
     pub username: String,
     pub password: String,
-    pub otp_secret: Option<String>,
-    pub pass_keys: Vec<Passkey>,
+    pub totp_credentials: Vec<TotpCredential>,
+    pub pass_keys: Vec<PasskeyCredential>,
+    /// SHA-256 hashes of the unused recovery codes.
+    pub recovery_codes: Vec<String>,
+    /// Bumped by every write, so an update can tell whether the user changed since it was read.
+    pub version: u64,
 }
 
 impl User {
@@ -19,7 +26,30 @@ impl User {
             name: name.to_string(),
             password: password.to_string(),
             pass_keys: Vec::new(),
-            otp_secret: None,
+            totp_credentials: Vec::new(),
+            recovery_codes: Vec::new(),
+            version: 0,
+        }
+    }
+
+    /// Whether any second factor is enrolled.
+    pub fn has_mfa(&self) -> bool {
+        !self.totp_credentials.is_empty() || !self.pass_keys.is_empty()
+    }
+
+    /// Replaces every recovery code, returning the plain codes to show once.
+    pub fn regenerate_recovery_codes(&mut self) -> Vec<String> {
+        let codes = RecoveryCodes::generate();
+        self.recovery_codes = codes.hashed;
+        codes.plain
+    }
+
+    /// Codes for a newly enrolled factor; users who still have unused codes keep them.
+    pub fn issue_missing_recovery_codes(&mut self) -> Vec<String> {
+        if self.recovery_codes.is_empty() {
+            self.regenerate_recovery_codes()
+        } else {
+            Vec::new()
         }
     }
 }
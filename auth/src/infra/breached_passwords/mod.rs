@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use contracts::auth::error::BreachedPasswordsError;
+use sha1::{Digest, Sha1};
+
+use crate::{config::ConfigError, ports::breached_passwords::BreachedPasswords};
+
+#[cfg(test)]
+mod tests;
+
+const PREFIX_LEN: usize = 5;
+const SHA1_HEX_LEN: usize = 40;
+
+/// Breached password hashes read from a local file, grouped by the 5 digit prefix the way the
+/// Pwned Passwords range API serves them.
+pub struct FileBreachedPasswords {
+    ranges: HashMap<String, Vec<String>>,
+}
+
+impl FileBreachedPasswords {
+    /// Reads `path`: one hex SHA-1 per line, optionally followed by `:count`. Blank lines are
+    /// skipped; anything else fails, so a truncated or wrong file is noticed at startup.
+    pub async fn load(path: &str) -> Result<Self, ConfigError> {
+        let contents = tokio::fs::read_to_string(path).await.map_err(|err| {
+            ConfigError::InvalidPasswordPolicy(format!("failed to read {}: {}", path, err))
+        })?;
+
+        let mut ranges: HashMap<String, Vec<String>> = HashMap::new();
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let hash = line.split_once(':').map_or(line, |(hash, _)| hash);
+            if hash.len() != SHA1_HEX_LEN || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(ConfigError::InvalidPasswordPolicy(format!(
+                    "{}:{}: expected a hex SHA-1",
+                    path,
+                    number + 1
+                )));
+            }
+            let hash = hash.to_ascii_uppercase();
+            ranges
+                .entry(hash[..PREFIX_LEN].to_string())
+                .or_default()
+                .push(hash[PREFIX_LEN..].to_string());
+        }
+        Ok(Self { ranges })
+    }
+}
+
+#[async_trait]
+impl BreachedPasswords for FileBreachedPasswords {
+    async fn is_breached(&self, password: &str) -> Result<bool, BreachedPasswordsError> {
+        let hash = format!("{:X}", Sha1::digest(password.as_bytes()));
+        let (prefix, suffix) = hash.split_at(PREFIX_LEN);
+        Ok(self
+            .ranges
+            .get(prefix)
+            .is_some_and(|suffixes| suffixes.iter().any(|breached| breached == suffix)))
+    }
+}
@@ -0,0 +1,52 @@
+use sha1::{Digest, Sha1};
+use uuid::Uuid;
+
+use super::FileBreachedPasswords;
+use crate::{config::ConfigError, ports::breached_passwords::BreachedPasswords};
+
+fn sha1(password: &str) -> String {
+    format!("{:X}", Sha1::digest(password.as_bytes()))
+}
+
+async fn load(contents: &str) -> Result<FileBreachedPasswords, ConfigError> {
+    let path = std::env::temp_dir().join(format!("breached-{}.txt", Uuid::new_v4()));
+    std::fs::write(&path, contents).unwrap();
+    let loaded = FileBreachedPasswords::load(path.to_str().unwrap()).await;
+    let _ = std::fs::remove_file(&path);
+    loaded
+}
+
+/// Hashes may carry a count and be in either case; blank lines are skipped.
+#[tokio::test]
+async fn listed_passwords_are_breached() {
+    let contents = format!(
+        "{}:52579\n\n  {}  \n",
+        sha1("Senha@123"),
+        sha1("Password123!").to_lowercase()
+    );
+    let breached = load(&contents).await.unwrap();
+
+    assert!(breached.is_breached("Senha@123").await.unwrap());
+    assert!(breached.is_breached("Password123!").await.unwrap());
+    assert!(!breached.is_breached("Senha@1234").await.unwrap());
+}
+
+#[tokio::test]
+async fn a_line_that_is_not_a_sha1_fails_with_its_number() {
+    let contents = format!("{}\nSenha@123\n", sha1("Password123!"));
+
+    match load(&contents).await {
+        Err(ConfigError::InvalidPasswordPolicy(message)) => {
+            assert!(message.ends_with(":2: expected a hex SHA-1"), "{}", message)
+        }
+        _ => panic!("expected InvalidPasswordPolicy"),
+    }
+}
+
+#[tokio::test]
+async fn a_missing_file_fails() {
+    assert!(matches!(
+        FileBreachedPasswords::load("/nonexistent/breached.txt").await,
+        Err(ConfigError::InvalidPasswordPolicy(_))
+    ));
+}
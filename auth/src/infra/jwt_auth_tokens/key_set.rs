@@ -0,0 +1,164 @@
+use crate::entities::signing_key_set::{RetiredKey, SigningKeySet};
+use contracts::auth::error::AuthTokenError;
+use ed25519_dalek::{
+    SigningKey,
+    pkcs8::{EncodePrivateKey, EncodePublicKey, KeypairBytes},
+};
+use log::error;
+use rand::Rng;
+use std::{io::ErrorKind, path::Path};
+use tokio::{fs, io::AsyncWriteExt};
+
+const MANIFEST: &str = "keys.json";
+
+/// Reads `keys.json` from `dir`. Directories without a manifest keep the old layout: the
+/// lexicographically last `*_key.pem` signs and every `*_public.pem` stays published.
+pub async fn load(dir: &str) -> Result<SigningKeySet, AuthTokenError> {
+    match fs::read_to_string(Path::new(dir).join(MANIFEST)).await {
+        Ok(json) => serde_json::from_str(&json).map_err(|err| {
+            error!("Invalid signing key manifest: {}", err);
+            AuthTokenError::JwksFetchError
+        }),
+        Err(err) if err.kind() == ErrorKind::NotFound => legacy(dir).await,
+        Err(err) => {
+            error!("Failed to read signing key manifest: {}", err);
+            Err(AuthTokenError::JwksFetchError)
+        }
+    }
+}
+
+async fn legacy(dir: &str) -> Result<SigningKeySet, AuthTokenError> {
+    let mut private = Vec::new();
+    let mut public = Vec::new();
+
+    let mut rd = fs::read_dir(dir).await.map_err(|err| {
+        error!("Failed to read keys directory: {}", err);
+        AuthTokenError::JwksFetchError
+    })?;
+    while let Some(entry) = rd.next_entry().await.map_err(|err| {
+        error!("Failed to read directory entry: {}", err);
+        AuthTokenError::JwksFetchError
+    })? {
+        let fname = entry.file_name().to_string_lossy().into_owned();
+        if let Some(kid) = fname.strip_suffix("_key.pem") {
+            private.push(kid.to_string());
+        } else if let Some(kid) = fname.strip_suffix("_public.pem") {
+            public.push(kid.to_string());
+        }
+    }
+
+    let Some(active) = private.into_iter().max() else {
+        error!("No key files found in the keys directory.");
+        return Err(AuthTokenError::JwksFetchError);
+    };
+    let retired = public
+        .into_iter()
+        .filter(|kid| *kid != active)
+        .map(|kid| RetiredKey {
+            kid,
+            verify_until: u64::MAX,
+        })
+        .collect();
+
+    Ok(SigningKeySet {
+        active,
+        next: None,
+        retired,
+    })
+}
+
+pub async fn save(dir: &str, key_set: &SigningKeySet) -> Result<(), AuthTokenError> {
+    let json = serde_json::to_string_pretty(key_set).map_err(|err| {
+        error!("Failed to serialize signing key manifest: {}", err);
+        AuthTokenError::KeyRotationFailure
+    })?;
+    // Write then rename so readers never see a half-written manifest.
+    let tmp = Path::new(dir).join(format!("{}.tmp", MANIFEST));
+    fs::write(&tmp, json).await.map_err(|err| {
+        error!("Failed to write signing key manifest: {}", err);
+        AuthTokenError::KeyRotationFailure
+    })?;
+    fs::rename(&tmp, Path::new(dir).join(MANIFEST))
+        .await
+        .map_err(|err| {
+            error!("Failed to replace signing key manifest: {}", err);
+            AuthTokenError::KeyRotationFailure
+        })
+}
+
+/// Writes a new Ed25519 pair as `{kid}_key.pem` (PKCS#8) and `{kid}_public.pem` (SPKI), failing
+/// if either file already exists.
+pub async fn generate(dir: &str, kid: &str) -> Result<(), AuthTokenError> {
+    let mut secret = [0u8; 32];
+    rand::rng().fill(&mut secret);
+    let signing_key = SigningKey::from_bytes(&secret);
+
+    let private_der = KeypairBytes {
+        secret_key: secret,
+        public_key: None,
+    }
+    .to_pkcs8_der()
+    .map_err(|err| {
+        error!("Failed to encode private key: {}", err);
+        AuthTokenError::KeyRotationFailure
+    })?;
+    let public_der = signing_key
+        .verifying_key()
+        .to_public_key_der()
+        .map_err(|err| {
+            error!("Failed to encode public key: {}", err);
+            AuthTokenError::KeyRotationFailure
+        })?;
+
+    let private_pem = pem::encode(&pem::Pem::new("PRIVATE KEY", private_der.as_bytes()));
+    let public_pem = pem::encode(&pem::Pem::new("PUBLIC KEY", public_der.as_bytes()));
+
+    let private_path = Path::new(dir).join(format!("{}_key.pem", kid));
+    write_key(&private_path, private_pem).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&private_path, std::fs::Permissions::from_mode(0o600))
+            .await
+            .map_err(|err| {
+                error!("Failed to restrict key file {:?}: {}", private_path, err);
+                AuthTokenError::KeyRotationFailure
+            })?;
+    }
+    write_key(
+        &Path::new(dir).join(format!("{}_public.pem", kid)),
+        public_pem,
+    )
+    .await
+}
+
+async fn write_key(path: &Path, contents: String) -> Result<(), AuthTokenError> {
+    let write = async {
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .await?;
+        file.write_all(contents.as_bytes()).await
+    };
+    write.await.map_err(|err| {
+        error!("Failed to write key file {:?}: {}", path, err);
+        AuthTokenError::KeyRotationFailure
+    })
+}
+
+/// Deletes both files of a retired pair; files already gone are ignored.
+pub async fn remove(dir: &str, kid: &str) -> Result<(), AuthTokenError> {
+    for suffix in ["_key.pem", "_public.pem"] {
+        let path = Path::new(dir).join(format!("{}{}", kid, suffix));
+        match fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::NotFound => {}
+            Err(err) => {
+                error!("Failed to remove key file {:?}: {}", path, err);
+                return Err(AuthTokenError::KeyRotationFailure);
+            }
+        }
+    }
+    Ok(())
+}
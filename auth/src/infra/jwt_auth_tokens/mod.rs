@@ -1,20 +1,68 @@
-use crate::{entities::claims::Claims, ports::for_auth_tokens::ForAuthTokens};
+use crate::{
+    entities::{claims::Claims, signing_key_set::KeyRotation},
+    ports::{for_auth_tokens::ForAuthTokens, token_revocation_store::TokenRevocationStore},
+};
 use async_trait::async_trait;
 use contracts::auth::error::AuthTokenError;
 use jsonwebtoken::{
     Algorithm, DecodingKey, EncodingKey, Validation, dangerous::insecure_decode, decode, encode,
 };
 use log::{error, warn};
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
 
+mod key_set;
 mod read_key;
 mod read_public;
 
-pub struct JwtAuthTokens {}
+/// Clock skew tolerated on `exp`, `nbf` and `iat`, in seconds.
+const LEEWAY: u64 = 60;
+
+pub struct JwtAuthTokens {
+    issuer: String,
+    audience: String,
+    keys_dir: String,
+    token_revocation_store: Arc<dyn TokenRevocationStore>,
+}
+
+impl JwtAuthTokens {
+    pub fn new(
+        issuer: String,
+        audience: String,
+        keys_dir: String,
+        token_revocation_store: Arc<dyn TokenRevocationStore>,
+    ) -> Self {
+        Self {
+            issuer,
+            audience,
+            keys_dir,
+            token_revocation_store,
+        }
+    }
+}
 
 #[async_trait]
 impl ForAuthTokens for JwtAuthTokens {
-    async fn create_token(&self, claims: Claims) -> Result<String, AuthTokenError> {
-        let (name, data) = read_key::get_first_key_cached("./ed25519").await?;
+    async fn create_token(&self, mut claims: Claims) -> Result<String, AuthTokenError> {
+        claims.iss = self.issuer.clone();
+        claims.aud = self.audience.clone();
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
+            error!("Token subject is not a user id: {}", claims.sub);
+            AuthTokenError::TokenCreationFailure
+        })?;
+        claims.generation = self
+            .token_revocation_store
+            .generation(user_id)
+            .await
+            .map_err(|err| {
+                error!("Failed to read the token generation: {}", err);
+                AuthTokenError::TokenCreationFailure
+            })?;
+
+        let (name, data) = read_key::get_active_key_cached(&self.keys_dir).await?;
 
         let encoding_key = EncodingKey::from_ed_pem(&data).map_err(|err| {
             match *err.kind() {
@@ -34,11 +82,7 @@ impl ForAuthTokens for JwtAuthTokens {
             ..Default::default()
         };
         let token = encode(&header, &claims, &encoding_key).map_err(|err| {
-            match *err.kind() {
-                _ => {
-                    error!("An error occurred while encoding the token: {}", err);
-                }
-            }
+            error!("An error occurred while encoding the token: {}", err);
             AuthTokenError::TokenCreationFailure
         })?;
 
@@ -62,7 +106,7 @@ impl ForAuthTokens for JwtAuthTokens {
         if claims.token_type != token_type {
             warn!(
                 "Token type mismatch: expected {}, found {}",
-                claims.token_type, token_type
+                token_type, claims.token_type
             );
             return Err(AuthTokenError::InvalidToken);
         }
@@ -80,7 +124,7 @@ impl ForAuthTokens for JwtAuthTokens {
                 AuthTokenError::InvalidToken
             })?
         };
-        let jwks = read_public::build_jwks_from_dir("./ed25519").await?;
+        let jwks = read_public::build_jwks_from_dir(&self.keys_dir).await?;
         let jwk = jwks.find(&kid).ok_or_else(|| {
             error!("No matching JWK found for kid: {}", kid);
             AuthTokenError::InvalidToken
@@ -97,7 +141,12 @@ impl ForAuthTokens for JwtAuthTokens {
             AuthTokenError::TokenCreationFailure
         })?;
 
-        let validation = Validation::new(Algorithm::EdDSA);
+        let mut validation = Validation::new(Algorithm::EdDSA);
+        validation.leeway = LEEWAY;
+        validation.validate_nbf = true;
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+        validation.set_required_spec_claims(&["exp", "nbf", "iss", "aud", "sub"]);
         let claims =
             decode::<Claims>(&token, &decoding_key, &validation).map_err(|err| {
                 match *err.kind() {
@@ -108,22 +157,95 @@ impl ForAuthTokens for JwtAuthTokens {
                     jsonwebtoken::errors::ErrorKind::InvalidSignature => {
                         AuthTokenError::InvalidSignature
                     }
+                    jsonwebtoken::errors::ErrorKind::ImmatureSignature
+                    | jsonwebtoken::errors::ErrorKind::InvalidIssuer
+                    | jsonwebtoken::errors::ErrorKind::InvalidAudience => {
+                        warn!("Token rejected: {}", err);
+                        AuthTokenError::InvalidToken
+                    }
                     _ => {
                         error!("An error occurred while decoding the token: {}", err);
                         AuthTokenError::InvalidToken
                     }
                 }
             })?;
+        let claims = claims.claims;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should go forward")
+            .as_secs();
+        if claims.iat as u64 > now + LEEWAY {
+            warn!("Token rejected: issued in the future (iat {})", claims.iat);
+            return Err(AuthTokenError::InvalidToken);
+        }
+        if claims.jti.is_empty() {
+            warn!("Token rejected: empty jti");
+            return Err(AuthTokenError::InvalidToken);
+        }
+
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| {
+            warn!("Token rejected: sub is not a user id");
+            AuthTokenError::InvalidToken
+        })?;
+        let revoked = self
+            .token_revocation_store
+            .is_revoked(&claims.jti, user_id, claims.generation)
+            .await
+            .map_err(|err| {
+                error!("Failed to check token revocation: {}", err);
+                AuthTokenError::RevocationCheckFailed
+            })?;
+        if revoked {
+            warn!("Token rejected: {} was revoked", claims.jti);
+            return Err(AuthTokenError::TokenRevoked);
+        }
 
-        Ok(claims.claims)
+        Ok(claims)
     }
 
     async fn get_jwks(&self) -> Result<String, AuthTokenError> {
-        let jwks = read_public::build_jwks_from_dir("./ed25519").await?;
+        let jwks = read_public::build_jwks_from_dir(&self.keys_dir).await?;
         let jwks_json = serde_json::to_string(&jwks).map_err(|err| {
             error!("Failed to serialize JWKs to JSON: {}", err);
             AuthTokenError::JwksFetchError
         })?;
         Ok(jwks_json)
     }
+
+    async fn rotate_keys(&self, max_token_lifetime: u64) -> Result<KeyRotation, AuthTokenError> {
+        let mut key_set = key_set::load(&self.keys_dir).await?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should go forward")
+            .as_secs();
+        // Other processes may keep signing with the old active key until their cache expires.
+        let retire_for = max_token_lifetime + read_key::TTL.as_secs() + LEEWAY;
+
+        // Keys inherited from a directory without manifest get a real deadline on first rotation.
+        for key in key_set.retired.iter_mut() {
+            if key.verify_until == u64::MAX {
+                key.verify_until = now + retire_for;
+            }
+        }
+        let removed = key_set.prune(now);
+
+        // The random suffix keeps rotations within the same second apart; `generate` also refuses
+        // to overwrite an existing pair.
+        let kid = format!("k{}-{:08x}", now, rand::random::<u32>());
+        key_set::generate(&self.keys_dir, &kid).await?;
+        key_set.rotate(kid, now, retire_for);
+        key_set::save(&self.keys_dir, &key_set).await?;
+
+        for kid in &removed {
+            key_set::remove(&self.keys_dir, kid).await?;
+        }
+        read_key::invalidate(&self.keys_dir).await;
+        read_public::invalidate(&self.keys_dir).await;
+
+        Ok(KeyRotation { key_set, removed })
+    }
 }
+
+#[cfg(test)]
+mod tests;
@@ -6,10 +6,16 @@ use jsonwebtoken::jwk::{
 };
 use log::error;
 use once_cell::sync::Lazy;
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 use tokio::{fs, sync::RwLock, time::Instant};
 
-const CACHE_TTL_SECS: u64 = 10 * 60; // 10 minutes
+use super::key_set;
+
+pub const CACHE_TTL_SECS: u64 = 10 * 60; // 10 minutes
 
 #[derive(Debug)]
 struct CacheEntry {
@@ -17,44 +23,31 @@ struct CacheEntry {
     expires_at: Instant,
 }
 
-static CACHE: Lazy<RwLock<Option<CacheEntry>>> = Lazy::new(|| RwLock::new(None));
+/// Per keys directory.
+static CACHE: Lazy<RwLock<HashMap<String, CacheEntry>>> = Lazy::new(|| RwLock::new(HashMap::new()));
 
+/// JWKS with the active, next and still-verifiable retired keys.
 pub async fn build_jwks_from_dir(dir: &str) -> Result<JwkSet, AuthTokenError> {
     {
         let read = CACHE.read().await;
-        if let Some(cached) = read.as_ref() {
-            if Instant::now() < cached.expires_at {
-                return Ok(cached.jwks.clone());
-            }
+        if let Some(cached) = read.get(dir)
+            && Instant::now() < cached.expires_at
+        {
+            return Ok(cached.jwks.clone());
         }
     }
 
     let mut keys = Vec::new();
 
-    let mut rd = fs::read_dir(dir).await.map_err(|err| {
-        error!("Failed to read public directory: {}", err);
-        AuthTokenError::JwksFetchError
-    })?;
-
-    while let Some(entry) = rd.next_entry().await.map_err(|err| {
-        error!("Failed to read directory entry: {}", err);
-        AuthTokenError::JwksFetchError
-    })? {
-        let path = entry.path();
-
-        if !path.is_file() {
-            continue;
-        }
-
-        let Some(fname) = path.file_name().and_then(|s| s.to_str()) else {
-            continue;
-        };
-
-        if !fname.ends_with("_public.pem") {
-            continue;
-        }
+    let key_set = key_set::load(dir).await?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs();
 
-        let kid = fname.trim_end_matches("_public.pem").to_string();
+    for kid in key_set.verification_kids(now) {
+        let path = Path::new(dir).join(format!("{}_public.pem", kid));
+        let kid = kid.to_string();
         let pem = fs::read_to_string(&path).await.map_err(|err| {
             error!("Failed to read public PEM file {:?}: {}", path, err);
             AuthTokenError::JwksFetchError
@@ -91,11 +84,18 @@ pub async fn build_jwks_from_dir(dir: &str) -> Result<JwkSet, AuthTokenError> {
 
     {
         let mut write = CACHE.write().await;
-        *write = Some(CacheEntry {
-            jwks: jwks.clone(),
-            expires_at: Instant::now() + Duration::from_secs(CACHE_TTL_SECS),
-        });
+        write.insert(
+            dir.to_string(),
+            CacheEntry {
+                jwks: jwks.clone(),
+                expires_at: Instant::now() + Duration::from_secs(CACHE_TTL_SECS),
+            },
+        );
     }
 
     Ok(jwks)
 }
+
+pub async fn invalidate(dir: &str) {
+    CACHE.write().await.remove(dir);
+}
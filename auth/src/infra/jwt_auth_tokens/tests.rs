@@ -0,0 +1,321 @@
+use std::{collections::HashSet, path::Path, sync::Arc};
+
+use async_trait::async_trait;
+use contracts::auth::{
+    error::{AuthError, AuthTokenError, TokenRevocationStoreError},
+    token::ValidateTokenInput,
+};
+use uuid::Uuid;
+
+use super::{JwtAuthTokens, key_set, read_key, read_public};
+use crate::{
+    entities::{claims::Claims, signing_key_set::SigningKeySet},
+    infra::memory::token_revocation_store::MemoryTokenRevocationStore,
+    ports::{for_auth_tokens::ForAuthTokens, token_revocation_store::TokenRevocationStore},
+    usecases::validate_token::ValidateTokenUseCase,
+};
+
+const MAX_TOKEN_LIFETIME: u64 = 3600;
+
+/// Adapter over a fresh keys directory holding one key without manifest, as a first install does.
+async fn tokens() -> JwtAuthTokens {
+    tokens_with(Arc::new(MemoryTokenRevocationStore::new())).await
+}
+
+async fn tokens_with(token_revocation_store: Arc<dyn TokenRevocationStore>) -> JwtAuthTokens {
+    let dir = std::env::temp_dir().join(format!("auth-keys-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).expect("keys directory should be created");
+    let dir = dir.to_string_lossy().into_owned();
+    key_set::generate(&dir, "k0").await.expect("initial key");
+    JwtAuthTokens::new(
+        "issuer".to_string(),
+        "audience".to_string(),
+        dir,
+        token_revocation_store,
+    )
+}
+
+async fn sign(tokens: &JwtAuthTokens) -> String {
+    sign_for(tokens, Uuid::new_v4()).await
+}
+
+async fn sign_for(tokens: &JwtAuthTokens, user_id: Uuid) -> String {
+    let claims = Claims::new("access", user_id.to_string(), 60);
+    tokens.create_token(claims).await.expect("token")
+}
+
+async fn validate(tokens: &JwtAuthTokens, token: &str) -> Result<Claims, AuthTokenError> {
+    tokens
+        .validate_token(token.to_string(), "access".to_string())
+        .await
+}
+
+async fn published_kids(tokens: &JwtAuthTokens) -> HashSet<String> {
+    let jwks: serde_json::Value =
+        serde_json::from_str(&tokens.get_jwks().await.expect("jwks")).expect("jwks json");
+    jwks["keys"]
+        .as_array()
+        .expect("keys array")
+        .iter()
+        .map(|key| key["kid"].as_str().expect("kid").to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn next_key_is_published_before_it_signs() {
+    let tokens = tokens().await;
+
+    let rotation = tokens
+        .rotate_keys(MAX_TOKEN_LIFETIME)
+        .await
+        .expect("rotation");
+    let next = rotation.key_set.next.clone().expect("staged key");
+    assert_eq!(rotation.key_set.active, "k0");
+    assert_eq!(
+        published_kids(&tokens).await,
+        HashSet::from(["k0".to_string(), next])
+    );
+
+    let token = sign(&tokens).await;
+    let header = jsonwebtoken::decode_header(&token).expect("header");
+    assert_eq!(header.kid.as_deref(), Some("k0"));
+    validate(&tokens, &token).await.expect("valid token");
+}
+
+#[tokio::test]
+async fn rotations_within_one_second_keep_older_tokens_valid() {
+    let tokens = tokens().await;
+    tokens
+        .rotate_keys(MAX_TOKEN_LIFETIME)
+        .await
+        .expect("rotation");
+    let promoted = tokens
+        .rotate_keys(MAX_TOKEN_LIFETIME)
+        .await
+        .expect("rotation");
+    let token = sign(&tokens).await;
+    let last = tokens
+        .rotate_keys(MAX_TOKEN_LIFETIME)
+        .await
+        .expect("rotation");
+
+    let kids: HashSet<_> = [
+        promoted.key_set.active.clone(),
+        last.key_set.active.clone(),
+        last.key_set.next.clone().expect("staged key"),
+    ]
+    .into();
+    assert_eq!(kids.len(), 3, "every rotation should stage a new kid");
+    assert!(
+        last.key_set
+            .retired
+            .iter()
+            .any(|key| key.kid == promoted.key_set.active)
+    );
+    validate(&tokens, &token)
+        .await
+        .expect("tokens of a retired key stay valid during the overlap window");
+}
+
+#[tokio::test]
+async fn retired_key_is_dropped_after_its_deadline() {
+    let tokens = tokens().await;
+    tokens
+        .rotate_keys(MAX_TOKEN_LIFETIME)
+        .await
+        .expect("rotation");
+    tokens
+        .rotate_keys(MAX_TOKEN_LIFETIME)
+        .await
+        .expect("rotation");
+    let token = sign(&tokens).await;
+    let rotation = tokens
+        .rotate_keys(MAX_TOKEN_LIFETIME)
+        .await
+        .expect("rotation");
+    let signer = kid(&token);
+
+    // Pretend the overlap window has passed.
+    let mut key_set = rotation.key_set;
+    for key in key_set.retired.iter_mut() {
+        key.verify_until = 1;
+    }
+    key_set::save(&tokens.keys_dir, &key_set)
+        .await
+        .expect("manifest");
+    read_key::invalidate(&tokens.keys_dir).await;
+    read_public::invalidate(&tokens.keys_dir).await;
+
+    assert!(!published_kids(&tokens).await.contains(&signer));
+    assert!(matches!(
+        validate(&tokens, &token).await,
+        Err(AuthTokenError::InvalidToken)
+    ));
+
+    let rotation = tokens
+        .rotate_keys(MAX_TOKEN_LIFETIME)
+        .await
+        .expect("rotation");
+    assert!(rotation.removed.contains(&signer));
+    assert!(
+        !Path::new(&tokens.keys_dir)
+            .join(format!("{}_public.pem", signer))
+            .exists()
+    );
+    assert!(
+        !Path::new(&tokens.keys_dir)
+            .join(format!("{}_key.pem", signer))
+            .exists()
+    );
+}
+
+#[tokio::test]
+async fn existing_key_files_are_not_overwritten() {
+    let tokens = tokens().await;
+    let path = Path::new(&tokens.keys_dir).join("k0_key.pem");
+    let before = std::fs::read(&path).expect("key file");
+
+    assert!(matches!(
+        key_set::generate(&tokens.keys_dir, "k0").await,
+        Err(AuthTokenError::KeyRotationFailure)
+    ));
+    assert_eq!(std::fs::read(&path).expect("key file"), before);
+}
+
+#[tokio::test]
+async fn logout_all_spares_tokens_issued_right_after_it() {
+    let store = Arc::new(MemoryTokenRevocationStore::new());
+    let tokens = tokens_with(store.clone()).await;
+    let user_id = Uuid::new_v4();
+
+    let before = sign_for(&tokens, user_id).await;
+    store.revoke_all(user_id).await.expect("logout all");
+    let after = sign_for(&tokens, user_id).await;
+
+    // Both tokens usually share the same `iat` second; only the generation tells them apart.
+    assert!(matches!(
+        validate(&tokens, &before).await,
+        Err(AuthTokenError::TokenRevoked)
+    ));
+    validate(&tokens, &after)
+        .await
+        .expect("token issued after logout all");
+    validate(&tokens, &sign(&tokens).await)
+        .await
+        .expect("other users keep their tokens");
+}
+
+/// Revocation store whose lookups fail, as when the database is down.
+struct UnreachableRevocationStore;
+
+#[async_trait]
+impl TokenRevocationStore for UnreachableRevocationStore {
+    async fn revoke(&self, _: &str, _: usize) -> Result<(), TokenRevocationStoreError> {
+        Err(TokenRevocationStoreError::StorageError("down".to_string()))
+    }
+
+    async fn revoke_all(&self, _: Uuid) -> Result<(), TokenRevocationStoreError> {
+        Err(TokenRevocationStoreError::StorageError("down".to_string()))
+    }
+
+    async fn generation(&self, _: Uuid) -> Result<u64, TokenRevocationStoreError> {
+        Ok(0)
+    }
+
+    async fn is_revoked(
+        &self,
+        _: &str,
+        _: Uuid,
+        _: u64,
+    ) -> Result<bool, TokenRevocationStoreError> {
+        Err(TokenRevocationStoreError::StorageError("down".to_string()))
+    }
+}
+
+async fn validate_through_use_case(tokens: JwtAuthTokens, rejected: &[String]) -> Vec<AuthError> {
+    let use_case = ValidateTokenUseCase::new(Arc::new(tokens));
+    let mut errors = Vec::new();
+    for token in rejected {
+        let result = use_case
+            .execute(ValidateTokenInput {
+                token: token.clone(),
+                token_type: "access".to_string(),
+            })
+            .await;
+        errors.push(result.expect_err("token should be rejected"));
+    }
+    errors
+}
+
+#[tokio::test]
+async fn rejected_tokens_fail_validation() {
+    let store = Arc::new(MemoryTokenRevocationStore::new());
+    let tokens = tokens_with(store.clone()).await;
+    let user_id = Uuid::new_v4();
+    let revoked = sign_for(&tokens, user_id).await;
+    store.revoke_all(user_id).await.expect("logout all");
+
+    let errors = validate_through_use_case(tokens, &[revoked, "not-a-token".to_string()]).await;
+    assert!(
+        errors
+            .iter()
+            .all(|err| matches!(err, AuthError::TokenValidationFailed))
+    );
+}
+
+#[tokio::test]
+async fn failed_revocation_check_is_an_internal_error() {
+    let tokens = tokens_with(Arc::new(UnreachableRevocationStore)).await;
+    let token = sign(&tokens).await;
+
+    let errors = validate_through_use_case(tokens, &[token]).await;
+    assert!(matches!(
+        errors[..],
+        [AuthError::InternalError(
+            AuthTokenError::RevocationCheckFailed
+        )]
+    ));
+}
+
+#[tokio::test]
+async fn unreadable_keys_are_an_internal_error() {
+    let tokens = tokens().await;
+    let token = sign(&tokens).await;
+    std::fs::remove_file(Path::new(&tokens.keys_dir).join("k0_public.pem"))
+        .expect("public key removed");
+    read_public::invalidate(&tokens.keys_dir).await;
+
+    let errors = validate_through_use_case(tokens, &[token]).await;
+    assert!(matches!(
+        errors[..],
+        [AuthError::InternalError(AuthTokenError::JwksFetchError)]
+    ));
+}
+
+#[test]
+fn retired_keys_verify_until_their_deadline() {
+    let mut key_set = SigningKeySet {
+        active: "a".to_string(),
+        next: None,
+        retired: Vec::new(),
+    };
+    key_set.rotate("b".to_string(), 100, 50);
+    assert_eq!(key_set.active, "a");
+    assert_eq!(key_set.verification_kids(100), ["a", "b"]);
+
+    key_set.rotate("c".to_string(), 100, 50);
+    assert_eq!(key_set.active, "b");
+    assert_eq!(key_set.verification_kids(149), ["b", "c", "a"]);
+    assert_eq!(key_set.verification_kids(150), ["b", "c"]);
+
+    assert!(key_set.prune(149).is_empty());
+    assert_eq!(key_set.prune(150), ["a"]);
+    assert!(key_set.retired.is_empty());
+}
+
+fn kid(token: &str) -> String {
+    jsonwebtoken::decode_header(token)
+        .expect("header")
+        .kid
+        .expect("kid")
+}
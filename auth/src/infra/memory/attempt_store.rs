@@ -0,0 +1,113 @@
+use super::sweep::SweepSchedule;
+use crate::{entities::failed_attempts::FailedAttempts, ports::attempt_store::AttemptStore};
+use async_trait::async_trait;
+use contracts::auth::error::AttemptStoreError;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+struct Entry {
+    attempts: FailedAttempts,
+    expires_at: u64,
+}
+
+#[derive(Default)]
+struct Entries {
+    map: HashMap<String, Entry>,
+    sweep: SweepSchedule,
+}
+
+pub struct MemoryAttemptStore {
+    entries: Mutex<Entries>,
+}
+
+impl MemoryAttemptStore {
+    pub fn new() -> Self {
+        Self {
+            entries: Mutex::new(Entries::default()),
+        }
+    }
+}
+
+fn poisoned<T>(e: T) -> AttemptStoreError
+where
+    T: std::fmt::Display,
+{
+    AttemptStoreError::StorageError(format!("Mutex poisoned: {}", e))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs()
+}
+
+#[async_trait]
+impl AttemptStore for MemoryAttemptStore {
+    async fn get(&self, key: &str) -> Result<Option<FailedAttempts>, AttemptStoreError> {
+        let entries = self.entries.lock().map_err(poisoned)?;
+        let now = now();
+        Ok(entries
+            .map
+            .get(key)
+            .filter(|entry| entry.expires_at > now)
+            .map(|entry| entry.attempts.clone()))
+    }
+
+    async fn record_failure(
+        &self,
+        key: &str,
+        at: u64,
+        expires_at: u64,
+    ) -> Result<FailedAttempts, AttemptStoreError> {
+        let mut entries = self.entries.lock().map_err(poisoned)?;
+        let now = now();
+        if entries.sweep.due(now) {
+            entries.map.retain(|_, entry| entry.expires_at > now);
+        }
+
+        let entry = entries.map.entry(key.to_string()).or_insert(Entry {
+            attempts: FailedAttempts::default(),
+            expires_at,
+        });
+        // An expired entry the sweep has not reached yet starts over.
+        if entry.expires_at <= at {
+            *entry = Entry {
+                attempts: FailedAttempts::default(),
+                expires_at,
+            };
+        }
+        entry.attempts.failures += 1;
+        entry.attempts.last_failure_at = at;
+        entry.expires_at = entry.expires_at.max(expires_at);
+
+        Ok(entry.attempts.clone())
+    }
+
+    async fn release(&self, key: &str) -> Result<(), AttemptStoreError> {
+        let mut entries = self.entries.lock().map_err(poisoned)?;
+        if let Some(entry) = entries.map.get_mut(key) {
+            entry.attempts.failures = entry.attempts.failures.saturating_sub(1);
+        }
+
+        Ok(())
+    }
+
+    async fn lock(&self, key: &str, until: u64) -> Result<(), AttemptStoreError> {
+        let mut entries = self.entries.lock().map_err(poisoned)?;
+        let entry = entries.map.entry(key.to_string()).or_insert(Entry {
+            attempts: FailedAttempts::default(),
+            expires_at: until,
+        });
+        entry.attempts.locked_until = Some(until);
+        entry.expires_at = entry.expires_at.max(until);
+
+        Ok(())
+    }
+
+    async fn clear(&self, key: &str) -> Result<(), AttemptStoreError> {
+        self.entries.lock().map_err(poisoned)?.map.remove(key);
+        Ok(())
+    }
+}
@@ -1,39 +1,152 @@
+use super::sweep::SweepSchedule;
 use crate::ports::hsm_store::HSMStore;
 use contracts::auth::error::HSMStoreError;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
+struct Entry {
+    value: String,
+    expires_at: Option<u64>,
+}
+
+impl Entry {
+    fn is_live(&self, now: u64) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at > now)
+    }
+}
+
+struct Entries {
+    map: HashMap<(Uuid, String), Entry>,
+    /// Entries of `map` that expire, live or not.
+    expiring: usize,
+    sweep: SweepSchedule,
+}
+
+impl Entries {
+    fn insert(&mut self, key: (Uuid, String), entry: Entry) {
+        self.expiring += usize::from(entry.expires_at.is_some());
+        if let Some(replaced) = self.map.insert(key, entry) {
+            self.expiring -= usize::from(replaced.expires_at.is_some());
+        }
+    }
+
+    fn remove(&mut self, key: &(Uuid, String)) -> Option<Entry> {
+        let removed = self.map.remove(key)?;
+        self.expiring -= usize::from(removed.expires_at.is_some());
+        Some(removed)
+    }
+
+    /// Drops the expired values, when a sweep is due.
+    fn sweep(&mut self, now: u64) {
+        if !self.sweep.due(now) {
+            return;
+        }
+        self.map.retain(|_, entry| entry.is_live(now));
+        self.expiring = self
+            .map
+            .values()
+            .filter(|entry| entry.expires_at.is_some())
+            .count();
+    }
+}
+
 pub struct MemoryHsmStore {
-    store: Arc<RwLock<HashMap<(Uuid, String), String>>>,
+    store: Arc<RwLock<Entries>>,
+    max_expiring: usize,
 }
 
 impl MemoryHsmStore {
+    #[cfg(test)]
     pub fn new() -> Self {
+        Self::with_max_expiring(usize::MAX)
+    }
+
+    /// Refuses expiring values once `max_expiring` of them are held, since anyone can start a
+    /// usernameless login and each one leaves a value behind until it expires.
+    pub fn with_max_expiring(max_expiring: usize) -> Self {
         Self {
-            store: Arc::new(RwLock::new(HashMap::new())),
+            store: Arc::new(RwLock::new(Entries {
+                map: HashMap::new(),
+                expiring: 0,
+                sweep: SweepSchedule::default(),
+            })),
+            max_expiring,
         }
     }
 }
 
+fn poisoned<T>(e: T) -> HSMStoreError
+where
+    T: std::fmt::Display,
+{
+    HSMStoreError::StorageError(format!("Mutex poisoned: {}", e))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs()
+}
+
 impl HSMStore for MemoryHsmStore {
     fn get(&self, user_id: Uuid, key: &str) -> Result<Option<String>, HSMStoreError> {
-        let map = self
-            .store
-            .read()
-            .map_err(|e| HSMStoreError::StorageError(format!("Mutex poisoned: {}", e)))?;
+        let entries = self.store.read().map_err(poisoned)?;
 
-        Ok(map.get(&(user_id, key.to_string())).cloned())
+        Ok(entries
+            .map
+            .get(&(user_id, key.to_string()))
+            .filter(|entry| entry.is_live(now()))
+            .map(|entry| entry.value.clone()))
     }
 
     fn set(&self, user_id: Uuid, key: &str, value: &str) -> Result<(), HSMStoreError> {
-        let mut map = self
-            .store
-            .write()
-            .map_err(|e| HSMStoreError::StorageError(format!("Mutex poisoned: {}", e)))?;
+        let mut entries = self.store.write().map_err(poisoned)?;
 
-        map.insert((user_id, key.to_string()), value.to_string());
+        entries.insert(
+            (user_id, key.to_string()),
+            Entry {
+                value: value.to_string(),
+                expires_at: None,
+            },
+        );
 
         Ok(())
     }
+
+    fn set_expiring(
+        &self,
+        id: Uuid,
+        key: &str,
+        value: &str,
+        expires_at: u64,
+    ) -> Result<(), HSMStoreError> {
+        let mut entries = self.store.write().map_err(poisoned)?;
+
+        entries.sweep(now());
+        let key = (id, key.to_string());
+        if entries.expiring >= self.max_expiring && !entries.map.contains_key(&key) {
+            return Err(HSMStoreError::Full);
+        }
+        entries.insert(
+            key,
+            Entry {
+                value: value.to_string(),
+                expires_at: Some(expires_at),
+            },
+        );
+
+        Ok(())
+    }
+
+    fn take(&self, id: Uuid, key: &str) -> Result<Option<String>, HSMStoreError> {
+        let mut entries = self.store.write().map_err(poisoned)?;
+
+        Ok(entries
+            .remove(&(id, key.to_string()))
+            .filter(|entry| entry.is_live(now()))
+            .map(|entry| entry.value))
+    }
 }
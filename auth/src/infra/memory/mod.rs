@@ -1,2 +1,6 @@
+pub mod attempt_store;
 pub mod hsm_store;
+pub mod refresh_token_store;
+mod sweep;
+pub mod token_revocation_store;
 pub mod user_repository;
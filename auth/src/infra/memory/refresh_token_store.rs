@@ -0,0 +1,104 @@
+use super::sweep::SweepSchedule;
+use crate::entities::refresh_token::RefreshTokenStatus;
+use crate::ports::refresh_token_store::RefreshTokenStore;
+use async_trait::async_trait;
+use contracts::auth::error::RefreshTokenStoreError;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+struct RefreshTokenEntry {
+    family_id: String,
+    expires_at: usize,
+    used: bool,
+}
+
+#[derive(Default)]
+struct Tokens {
+    map: HashMap<String, RefreshTokenEntry>,
+    sweep: SweepSchedule,
+}
+
+pub struct MemoryRefreshTokenStore {
+    tokens: Mutex<Tokens>,
+}
+
+impl MemoryRefreshTokenStore {
+    pub fn new() -> Self {
+        Self {
+            tokens: Mutex::new(Tokens::default()),
+        }
+    }
+}
+
+fn now() -> usize {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs() as usize
+}
+
+#[async_trait]
+impl RefreshTokenStore for MemoryRefreshTokenStore {
+    async fn register(
+        &self,
+        jti: &str,
+        family_id: &str,
+        expires_at: usize,
+    ) -> Result<(), RefreshTokenStoreError> {
+        let mut tokens = self
+            .tokens
+            .lock()
+            .map_err(|e| RefreshTokenStoreError::StorageError(format!("Mutex poisoned: {}", e)))?;
+
+        let now = now();
+        if tokens.sweep.due(now as u64) {
+            tokens.map.retain(|_, entry| entry.expires_at > now);
+        }
+        tokens.map.insert(
+            jti.to_string(),
+            RefreshTokenEntry {
+                family_id: family_id.to_string(),
+                expires_at,
+                used: false,
+            },
+        );
+
+        Ok(())
+    }
+
+    async fn consume(&self, jti: &str) -> Result<RefreshTokenStatus, RefreshTokenStoreError> {
+        let mut tokens = self
+            .tokens
+            .lock()
+            .map_err(|e| RefreshTokenStoreError::StorageError(format!("Mutex poisoned: {}", e)))?;
+
+        let Some(entry) = tokens.map.get_mut(jti) else {
+            return Ok(RefreshTokenStatus::Unknown);
+        };
+        if entry.expires_at <= now() {
+            return Ok(RefreshTokenStatus::Unknown);
+        }
+        if entry.used {
+            return Ok(RefreshTokenStatus::Reused {
+                family_id: entry.family_id.clone(),
+            });
+        }
+
+        entry.used = true;
+        Ok(RefreshTokenStatus::Valid {
+            family_id: entry.family_id.clone(),
+        })
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<(), RefreshTokenStoreError> {
+        let mut tokens = self
+            .tokens
+            .lock()
+            .map_err(|e| RefreshTokenStoreError::StorageError(format!("Mutex poisoned: {}", e)))?;
+
+        tokens.map.retain(|_, entry| entry.family_id != family_id);
+
+        Ok(())
+    }
+}
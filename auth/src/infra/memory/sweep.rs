@@ -0,0 +1,17 @@
+/// When expired entries are next swept out of a map: at most once a second, so writes stay cheap
+/// however many entries are held.
+#[derive(Default)]
+pub struct SweepSchedule {
+    next: u64,
+}
+
+impl SweepSchedule {
+    /// Whether a sweep is due at `now`; if so the next one is a second away.
+    pub fn due(&mut self, now: u64) -> bool {
+        if now < self.next {
+            return false;
+        }
+        self.next = now + 1;
+        true
+    }
+}
@@ -0,0 +1,69 @@
+use crate::ports::token_revocation_store::TokenRevocationStore;
+use async_trait::async_trait;
+use contracts::auth::error::TokenRevocationStoreError;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+pub struct MemoryTokenRevocationStore {
+    tokens: Mutex<HashMap<String, usize>>,
+    generations: Mutex<HashMap<Uuid, u64>>,
+}
+
+impl MemoryTokenRevocationStore {
+    pub fn new() -> Self {
+        Self {
+            tokens: Mutex::new(HashMap::new()),
+            generations: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+fn poisoned<T>(e: T) -> TokenRevocationStoreError
+where
+    T: std::fmt::Display,
+{
+    TokenRevocationStoreError::StorageError(format!("Mutex poisoned: {}", e))
+}
+
+#[async_trait]
+impl TokenRevocationStore for MemoryTokenRevocationStore {
+    async fn revoke(&self, jti: &str, expires_at: usize) -> Result<(), TokenRevocationStoreError> {
+        let mut tokens = self.tokens.lock().map_err(poisoned)?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should go forward")
+            .as_secs() as usize;
+        tokens.retain(|_, exp| *exp > now);
+        tokens.insert(jti.to_string(), expires_at);
+
+        Ok(())
+    }
+
+    async fn revoke_all(&self, user_id: Uuid) -> Result<(), TokenRevocationStoreError> {
+        let mut generations = self.generations.lock().map_err(poisoned)?;
+        *generations.entry(user_id).or_insert(0) += 1;
+
+        Ok(())
+    }
+
+    async fn generation(&self, user_id: Uuid) -> Result<u64, TokenRevocationStoreError> {
+        let generations = self.generations.lock().map_err(poisoned)?;
+        Ok(generations.get(&user_id).copied().unwrap_or(0))
+    }
+
+    async fn is_revoked(
+        &self,
+        jti: &str,
+        user_id: Uuid,
+        generation: u64,
+    ) -> Result<bool, TokenRevocationStoreError> {
+        if self.tokens.lock().map_err(poisoned)?.contains_key(jti) {
+            return Ok(true);
+        }
+
+        Ok(self.generation(user_id).await? > generation)
+    }
+}
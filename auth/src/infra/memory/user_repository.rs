@@ -1,4 +1,4 @@
-use crate::entities::user::User;
+use crate::entities::{totp_credential::TotpCredential, user::User};
 use crate::ports::user_repository::UserRepository;
 use async_trait::async_trait;
 use contracts::auth::error::UserRepositoryError;
@@ -20,12 +20,48 @@ impl MemoryUserRepository {
 
 #[async_trait]
 impl UserRepository for MemoryUserRepository {
-    async fn save(&self, user: User) -> Result<(), UserRepositoryError> {
+    async fn create(&self, user: User) -> Result<(), UserRepositoryError> {
         let mut repositories = self
             .users
             .lock()
             .map_err(|e| UserRepositoryError::ConnectionError(format!("Mutex poisoned: {}", e)))?;
-        repositories.insert(user.username.clone(), user.clone());
+        if repositories.contains_key(&user.username) {
+            return Err(UserRepositoryError::Conflict(format!(
+                "username {} already exists",
+                user.username
+            )));
+        }
+        repositories.insert(user.username.clone(), user);
+        Ok(())
+    }
+
+    async fn update(&self, user: User) -> Result<(), UserRepositoryError> {
+        let mut repositories = self
+            .users
+            .lock()
+            .map_err(|e| UserRepositoryError::ConnectionError(format!("Mutex poisoned: {}", e)))?;
+        let current = repositories
+            .values()
+            .find(|existing| existing.id == user.id)
+            .ok_or(UserRepositoryError::NotFound)?;
+        if current.version != user.version {
+            return Err(UserRepositoryError::Stale);
+        }
+        let current_username = current.username.clone();
+        if current_username != user.username && repositories.contains_key(&user.username) {
+            return Err(UserRepositoryError::Conflict(format!(
+                "username {} already exists",
+                user.username
+            )));
+        }
+        repositories.remove(&current_username);
+        repositories.insert(
+            user.username.clone(),
+            User {
+                version: user.version + 1,
+                ..user
+            },
+        );
         Ok(())
     }
 
@@ -49,4 +85,72 @@ impl UserRepository for MemoryUserRepository {
         }
         Ok(None)
     }
+
+    async fn record_totp_use(
+        &self,
+        id: uuid::Uuid,
+        credential: &TotpCredential,
+        last_step: Option<u64>,
+    ) -> Result<bool, UserRepositoryError> {
+        let mut repositories = self
+            .users
+            .lock()
+            .map_err(|e| UserRepositoryError::ConnectionError(format!("Mutex poisoned: {}", e)))?;
+        let Some(user) = repositories.values_mut().find(|user| user.id == id) else {
+            return Ok(false);
+        };
+        match user
+            .totp_credentials
+            .iter_mut()
+            .find(|stored| stored.secret == credential.secret)
+        {
+            Some(stored) if stored.last_step == last_step => {
+                *stored = credential.clone();
+                user.version += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        id: uuid::Uuid,
+        code_hash: &str,
+    ) -> Result<bool, UserRepositoryError> {
+        let mut repositories = self
+            .users
+            .lock()
+            .map_err(|e| UserRepositoryError::ConnectionError(format!("Mutex poisoned: {}", e)))?;
+        let Some(user) = repositories.values_mut().find(|user| user.id == id) else {
+            return Ok(false);
+        };
+        let Some(index) = user.recovery_codes.iter().position(|c| c == code_hash) else {
+            return Ok(false);
+        };
+        user.recovery_codes.swap_remove(index);
+        user.version += 1;
+        Ok(true)
+    }
+
+    async fn update_password_hash(
+        &self,
+        id: uuid::Uuid,
+        old_hash: &str,
+        new_hash: &str,
+    ) -> Result<bool, UserRepositoryError> {
+        let mut repositories = self
+            .users
+            .lock()
+            .map_err(|e| UserRepositoryError::ConnectionError(format!("Mutex poisoned: {}", e)))?;
+        let Some(user) = repositories
+            .values_mut()
+            .find(|user| user.id == id && user.password == old_hash)
+        else {
+            return Ok(false);
+        };
+        user.password = new_hash.to_string();
+        user.version += 1;
+        Ok(true)
+    }
 }
@@ -1,3 +1,12 @@
+pub mod breached_passwords;
 pub mod jwt_auth_tokens;
 pub mod memory;
+pub mod password_hasher;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(test)]
+pub(crate) mod tests;
 pub mod totp;
+pub mod webauthn_passkeys;
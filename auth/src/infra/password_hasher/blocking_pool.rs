@@ -0,0 +1,77 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use contracts::auth::error::PasswordHasherError;
+use tokio::sync::Semaphore;
+
+/// Runs blocking jobs on tokio's blocking threads, at most `workers` at a time. Jobs beyond that
+/// wait for a free worker, up to `queue_limit` of them; past it `run` fails with `Busy` instead
+/// of letting the backlog, and the latency of every caller, grow without bound.
+pub struct BlockingPool {
+    workers: Arc<Semaphore>,
+    queued: AtomicUsize,
+    queue_limit: usize,
+}
+
+impl BlockingPool {
+    pub fn new(workers: usize, queue_limit: usize) -> Self {
+        Self {
+            workers: Arc::new(Semaphore::new(workers)),
+            queued: AtomicUsize::new(0),
+            queue_limit,
+        }
+    }
+
+    pub async fn run<T, F>(&self, job: F) -> Result<T, PasswordHasherError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let permit = match self.workers.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let _queued = QueueSlot::take(self)?;
+                self.workers
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .map_err(|err| PasswordHasherError::HashingFailed(err.to_string()))?
+            }
+        };
+
+        // The permit moves into the job, so a worker stays taken until the job ends even if the
+        // caller stops waiting for it.
+        tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            job()
+        })
+        .await
+        .map_err(|err| PasswordHasherError::HashingFailed(err.to_string()))
+    }
+}
+
+/// Place in the queue, given back when dropped, including when the waiting caller is cancelled.
+struct QueueSlot<'a> {
+    queued: &'a AtomicUsize,
+}
+
+impl<'a> QueueSlot<'a> {
+    fn take(pool: &'a BlockingPool) -> Result<Self, PasswordHasherError> {
+        pool.queued
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |queued| {
+                (queued < pool.queue_limit).then_some(queued + 1)
+            })
+            .map_err(|_| PasswordHasherError::Busy)?;
+        Ok(Self {
+            queued: &pool.queued,
+        })
+    }
+}
+
+impl Drop for QueueSlot<'_> {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(1, Ordering::AcqRel);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{
+    config::{ConfigError, PasswordAlgorithm, PasswordHashingConfig},
+    ports::password_hasher::PasswordHasher,
+};
+use argon2::{
+    Argon2, PasswordHasher as _, PasswordVerifier as _,
+    password_hash::{self, PasswordHash, SaltString, rand_core::OsRng},
+};
+use async_trait::async_trait;
+use blocking_pool::BlockingPool;
+use contracts::auth::error::PasswordHasherError;
+use std::sync::Arc;
+
+mod blocking_pool;
+#[cfg(test)]
+pub(crate) mod tests;
+
+/// Hashes in PHC format: `$argon2id$...`, or the `$2b$...` format of bcrypt. Verifies either,
+/// with the parameters embedded in the stored hash.
+pub struct PhcPasswordHasher {
+    algorithm: PasswordAlgorithm,
+    argon2: Arc<Argon2<'static>>,
+    bcrypt_cost: u32,
+    legacy_bcrypt_hashes: bool,
+    pool: BlockingPool,
+}
+
+impl PhcPasswordHasher {
+    pub fn new(config: PasswordHashingConfig) -> Result<Self, ConfigError> {
+        config.validate()?;
+        Ok(Self {
+            algorithm: config.algorithm,
+            argon2: Arc::new(Argon2::new(
+                argon2::Algorithm::Argon2id,
+                argon2::Version::V0x13,
+                config.argon2_params()?,
+            )),
+            bcrypt_cost: config.bcrypt_cost,
+            legacy_bcrypt_hashes: config.legacy_bcrypt_hashes,
+            pool: BlockingPool::new(config.workers, config.queue_limit),
+        })
+    }
+}
+
+fn hashing_failed(err: impl std::fmt::Display) -> PasswordHasherError {
+    PasswordHasherError::HashingFailed(err.to_string())
+}
+
+fn is_bcrypt(hash: &str) -> bool {
+    ["$2a$", "$2b$", "$2x$", "$2y$"]
+        .iter()
+        .any(|prefix| hash.starts_with(prefix))
+}
+
+impl PhcPasswordHasher {
+    async fn hash_with(
+        &self,
+        algorithm: PasswordAlgorithm,
+        password: String,
+    ) -> Result<String, PasswordHasherError> {
+        match algorithm {
+            PasswordAlgorithm::Argon2id => {
+                let argon2 = self.argon2.clone();
+                self.pool
+                    .run(move || {
+                        let salt = SaltString::generate(&mut OsRng);
+                        argon2
+                            .hash_password(password.as_bytes(), &salt)
+                            .map(|hash| hash.to_string())
+                            .map_err(hashing_failed)
+                    })
+                    .await?
+            }
+            PasswordAlgorithm::Bcrypt => {
+                let cost = self.bcrypt_cost;
+                self.pool
+                    .run(move || bcrypt::hash(password, cost).map_err(hashing_failed))
+                    .await?
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl PasswordHasher for PhcPasswordHasher {
+    async fn hash(&self, password: String) -> Result<String, PasswordHasherError> {
+        self.hash_with(self.algorithm, password).await
+    }
+
+    async fn verify(&self, password: String, hash: String) -> Result<bool, PasswordHasherError> {
+        if is_bcrypt(&hash) {
+            return self
+                .pool
+                .run(move || bcrypt::verify(password, &hash).map_err(hashing_failed))
+                .await?;
+        }
+
+        let argon2 = self.argon2.clone();
+        self.pool
+            .run(move || {
+                let parsed = PasswordHash::new(&hash).map_err(hashing_failed)?;
+                match argon2.verify_password(password.as_bytes(), &parsed) {
+                    Ok(()) => Ok(true),
+                    Err(password_hash::Error::Password) => Ok(false),
+                    Err(err) => Err(hashing_failed(err)),
+                }
+            })
+            .await?
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        match self.algorithm {
+            PasswordAlgorithm::Argon2id => {
+                let Ok(parsed) = PasswordHash::new(hash) else {
+                    return true;
+                };
+                let params = self.argon2.params();
+                // Parsed parameters carry the length of the stored output, while the configured
+                // ones leave it unset for the default.
+                let output_len = |params: &argon2::Params| {
+                    params
+                        .output_len()
+                        .unwrap_or(argon2::Params::DEFAULT_OUTPUT_LEN)
+                };
+                parsed.algorithm != argon2::Algorithm::Argon2id.ident()
+                    || parsed.version != Some(argon2::Version::V0x13.into())
+                    || argon2::Params::try_from(&parsed).map_or(true, |stored| {
+                        stored.m_cost() != params.m_cost()
+                            || stored.t_cost() != params.t_cost()
+                            || stored.p_cost() != params.p_cost()
+                            || output_len(&stored) != output_len(params)
+                    })
+            }
+            PasswordAlgorithm::Bcrypt => {
+                !is_bcrypt(hash)
+                    || hash.get(4..6).and_then(|cost| cost.parse::<u32>().ok())
+                        != Some(self.bcrypt_cost)
+            }
+        }
+    }
+
+    async fn dummy_hashes(&self) -> Result<Vec<String>, PasswordHasherError> {
+        let mut algorithms = vec![self.algorithm];
+        if self.legacy_bcrypt_hashes && self.algorithm != PasswordAlgorithm::Bcrypt {
+            algorithms.push(PasswordAlgorithm::Bcrypt);
+        }
+        let mut hashes = Vec::new();
+        for algorithm in algorithms {
+            hashes.push(
+                self.hash_with(algorithm, "dummy password".to_string())
+                    .await?,
+            );
+        }
+        Ok(hashes)
+    }
+
+    fn same_algorithm(&self, a: &str, b: &str) -> bool {
+        is_bcrypt(a) == is_bcrypt(b)
+    }
+}
@@ -0,0 +1,98 @@
+use std::sync::{Arc, mpsc};
+
+use contracts::auth::error::PasswordHasherError;
+use tokio::sync::oneshot;
+
+use super::{PhcPasswordHasher, blocking_pool::BlockingPool};
+use crate::{
+    config::{PasswordAlgorithm, PasswordHashingConfig},
+    ports::password_hasher::PasswordHasher,
+};
+
+const PASSWORD: &str = "Password123!";
+
+/// Cheap parameters, so the tests do not spend their time hashing.
+pub(crate) fn hashing_config(algorithm: PasswordAlgorithm, cost: u32) -> PasswordHashingConfig {
+    PasswordHashingConfig {
+        algorithm,
+        argon2_memory: 8 * cost,
+        argon2_iterations: cost,
+        bcrypt_cost: 3 + cost,
+        ..PasswordHashingConfig::default()
+    }
+}
+
+fn hasher(algorithm: PasswordAlgorithm, cost: u32) -> PhcPasswordHasher {
+    PhcPasswordHasher::new(hashing_config(algorithm, cost)).unwrap()
+}
+
+#[tokio::test]
+async fn bcrypt_hashes_are_upgraded_to_argon2id() {
+    let bcrypt = hasher(PasswordAlgorithm::Bcrypt, 1);
+    let argon2 = hasher(PasswordAlgorithm::Argon2id, 1);
+    let hash = bcrypt.hash(PASSWORD.to_string()).await.unwrap();
+
+    assert!(!bcrypt.needs_rehash(&hash));
+    assert!(argon2.needs_rehash(&hash));
+    assert!(argon2.verify(PASSWORD.to_string(), hash).await.unwrap());
+}
+
+#[tokio::test]
+async fn hashes_with_outdated_parameters_are_upgraded() {
+    let hash = hasher(PasswordAlgorithm::Argon2id, 1)
+        .hash(PASSWORD.to_string())
+        .await
+        .unwrap();
+    assert!(hasher(PasswordAlgorithm::Argon2id, 2).needs_rehash(&hash));
+
+    let hash = hasher(PasswordAlgorithm::Bcrypt, 1)
+        .hash(PASSWORD.to_string())
+        .await
+        .unwrap();
+    assert!(hasher(PasswordAlgorithm::Bcrypt, 2).needs_rehash(&hash));
+}
+
+#[tokio::test]
+async fn current_hashes_are_left_alone() {
+    for algorithm in [PasswordAlgorithm::Argon2id, PasswordAlgorithm::Bcrypt] {
+        let hasher = hasher(algorithm, 1);
+        let hash = hasher.hash(PASSWORD.to_string()).await.unwrap();
+        assert!(!hasher.needs_rehash(&hash), "{}", hash);
+    }
+}
+
+/// With every worker taken and the queue full, the next job is turned away at once; the queued
+/// one still runs once the worker frees up.
+#[tokio::test]
+async fn a_full_pool_is_busy() {
+    let pool = Arc::new(BlockingPool::new(1, 1));
+    let (started_tx, started) = oneshot::channel();
+    let (release, release_rx) = mpsc::channel::<()>();
+
+    let running = tokio::spawn({
+        let pool = pool.clone();
+        async move {
+            pool.run(move || {
+                started_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            })
+            .await
+        }
+    });
+    started.await.unwrap();
+    let queued = tokio::spawn({
+        let pool = pool.clone();
+        async move { pool.run(|| "queued").await }
+    });
+    // Lets the queued job take its place in the queue.
+    tokio::task::yield_now().await;
+
+    assert!(matches!(
+        pool.run(|| "rejected").await,
+        Err(PasswordHasherError::Busy)
+    ));
+
+    release.send(()).unwrap();
+    running.await.unwrap().unwrap();
+    assert_eq!(queued.await.unwrap().unwrap(), "queued");
+}
@@ -0,0 +1,121 @@
+use crate::{entities::failed_attempts::FailedAttempts, ports::attempt_store::AttemptStore};
+use async_trait::async_trait;
+use contracts::auth::error::AttemptStoreError;
+use sqlx::Row;
+use sqlx::postgres::{PgPool, PgRow};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct PostgresAttemptStore {
+    pool: PgPool,
+}
+
+impl PostgresAttemptStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_sqlx_error(err: sqlx::Error) -> AttemptStoreError {
+    AttemptStoreError::StorageError(err.to_string())
+}
+
+fn attempts_from_row(row: &PgRow) -> Result<FailedAttempts, AttemptStoreError> {
+    let failures: i32 = row.try_get("failures").map_err(map_sqlx_error)?;
+    let last_failure_at: i64 = row.try_get("last_failure_at").map_err(map_sqlx_error)?;
+    let locked_until: Option<i64> = row.try_get("locked_until").map_err(map_sqlx_error)?;
+
+    Ok(FailedAttempts {
+        failures: failures as u32,
+        last_failure_at: last_failure_at as u64,
+        locked_until: locked_until.map(|until| until as u64),
+    })
+}
+
+#[async_trait]
+impl AttemptStore for PostgresAttemptStore {
+    async fn get(&self, key: &str) -> Result<Option<FailedAttempts>, AttemptStoreError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should go forward")
+            .as_secs() as i64;
+        let row = sqlx::query(
+            "SELECT failures, last_failure_at, locked_until FROM failed_attempts
+             WHERE attempt_key = $1 AND expires_at > $2",
+        )
+        .bind(key)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        row.as_ref().map(attempts_from_row).transpose()
+    }
+
+    async fn record_failure(
+        &self,
+        key: &str,
+        at: u64,
+        expires_at: u64,
+    ) -> Result<FailedAttempts, AttemptStoreError> {
+        sqlx::query("DELETE FROM failed_attempts WHERE expires_at <= $1")
+            .bind(at as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(map_sqlx_error)?;
+
+        let row = sqlx::query(
+            "INSERT INTO failed_attempts (attempt_key, failures, last_failure_at, expires_at)
+             VALUES ($1, 1, $2, $3)
+             ON CONFLICT (attempt_key) DO UPDATE
+             SET failures = failed_attempts.failures + 1,
+                 last_failure_at = excluded.last_failure_at,
+                 expires_at = GREATEST(failed_attempts.expires_at, excluded.expires_at)
+             RETURNING failures, last_failure_at, locked_until",
+        )
+        .bind(key)
+        .bind(at as i64)
+        .bind(expires_at as i64)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        attempts_from_row(&row)
+    }
+
+    async fn release(&self, key: &str) -> Result<(), AttemptStoreError> {
+        sqlx::query(
+            "UPDATE failed_attempts SET failures = GREATEST(failures - 1, 0) WHERE attempt_key = $1",
+        )
+        .bind(key)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn lock(&self, key: &str, until: u64) -> Result<(), AttemptStoreError> {
+        sqlx::query(
+            "UPDATE failed_attempts
+             SET locked_until = $2, expires_at = GREATEST(expires_at, $2)
+             WHERE attempt_key = $1",
+        )
+        .bind(key)
+        .bind(until as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn clear(&self, key: &str) -> Result<(), AttemptStoreError> {
+        sqlx::query("DELETE FROM failed_attempts WHERE attempt_key = $1")
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+}
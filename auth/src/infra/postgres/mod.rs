@@ -0,0 +1,25 @@
+use contracts::auth::error::UserRepositoryError;
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+pub mod attempt_store;
+pub mod refresh_token_store;
+pub mod token_revocation_store;
+pub mod user_repository;
+
+const MAX_CONNECTIONS: u32 = 10;
+
+/// Connects to the database and applies the migrations.
+pub async fn connect(url: &str) -> Result<PgPool, UserRepositoryError> {
+    let pool = PgPoolOptions::new()
+        .max_connections(MAX_CONNECTIONS)
+        .connect(url)
+        .await
+        .map_err(|e| UserRepositoryError::ConnectionError(e.to_string()))?;
+
+    sqlx::migrate!("./migrations/postgres")
+        .run(&pool)
+        .await
+        .map_err(|e| UserRepositoryError::ConnectionError(format!("Migration failed: {}", e)))?;
+
+    Ok(pool)
+}
@@ -0,0 +1,90 @@
+use crate::ports::token_revocation_store::TokenRevocationStore;
+use async_trait::async_trait;
+use contracts::auth::error::TokenRevocationStoreError;
+use sqlx::postgres::PgPool;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+pub struct PostgresTokenRevocationStore {
+    pool: PgPool,
+}
+
+impl PostgresTokenRevocationStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_sqlx_error(err: sqlx::Error) -> TokenRevocationStoreError {
+    TokenRevocationStoreError::StorageError(err.to_string())
+}
+
+#[async_trait]
+impl TokenRevocationStore for PostgresTokenRevocationStore {
+    async fn revoke(&self, jti: &str, expires_at: usize) -> Result<(), TokenRevocationStoreError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should go forward")
+            .as_secs() as i64;
+        sqlx::query("DELETE FROM revoked_tokens WHERE expires_at <= $1")
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(map_sqlx_error)?;
+
+        sqlx::query(
+            "INSERT INTO revoked_tokens (jti, expires_at) VALUES ($1, $2)
+             ON CONFLICT (jti) DO NOTHING",
+        )
+        .bind(jti)
+        .bind(expires_at as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn revoke_all(&self, user_id: Uuid) -> Result<(), TokenRevocationStoreError> {
+        sqlx::query(
+            "INSERT INTO user_token_revocations (user_id, generation) VALUES ($1, 1)
+             ON CONFLICT (user_id) DO UPDATE SET generation = user_token_revocations.generation + 1",
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn generation(&self, user_id: Uuid) -> Result<u64, TokenRevocationStoreError> {
+        let generation: Option<i64> =
+            sqlx::query_scalar("SELECT generation FROM user_token_revocations WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(map_sqlx_error)?;
+
+        Ok(generation.unwrap_or(0) as u64)
+    }
+
+    async fn is_revoked(
+        &self,
+        jti: &str,
+        user_id: Uuid,
+        generation: u64,
+    ) -> Result<bool, TokenRevocationStoreError> {
+        sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM revoked_tokens WHERE jti = $1)
+                 OR EXISTS (SELECT 1 FROM user_token_revocations
+                            WHERE user_id = $2 AND generation > $3)",
+        )
+        .bind(jti)
+        .bind(user_id)
+        .bind(generation as i64)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(map_sqlx_error)
+    }
+}
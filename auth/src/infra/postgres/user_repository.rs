@@ -0,0 +1,208 @@
+use crate::entities::{
+    passkey_credential::PasskeyCredential, totp_credential::TotpCredential, user::User,
+};
+use crate::ports::user_repository::UserRepository;
+use async_trait::async_trait;
+use contracts::auth::error::UserRepositoryError;
+use sqlx::Row;
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::types::Json;
+use uuid::Uuid;
+
+pub struct PostgresUserRepository {
+    pool: PgPool,
+}
+
+impl PostgresUserRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+fn user_from_row(row: &PgRow) -> Result<User, UserRepositoryError> {
+    let totp_credentials: Json<Vec<TotpCredential>> =
+        row.try_get("totp_credentials").map_err(map_sqlx_error)?;
+    let pass_keys: Json<Vec<PasskeyCredential>> =
+        row.try_get("pass_keys").map_err(map_sqlx_error)?;
+    let recovery_codes: Json<Vec<String>> =
+        row.try_get("recovery_codes").map_err(map_sqlx_error)?;
+
+    Ok(User {
+        id: row.try_get("id").map_err(map_sqlx_error)?,
+        name: row.try_get("name").map_err(map_sqlx_error)?,
+        username: row.try_get("username").map_err(map_sqlx_error)?,
+        password: row.try_get("password").map_err(map_sqlx_error)?,
+        totp_credentials: totp_credentials.0,
+        pass_keys: pass_keys.0,
+        recovery_codes: recovery_codes.0,
+        version: row.try_get::<i64, _>("version").map_err(map_sqlx_error)? as u64,
+    })
+}
+
+fn map_sqlx_error(err: sqlx::Error) -> UserRepositoryError {
+    match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            UserRepositoryError::Conflict(db_err.message().to_string())
+        }
+        sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => {
+            UserRepositoryError::SerializationError(err.to_string())
+        }
+        _ => UserRepositoryError::ConnectionError(err.to_string()),
+    }
+}
+
+#[async_trait]
+impl UserRepository for PostgresUserRepository {
+    async fn create(&self, user: User) -> Result<(), UserRepositoryError> {
+        sqlx::query(
+            "INSERT INTO users (id, name, username, password, totp_credentials, pass_keys,
+             recovery_codes)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(user.id)
+        .bind(&user.name)
+        .bind(&user.username)
+        .bind(&user.password)
+        .bind(Json(&user.totp_credentials))
+        .bind(Json(&user.pass_keys))
+        .bind(Json(&user.recovery_codes))
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn update(&self, user: User) -> Result<(), UserRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE users SET name = $2, username = $3, password = $4, totp_credentials = $5,
+             pass_keys = $6, recovery_codes = $7, version = version + 1
+             WHERE id = $1 AND version = $8",
+        )
+        .bind(user.id)
+        .bind(&user.name)
+        .bind(&user.username)
+        .bind(&user.password)
+        .bind(Json(&user.totp_credentials))
+        .bind(Json(&user.pass_keys))
+        .bind(Json(&user.recovery_codes))
+        .bind(user.version as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            let exists = sqlx::query("SELECT 1 FROM users WHERE id = $1")
+                .bind(user.id)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(map_sqlx_error)?
+                .is_some();
+            return Err(if exists {
+                UserRepositoryError::Stale
+            } else {
+                UserRepositoryError::NotFound
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn find_username(&self, username: String) -> Result<Option<User>, UserRepositoryError> {
+        let row = sqlx::query(
+            "SELECT id, name, username, password, totp_credentials, pass_keys, recovery_codes,
+             version
+             FROM users WHERE username = $1",
+        )
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        row.as_ref().map(user_from_row).transpose()
+    }
+
+    async fn find_id(&self, id: Uuid) -> Result<Option<User>, UserRepositoryError> {
+        let row = sqlx::query(
+            "SELECT id, name, username, password, totp_credentials, pass_keys, recovery_codes,
+             version
+             FROM users WHERE id = $1",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        row.as_ref().map(user_from_row).transpose()
+    }
+
+    async fn record_totp_use(
+        &self,
+        id: Uuid,
+        credential: &TotpCredential,
+        last_step: Option<u64>,
+    ) -> Result<bool, UserRepositoryError> {
+        // Credentials are matched by secret, like the SQLite adapter does.
+        let result = sqlx::query(
+            "UPDATE users
+             SET totp_credentials = (
+                 SELECT jsonb_agg(CASE WHEN c->>'secret' = $2 THEN $3 ELSE c END ORDER BY i)
+                 FROM jsonb_array_elements(totp_credentials) WITH ORDINALITY AS t(c, i)
+             ),
+             version = version + 1
+             WHERE id = $1
+               AND EXISTS (
+                   SELECT 1 FROM jsonb_array_elements(totp_credentials) AS c
+                   WHERE c->>'secret' = $2
+                     AND (c->>'last_step')::bigint IS NOT DISTINCT FROM $4
+               )",
+        )
+        .bind(id)
+        .bind(&credential.secret)
+        .bind(Json(credential))
+        .bind(last_step.map(|step| step as i64))
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, UserRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE users SET recovery_codes = recovery_codes - $2::text, version = version + 1
+             WHERE id = $1 AND recovery_codes @> jsonb_build_array($2::text)",
+        )
+        .bind(id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update_password_hash(
+        &self,
+        id: Uuid,
+        old_hash: &str,
+        new_hash: &str,
+    ) -> Result<bool, UserRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE users SET password = $3, version = version + 1
+             WHERE id = $1 AND password = $2",
+        )
+        .bind(id)
+        .bind(old_hash)
+        .bind(new_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,27 @@
+use contracts::auth::error::UserRepositoryError;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use std::str::FromStr;
+
+pub mod attempt_store;
+pub mod refresh_token_store;
+pub mod token_revocation_store;
+pub mod user_repository;
+
+/// Opens the database (creating the file if needed) and applies the migrations.
+pub async fn connect(url: &str) -> Result<SqlitePool, UserRepositoryError> {
+    let options = SqliteConnectOptions::from_str(url)
+        .map_err(|e| UserRepositoryError::ConnectionError(format!("Invalid URL: {}", e)))?
+        .create_if_missing(true);
+
+    let pool = SqlitePoolOptions::new()
+        .connect_with(options)
+        .await
+        .map_err(|e| UserRepositoryError::ConnectionError(e.to_string()))?;
+
+    sqlx::migrate!("./migrations/sqlite")
+        .run(&pool)
+        .await
+        .map_err(|e| UserRepositoryError::ConnectionError(format!("Migration failed: {}", e)))?;
+
+    Ok(pool)
+}
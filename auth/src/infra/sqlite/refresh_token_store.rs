@@ -0,0 +1,95 @@
+use crate::entities::refresh_token::RefreshTokenStatus;
+use crate::ports::refresh_token_store::RefreshTokenStore;
+use async_trait::async_trait;
+use contracts::auth::error::RefreshTokenStoreError;
+use sqlx::sqlite::SqlitePool;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub struct SqliteRefreshTokenStore {
+    pool: SqlitePool,
+}
+
+impl SqliteRefreshTokenStore {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+fn map_sqlx_error(err: sqlx::Error) -> RefreshTokenStoreError {
+    RefreshTokenStoreError::StorageError(err.to_string())
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs() as i64
+}
+
+#[async_trait]
+impl RefreshTokenStore for SqliteRefreshTokenStore {
+    async fn register(
+        &self,
+        jti: &str,
+        family_id: &str,
+        expires_at: usize,
+    ) -> Result<(), RefreshTokenStoreError> {
+        sqlx::query("DELETE FROM refresh_tokens WHERE expires_at <= ?")
+            .bind(now())
+            .execute(&self.pool)
+            .await
+            .map_err(map_sqlx_error)?;
+
+        sqlx::query("INSERT INTO refresh_tokens (jti, family_id, expires_at) VALUES (?, ?, ?)")
+            .bind(jti)
+            .bind(family_id)
+            .bind(expires_at as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn consume(&self, jti: &str) -> Result<RefreshTokenStatus, RefreshTokenStoreError> {
+        let now = now();
+        // Marking the token as used is the check itself, so of two concurrent refreshes with the
+        // same token only one sees it unused.
+        let family_id: Option<String> = sqlx::query_scalar(
+            "UPDATE refresh_tokens SET used = 1
+             WHERE jti = ? AND used = 0 AND expires_at > ?
+             RETURNING family_id",
+        )
+        .bind(jti)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+        if let Some(family_id) = family_id {
+            return Ok(RefreshTokenStatus::Valid { family_id });
+        }
+
+        let family_id: Option<String> = sqlx::query_scalar(
+            "SELECT family_id FROM refresh_tokens WHERE jti = ? AND used = 1 AND expires_at > ?",
+        )
+        .bind(jti)
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+        Ok(match family_id {
+            Some(family_id) => RefreshTokenStatus::Reused { family_id },
+            None => RefreshTokenStatus::Unknown,
+        })
+    }
+
+    async fn revoke_family(&self, family_id: &str) -> Result<(), RefreshTokenStoreError> {
+        sqlx::query("DELETE FROM refresh_tokens WHERE family_id = ?")
+            .bind(family_id)
+            .execute(&self.pool)
+            .await
+            .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use crate::entities::{totp_credential::TotpCredential, user::User};
+use crate::ports::user_repository::UserRepository;
+use async_trait::async_trait;
+use contracts::auth::error::UserRepositoryError;
+use sqlx::Row;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use uuid::Uuid;
+
+pub struct SqliteUserRepository {
+    pool: SqlitePool,
+}
+
+impl SqliteUserRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+fn user_from_row(row: &SqliteRow) -> Result<User, UserRepositoryError> {
+    let id: String = row.try_get("id").map_err(map_sqlx_error)?;
+    let totp_credentials: String = row.try_get("totp_credentials").map_err(map_sqlx_error)?;
+    let pass_keys: String = row.try_get("pass_keys").map_err(map_sqlx_error)?;
+    let recovery_codes: String = row.try_get("recovery_codes").map_err(map_sqlx_error)?;
+    let version: i64 = row.try_get("version").map_err(map_sqlx_error)?;
+
+    Ok(User {
+        id: Uuid::parse_str(&id)
+            .map_err(|e| UserRepositoryError::SerializationError(format!("Invalid id: {}", e)))?,
+        name: row.try_get("name").map_err(map_sqlx_error)?,
+        username: row.try_get("username").map_err(map_sqlx_error)?,
+        password: row.try_get("password").map_err(map_sqlx_error)?,
+        totp_credentials: serde_json::from_str(&totp_credentials).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid totp_credentials: {}", e))
+        })?,
+        pass_keys: serde_json::from_str(&pass_keys).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid pass_keys: {}", e))
+        })?,
+        recovery_codes: serde_json::from_str(&recovery_codes).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid recovery_codes: {}", e))
+        })?,
+        version: version as u64,
+    })
+}
+
+fn map_sqlx_error(err: sqlx::Error) -> UserRepositoryError {
+    match err {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            UserRepositoryError::Conflict(db_err.message().to_string())
+        }
+        sqlx::Error::ColumnDecode { .. } | sqlx::Error::Decode(_) => {
+            UserRepositoryError::SerializationError(err.to_string())
+        }
+        _ => UserRepositoryError::ConnectionError(err.to_string()),
+    }
+}
+
+#[async_trait]
+impl UserRepository for SqliteUserRepository {
+    async fn create(&self, user: User) -> Result<(), UserRepositoryError> {
+        let totp_credentials = serde_json::to_string(&user.totp_credentials).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid totp_credentials: {}", e))
+        })?;
+        let pass_keys = serde_json::to_string(&user.pass_keys).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid pass_keys: {}", e))
+        })?;
+        let recovery_codes = serde_json::to_string(&user.recovery_codes).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid recovery_codes: {}", e))
+        })?;
+
+        sqlx::query(
+            "INSERT INTO users (id, name, username, password, totp_credentials, pass_keys,
+             recovery_codes)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(user.id.to_string())
+        .bind(&user.name)
+        .bind(&user.username)
+        .bind(&user.password)
+        .bind(totp_credentials)
+        .bind(pass_keys)
+        .bind(recovery_codes)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(())
+    }
+
+    async fn update(&self, user: User) -> Result<(), UserRepositoryError> {
+        let totp_credentials = serde_json::to_string(&user.totp_credentials).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid totp_credentials: {}", e))
+        })?;
+        let pass_keys = serde_json::to_string(&user.pass_keys).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid pass_keys: {}", e))
+        })?;
+        let recovery_codes = serde_json::to_string(&user.recovery_codes).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid recovery_codes: {}", e))
+        })?;
+
+        let result = sqlx::query(
+            "UPDATE users SET name = ?, username = ?, password = ?, totp_credentials = ?,
+             pass_keys = ?, recovery_codes = ?, version = version + 1
+             WHERE id = ? AND version = ?",
+        )
+        .bind(&user.name)
+        .bind(&user.username)
+        .bind(&user.password)
+        .bind(totp_credentials)
+        .bind(pass_keys)
+        .bind(recovery_codes)
+        .bind(user.id.to_string())
+        .bind(user.version as i64)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        if result.rows_affected() == 0 {
+            let exists = sqlx::query("SELECT 1 FROM users WHERE id = ?")
+                .bind(user.id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(map_sqlx_error)?
+                .is_some();
+            return Err(if exists {
+                UserRepositoryError::Stale
+            } else {
+                UserRepositoryError::NotFound
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn find_username(&self, username: String) -> Result<Option<User>, UserRepositoryError> {
+        let row = sqlx::query(
+            "SELECT id, name, username, password, totp_credentials, pass_keys, recovery_codes,
+             version
+             FROM users WHERE username = ?",
+        )
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        row.as_ref().map(user_from_row).transpose()
+    }
+
+    async fn find_id(&self, id: Uuid) -> Result<Option<User>, UserRepositoryError> {
+        let row = sqlx::query(
+            "SELECT id, name, username, password, totp_credentials, pass_keys, recovery_codes,
+             version
+             FROM users WHERE id = ?",
+        )
+        .bind(id.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        row.as_ref().map(user_from_row).transpose()
+    }
+
+    async fn record_totp_use(
+        &self,
+        id: Uuid,
+        credential: &TotpCredential,
+        last_step: Option<u64>,
+    ) -> Result<bool, UserRepositoryError> {
+        let stored = serde_json::to_string(credential).map_err(|e| {
+            UserRepositoryError::SerializationError(format!("Invalid totp_credentials: {}", e))
+        })?;
+
+        // Credentials are matched by secret: ids moved over from `otp_secret` are stored in
+        // another format than the one they are written back in.
+        let result = sqlx::query(
+            "UPDATE users
+             SET totp_credentials = json_set(
+                 totp_credentials,
+                 '$[' || (
+                     SELECT key FROM json_each(users.totp_credentials)
+                     WHERE json_extract(value, '$.secret') = ?1
+                 ) || ']',
+                 json(?2)
+             ),
+             version = version + 1
+             WHERE id = ?3
+               AND EXISTS (
+                   SELECT 1 FROM json_each(users.totp_credentials)
+                   WHERE json_extract(value, '$.secret') = ?1
+                     AND json_extract(value, '$.last_step') IS ?4
+               )",
+        )
+        .bind(&credential.secret)
+        .bind(stored)
+        .bind(id.to_string())
+        .bind(last_step.map(|step| step as i64))
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, UserRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE users
+             SET recovery_codes = (
+                 SELECT json_group_array(value) FROM json_each(users.recovery_codes)
+                 WHERE value <> ?1
+             ),
+             version = version + 1
+             WHERE id = ?2
+               AND EXISTS (SELECT 1 FROM json_each(users.recovery_codes) WHERE value = ?1)",
+        )
+        .bind(code_hash)
+        .bind(id.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn update_password_hash(
+        &self,
+        id: Uuid,
+        old_hash: &str,
+        new_hash: &str,
+    ) -> Result<bool, UserRepositoryError> {
+        let result = sqlx::query(
+            "UPDATE users SET password = ?, version = version + 1
+             WHERE id = ? AND password = ?",
+        )
+        .bind(new_hash)
+        .bind(id.to_string())
+        .bind(old_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(map_sqlx_error)?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
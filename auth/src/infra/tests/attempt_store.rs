@@ -0,0 +1,126 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use uuid::Uuid;
+
+use crate::{infra::memory::attempt_store::MemoryAttemptStore, ports::attempt_store::AttemptStore};
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs()
+}
+
+/// Keys no other test uses, since the PostgreSQL tests share one database.
+fn new_key() -> String {
+    format!("username:{}", Uuid::new_v4())
+}
+
+async fn failures(store: &Arc<dyn AttemptStore>, key: &str) -> Option<u32> {
+    store.get(key).await.unwrap().map(|a| a.failures)
+}
+
+async fn failures_are_counted_and_cleared(store: Arc<dyn AttemptStore>) {
+    let (key, now) = (new_key(), now());
+    assert_eq!(failures(&store, &key).await, None);
+
+    for expected in 1..=3 {
+        let attempts = store.record_failure(&key, now, now + 60).await.unwrap();
+        assert_eq!(
+            (attempts.failures, attempts.last_failure_at),
+            (expected, now)
+        );
+    }
+    store.lock(&key, now + 120).await.unwrap();
+    assert_eq!(
+        store.get(&key).await.unwrap().unwrap().locked_until,
+        Some(now + 120)
+    );
+
+    store.clear(&key).await.unwrap();
+    assert_eq!(failures(&store, &key).await, None);
+}
+
+async fn released_failures_are_taken_back(store: Arc<dyn AttemptStore>) {
+    let (key, now) = (new_key(), now());
+    store.record_failure(&key, now, now + 60).await.unwrap();
+    store.record_failure(&key, now, now + 60).await.unwrap();
+
+    store.release(&key).await.unwrap();
+    assert_eq!(failures(&store, &key).await, Some(1));
+    store.release(&key).await.unwrap();
+    store.release(&key).await.unwrap();
+    assert_eq!(failures(&store, &key).await, Some(0));
+
+    store.release(&new_key()).await.unwrap();
+}
+
+async fn expired_failures_start_over(store: Arc<dyn AttemptStore>) {
+    let (key, now) = (new_key(), now());
+    store
+        .record_failure(&key, now - 120, now - 60)
+        .await
+        .unwrap();
+    store
+        .record_failure(&key, now - 120, now - 60)
+        .await
+        .unwrap();
+    assert_eq!(failures(&store, &key).await, None);
+
+    let attempts = store.record_failure(&key, now, now + 60).await.unwrap();
+    assert_eq!(attempts.failures, 1);
+}
+
+macro_rules! attempt_store_tests {
+    ($adapter:ident, $store:expr) => {
+        mod $adapter {
+            use super::*;
+
+            #[tokio::test]
+            async fn failures_are_counted_and_cleared() {
+                if let Some(store) = $store {
+                    super::failures_are_counted_and_cleared(store).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn released_failures_are_taken_back() {
+                if let Some(store) = $store {
+                    super::released_failures_are_taken_back(store).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn expired_failures_start_over() {
+                if let Some(store) = $store {
+                    super::expired_failures_start_over(store).await;
+                }
+            }
+        }
+    };
+}
+
+attempt_store_tests!(memory, Some(Arc::new(MemoryAttemptStore::new())));
+
+#[cfg(feature = "sqlite")]
+attempt_store_tests!(
+    sqlite,
+    Some(Arc::new(
+        crate::infra::sqlite::attempt_store::SqliteAttemptStore::new(
+            crate::infra::tests::sqlite_pool().await
+        )
+    ))
+);
+
+#[cfg(feature = "postgres")]
+attempt_store_tests!(
+    postgres,
+    crate::infra::tests::postgres_pool()
+        .await
+        .map(|pool| Arc::new(
+            crate::infra::postgres::attempt_store::PostgresAttemptStore::new(pool)
+        ))
+);
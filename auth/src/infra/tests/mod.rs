@@ -0,0 +1,82 @@
+//! Behaviour shared by every adapter of a port, run against each adapter that is compiled in.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use contracts::auth::error::AuthTokenError;
+
+use crate::{
+    entities::{claims::Claims, signing_key_set::KeyRotation},
+    ports::for_auth_tokens::ForAuthTokens,
+};
+
+mod attempt_store;
+mod refresh_token_store;
+mod token_revocation_store;
+mod user_repository;
+
+/// Unsigned tokens carrying the claims as JSON, for tests of the use cases rather than of the
+/// JWT adapter.
+pub(crate) struct FakeAuthTokens;
+
+#[async_trait]
+impl ForAuthTokens for FakeAuthTokens {
+    async fn create_token(&self, claims: Claims) -> Result<String, AuthTokenError> {
+        serde_json::to_string(&claims).map_err(|_| AuthTokenError::TokenCreationFailure)
+    }
+
+    async fn validate_token(
+        &self,
+        token: String,
+        token_type: String,
+    ) -> Result<Claims, AuthTokenError> {
+        let claims: Claims =
+            serde_json::from_str(&token).map_err(|_| AuthTokenError::InvalidToken)?;
+        if claims.token_type != token_type {
+            return Err(AuthTokenError::InvalidToken);
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should go forward")
+            .as_secs() as usize;
+        if claims.exp <= now {
+            return Err(AuthTokenError::TokenExpired);
+        }
+        Ok(claims)
+    }
+
+    async fn get_jwks(&self) -> Result<String, AuthTokenError> {
+        Ok(r#"{"keys":[]}"#.to_string())
+    }
+
+    async fn rotate_keys(&self, _max_token_lifetime: u64) -> Result<KeyRotation, AuthTokenError> {
+        Err(AuthTokenError::KeyRotationFailure)
+    }
+}
+
+/// A fresh in-memory database with the migrations applied.
+#[cfg(feature = "sqlite")]
+async fn sqlite_pool() -> sqlx::SqlitePool {
+    crate::infra::sqlite::connect("sqlite::memory:")
+        .await
+        .unwrap()
+}
+
+/// The database at `DATABASE_URL`, migrated, or `None` to skip the test when the variable does
+/// not point to PostgreSQL. Under `CI` a missing database fails the test instead, so a broken
+/// pipeline cannot pass by skipping. Tests share the database, so they must not depend on it
+/// being empty.
+#[cfg(feature = "postgres")]
+async fn postgres_pool() -> Option<sqlx::PgPool> {
+    let url = std::env::var("DATABASE_URL")
+        .ok()
+        .filter(|url| url.starts_with("postgres://") || url.starts_with("postgresql://"));
+    let Some(url) = url else {
+        if std::env::var_os("CI").is_some() {
+            panic!("CI is set but DATABASE_URL is not a PostgreSQL URL");
+        }
+        eprintln!("DATABASE_URL is not a PostgreSQL URL; skipping");
+        return None;
+    };
+    Some(crate::infra::postgres::connect(&url).await.unwrap())
+}
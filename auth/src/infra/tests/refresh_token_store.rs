@@ -0,0 +1,251 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use contracts::auth::{
+    Component, error::AuthError, login::LoginInput, signup::SignupInput, token::RefreshTokenInput,
+};
+use uuid::Uuid;
+
+use crate::{
+    AuthComponent, AuthConfig,
+    entities::refresh_token::RefreshTokenStatus,
+    infra::{memory::refresh_token_store::MemoryRefreshTokenStore, tests::FakeAuthTokens},
+    ports::refresh_token_store::RefreshTokenStore,
+};
+
+const CONCURRENT_REFRESHES: usize = 8;
+
+fn in_an_hour() -> usize {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs() as usize
+        + 3600
+}
+
+/// Ids no other test uses, since the PostgreSQL tests share one database.
+fn new_id() -> String {
+    Uuid::new_v4().to_string()
+}
+
+async fn first_use_is_valid_then_reused(store: Arc<dyn RefreshTokenStore>) {
+    let (jti, family_id) = (new_id(), new_id());
+    store
+        .register(&jti, &family_id, in_an_hour())
+        .await
+        .unwrap();
+
+    assert_eq!(
+        store.consume(&jti).await.unwrap(),
+        RefreshTokenStatus::Valid {
+            family_id: family_id.clone()
+        }
+    );
+    assert_eq!(
+        store.consume(&jti).await.unwrap(),
+        RefreshTokenStatus::Reused { family_id }
+    );
+}
+
+async fn unknown_and_expired_tokens_are_unknown(store: Arc<dyn RefreshTokenStore>) {
+    assert_eq!(
+        store.consume(&new_id()).await.unwrap(),
+        RefreshTokenStatus::Unknown
+    );
+
+    let jti = new_id();
+    store.register(&jti, &new_id(), 1).await.unwrap();
+    assert_eq!(
+        store.consume(&jti).await.unwrap(),
+        RefreshTokenStatus::Unknown
+    );
+}
+
+async fn revoking_a_family_forgets_only_its_tokens(store: Arc<dyn RefreshTokenStore>) {
+    let (family, other_family) = (new_id(), new_id());
+    let (used, unused, other) = (new_id(), new_id(), new_id());
+    store.register(&used, &family, in_an_hour()).await.unwrap();
+    store
+        .register(&unused, &family, in_an_hour())
+        .await
+        .unwrap();
+    store
+        .register(&other, &other_family, in_an_hour())
+        .await
+        .unwrap();
+    store.consume(&used).await.unwrap();
+
+    store.revoke_family(&family).await.unwrap();
+
+    for jti in [&used, &unused] {
+        assert_eq!(
+            store.consume(jti).await.unwrap(),
+            RefreshTokenStatus::Unknown
+        );
+    }
+    assert_eq!(
+        store.consume(&other).await.unwrap(),
+        RefreshTokenStatus::Valid {
+            family_id: other_family
+        }
+    );
+}
+
+async fn concurrent_consumes_accept_one(store: Arc<dyn RefreshTokenStore>) {
+    let (jti, family_id) = (new_id(), new_id());
+    store
+        .register(&jti, &family_id, in_an_hour())
+        .await
+        .unwrap();
+
+    let consumes: Vec<_> = (0..CONCURRENT_REFRESHES)
+        .map(|_| {
+            let (store, jti) = (store.clone(), jti.clone());
+            tokio::spawn(async move { store.consume(&jti).await.unwrap() })
+        })
+        .collect();
+    let mut valid = 0;
+    for consume in consumes {
+        match consume.await.unwrap() {
+            RefreshTokenStatus::Valid { .. } => valid += 1,
+            RefreshTokenStatus::Reused { family_id: reused } => assert_eq!(reused, family_id),
+            RefreshTokenStatus::Unknown => panic!("registered token reported as unknown"),
+        }
+    }
+    assert_eq!(valid, 1);
+}
+
+/// Each refresh returns a new refresh token of the same family; presenting a rotated one again
+/// revokes the family, including the token that replaced it.
+async fn reuse_revokes_the_family(store: Arc<dyn RefreshTokenStore>) {
+    let auth = AuthComponent::builder(AuthConfig::default())
+        .refresh_token_store(store)
+        .auth_tokens(Arc::new(FakeAuthTokens))
+        .build()
+        .await
+        .unwrap();
+    let username = format!("{}@exemplo.com", new_id());
+    auth.signup(SignupInput {
+        name: "Ana".to_string(),
+        username: username.clone(),
+        password: "Password123!".to_string(),
+    })
+    .await
+    .unwrap();
+    let login = auth
+        .login(LoginInput {
+            username,
+            password: "Password123!".to_string(),
+            client_ip: None,
+        })
+        .await
+        .unwrap();
+    let refresh = |refresh_token: &String| {
+        auth.refresh_token(RefreshTokenInput {
+            refresh_token: refresh_token.clone(),
+        })
+    };
+
+    let first = login.refresh_token.unwrap();
+    let second = refresh(&first).await.unwrap().refresh_token;
+    assert_ne!(second, first);
+    let third = refresh(&second).await.unwrap().refresh_token;
+
+    assert!(matches!(
+        refresh(&first).await,
+        Err(AuthError::RefreshTokenReused)
+    ));
+    assert!(matches!(
+        refresh(&third).await,
+        Err(AuthError::InvalidRefreshToken)
+    ));
+}
+
+/// One test per behaviour for the store built by `$store`, an
+/// `Option<Arc<dyn RefreshTokenStore>>` that is `None` when the adapter cannot run here.
+macro_rules! refresh_token_store_tests {
+    ($adapter:ident, $store:expr) => {
+        mod $adapter {
+            use super::*;
+
+            #[tokio::test]
+            async fn first_use_is_valid_then_reused() {
+                if let Some(store) = $store {
+                    super::first_use_is_valid_then_reused(store).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn unknown_and_expired_tokens_are_unknown() {
+                if let Some(store) = $store {
+                    super::unknown_and_expired_tokens_are_unknown(store).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn revoking_a_family_forgets_only_its_tokens() {
+                if let Some(store) = $store {
+                    super::revoking_a_family_forgets_only_its_tokens(store).await;
+                }
+            }
+
+            #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+            async fn concurrent_consumes_accept_one() {
+                if let Some(store) = $store {
+                    super::concurrent_consumes_accept_one(store).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn reuse_revokes_the_family() {
+                if let Some(store) = $store {
+                    super::reuse_revokes_the_family(store).await;
+                }
+            }
+        }
+    };
+}
+
+refresh_token_store_tests!(memory, Some(Arc::new(MemoryRefreshTokenStore::new())));
+
+#[cfg(feature = "sqlite")]
+refresh_token_store_tests!(
+    sqlite,
+    Some(Arc::new(
+        crate::infra::sqlite::refresh_token_store::SqliteRefreshTokenStore::new(
+            crate::infra::tests::sqlite_pool().await
+        )
+    ))
+);
+
+#[cfg(feature = "postgres")]
+refresh_token_store_tests!(
+    postgres,
+    crate::infra::tests::postgres_pool().await.map(|pool| {
+        Arc::new(crate::infra::postgres::refresh_token_store::PostgresRefreshTokenStore::new(pool))
+    })
+);
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn sqlite_tokens_survive_a_restart() {
+    use crate::infra::sqlite::{self, refresh_token_store::SqliteRefreshTokenStore};
+
+    let path = std::env::temp_dir().join(format!("auth-{}.db", new_id()));
+    let url = format!("sqlite://{}", path.display());
+    let (jti, family_id) = (new_id(), new_id());
+
+    let before = SqliteRefreshTokenStore::new(sqlite::connect(&url).await.unwrap());
+    before
+        .register(&jti, &family_id, in_an_hour())
+        .await
+        .unwrap();
+    drop(before);
+
+    let after = SqliteRefreshTokenStore::new(sqlite::connect(&url).await.unwrap());
+    let status = after.consume(&jti).await.unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert_eq!(status, RefreshTokenStatus::Valid { family_id });
+}
@@ -0,0 +1,93 @@
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use uuid::Uuid;
+
+use crate::{
+    infra::memory::token_revocation_store::MemoryTokenRevocationStore,
+    ports::token_revocation_store::TokenRevocationStore,
+};
+
+fn in_an_hour() -> usize {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs() as usize
+        + 3600
+}
+
+async fn revoked_jti_is_revoked(store: Arc<dyn TokenRevocationStore>) {
+    let (jti, user_id) = (Uuid::new_v4().to_string(), Uuid::new_v4());
+    assert!(!store.is_revoked(&jti, user_id, 0).await.unwrap());
+
+    store.revoke(&jti, in_an_hour()).await.unwrap();
+    assert!(store.is_revoked(&jti, user_id, 0).await.unwrap());
+    assert!(
+        !store
+            .is_revoked(&Uuid::new_v4().to_string(), user_id, 0)
+            .await
+            .unwrap()
+    );
+}
+
+async fn revoke_all_revokes_earlier_generations_only(store: Arc<dyn TokenRevocationStore>) {
+    let (user_id, other_user) = (Uuid::new_v4(), Uuid::new_v4());
+    let jti = Uuid::new_v4().to_string();
+    assert_eq!(store.generation(user_id).await.unwrap(), 0);
+
+    store.revoke_all(user_id).await.unwrap();
+    assert_eq!(store.generation(user_id).await.unwrap(), 1);
+    assert!(store.is_revoked(&jti, user_id, 0).await.unwrap());
+    assert!(!store.is_revoked(&jti, user_id, 1).await.unwrap());
+    assert!(!store.is_revoked(&jti, other_user, 0).await.unwrap());
+
+    store.revoke_all(user_id).await.unwrap();
+    assert_eq!(store.generation(user_id).await.unwrap(), 2);
+    assert!(store.is_revoked(&jti, user_id, 1).await.unwrap());
+}
+
+macro_rules! token_revocation_store_tests {
+    ($adapter:ident, $store:expr) => {
+        mod $adapter {
+            use super::*;
+
+            #[tokio::test]
+            async fn revoked_jti_is_revoked() {
+                if let Some(store) = $store {
+                    super::revoked_jti_is_revoked(store).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn revoke_all_revokes_earlier_generations_only() {
+                if let Some(store) = $store {
+                    super::revoke_all_revokes_earlier_generations_only(store).await;
+                }
+            }
+        }
+    };
+}
+
+token_revocation_store_tests!(memory, Some(Arc::new(MemoryTokenRevocationStore::new())));
+
+#[cfg(feature = "sqlite")]
+token_revocation_store_tests!(
+    sqlite,
+    Some(Arc::new(
+        crate::infra::sqlite::token_revocation_store::SqliteTokenRevocationStore::new(
+            crate::infra::tests::sqlite_pool().await
+        )
+    ))
+);
+
+#[cfg(feature = "postgres")]
+token_revocation_store_tests!(
+    postgres,
+    crate::infra::tests::postgres_pool().await.map(|pool| {
+        Arc::new(
+            crate::infra::postgres::token_revocation_store::PostgresTokenRevocationStore::new(pool),
+        )
+    })
+);
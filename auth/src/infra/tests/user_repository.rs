@@ -0,0 +1,484 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    Component,
+    error::{AuthError, UserRepositoryError},
+    signup::SignupInput,
+};
+use uuid::Uuid;
+
+use crate::{
+    AuthComponent, AuthConfig,
+    entities::{
+        totp_credential::{TotpAlgorithm, TotpCredential, TotpParameters},
+        user::User,
+    },
+    infra::memory::user_repository,
+    ports::user_repository::UserRepository,
+};
+
+const CONCURRENT_REQUESTS: usize = 8;
+
+/// A username no other test uses, since the PostgreSQL tests share one database.
+fn new_user() -> User {
+    User::new(
+        &format!("{}@exemplo.com", Uuid::new_v4()),
+        "Ana",
+        "$argon2id$hash",
+    )
+}
+
+async fn create_and_find(repository: Arc<dyn UserRepository>) {
+    let user = new_user();
+    repository.create(user.clone()).await.unwrap();
+
+    let by_username = repository
+        .find_username(user.username.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(by_username.id, user.id);
+    assert_eq!(by_username.name, user.name);
+    assert_eq!(by_username.password, user.password);
+
+    let by_id = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(by_id.username, user.username);
+
+    assert!(
+        repository
+            .find_username("ninguem@exemplo.com".to_string())
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(repository.find_id(Uuid::new_v4()).await.unwrap().is_none());
+}
+
+async fn update_replaces_the_user(repository: Arc<dyn UserRepository>) {
+    let mut user = new_user();
+    repository.create(user.clone()).await.unwrap();
+
+    user.password = "$argon2id$other".to_string();
+    user.recovery_codes = vec!["hash".to_string()];
+    repository.update(user.clone()).await.unwrap();
+
+    let stored = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.password, user.password);
+    assert_eq!(stored.recovery_codes, user.recovery_codes);
+}
+
+async fn update_can_rename(repository: Arc<dyn UserRepository>) {
+    let mut user = new_user();
+    repository.create(user.clone()).await.unwrap();
+    let old_username = user.username.clone();
+
+    user.username = new_user().username;
+    repository.update(user.clone()).await.unwrap();
+
+    assert!(
+        repository
+            .find_username(old_username)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    let renamed = repository
+        .find_username(user.username.clone())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(renamed.id, user.id);
+}
+
+async fn rename_to_a_taken_username_conflicts(repository: Arc<dyn UserRepository>) {
+    let taken = new_user();
+    repository.create(taken.clone()).await.unwrap();
+    let mut user = new_user();
+    repository.create(user.clone()).await.unwrap();
+    let old_username = user.username.clone();
+
+    user.username = taken.username.clone();
+    let result = repository.update(user.clone()).await;
+    assert!(matches!(result, Err(UserRepositoryError::Conflict(_))));
+    let stored = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.username, old_username);
+    let owner = repository
+        .find_username(taken.username)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(owner.id, taken.id);
+}
+
+async fn update_of_unknown_user_is_not_found(repository: Arc<dyn UserRepository>) {
+    let result = repository.update(new_user()).await;
+    assert!(matches!(result, Err(UserRepositoryError::NotFound)));
+}
+
+/// A targeted write in between, here a recovery code being used, makes the read stale, so
+/// writing it back cannot restore the code.
+async fn update_of_a_stale_read_is_refused(repository: Arc<dyn UserRepository>) {
+    let mut user = new_user();
+    user.recovery_codes = vec!["first".to_string(), "second".to_string()];
+    repository.create(user.clone()).await.unwrap();
+    let mut read = repository.find_id(user.id).await.unwrap().unwrap();
+
+    assert!(
+        repository
+            .consume_recovery_code(user.id, "first")
+            .await
+            .unwrap()
+    );
+    read.name = "Bia".to_string();
+    let result = repository.update(read).await;
+    assert!(matches!(result, Err(UserRepositoryError::Stale)));
+
+    let mut stored = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.recovery_codes, vec!["second".to_string()]);
+    stored.name = "Bia".to_string();
+    repository.update(stored.clone()).await.unwrap();
+    let result = repository.update(stored).await;
+    assert!(matches!(result, Err(UserRepositoryError::Stale)));
+}
+
+async fn duplicate_username_conflicts(repository: Arc<dyn UserRepository>) {
+    let user = new_user();
+    repository.create(user.clone()).await.unwrap();
+
+    let result = repository
+        .create(User::new(&user.username, "Bia", "$argon2id$hash"))
+        .await;
+    assert!(matches!(result, Err(UserRepositoryError::Conflict(_))));
+    let stored = repository
+        .find_username(user.username)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stored.id, user.id);
+}
+
+/// A user with two TOTP credentials, the first last used at step 1.
+async fn user_with_totp(repository: &Arc<dyn UserRepository>) -> User {
+    let parameters = TotpParameters {
+        algorithm: TotpAlgorithm::Sha1,
+        digits: 6,
+        step: 30,
+    };
+    let mut user = new_user();
+    user.totp_credentials = vec![
+        TotpCredential::new("Phone".to_string(), "SECRET1".to_string(), parameters, 1),
+        TotpCredential::new("Backup".to_string(), "SECRET2".to_string(), parameters, 1),
+    ];
+    repository.create(user.clone()).await.unwrap();
+    user
+}
+
+async fn totp_use_is_recorded_once(repository: Arc<dyn UserRepository>) {
+    let user = user_with_totp(&repository).await;
+    let mut used = user.totp_credentials[0].clone();
+    used.mark_used(2);
+
+    assert!(
+        repository
+            .record_totp_use(user.id, &used, Some(1))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repository
+            .record_totp_use(user.id, &used, Some(1))
+            .await
+            .unwrap()
+    );
+
+    let stored = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.totp_credentials[0].last_step, Some(2));
+    assert_eq!(stored.totp_credentials[0].label, "Phone");
+    assert_eq!(stored.totp_credentials[1].last_step, Some(1));
+}
+
+/// Writing back a read from before a TOTP use would move its last step back and let the code be
+/// replayed.
+async fn update_after_a_totp_use_is_refused(repository: Arc<dyn UserRepository>) {
+    let user = user_with_totp(&repository).await;
+    let mut read = repository.find_id(user.id).await.unwrap().unwrap();
+    let mut used = user.totp_credentials[0].clone();
+    used.mark_used(2);
+
+    assert!(
+        repository
+            .record_totp_use(user.id, &used, Some(1))
+            .await
+            .unwrap()
+    );
+    read.totp_credentials[1].label = "Tablet".to_string();
+    let result = repository.update(read).await;
+    assert!(matches!(result, Err(UserRepositoryError::Stale)));
+
+    let stored = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.totp_credentials[0].last_step, Some(2));
+    assert_eq!(stored.totp_credentials[1].label, "Backup");
+}
+
+async fn concurrent_totp_uses_record_it_once(repository: Arc<dyn UserRepository>) {
+    let user = user_with_totp(&repository).await;
+    let mut used = user.totp_credentials[1].clone();
+    used.mark_used(2);
+
+    let records: Vec<_> = (0..CONCURRENT_REQUESTS)
+        .map(|_| {
+            let (repository, used) = (repository.clone(), used.clone());
+            tokio::spawn(async move {
+                repository
+                    .record_totp_use(user.id, &used, Some(1))
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect();
+    let mut recorded = 0;
+    for record in records {
+        if record.await.unwrap() {
+            recorded += 1;
+        }
+    }
+    assert_eq!(recorded, 1);
+}
+
+async fn recovery_codes_are_consumed_once(repository: Arc<dyn UserRepository>) {
+    let mut user = new_user();
+    user.recovery_codes = vec!["first".to_string(), "second".to_string()];
+    repository.create(user.clone()).await.unwrap();
+
+    assert!(
+        repository
+            .consume_recovery_code(user.id, "first")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repository
+            .consume_recovery_code(user.id, "first")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repository
+            .consume_recovery_code(user.id, "unknown")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repository
+            .consume_recovery_code(Uuid::new_v4(), "second")
+            .await
+            .unwrap()
+    );
+
+    let stored = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.recovery_codes, vec!["second".to_string()]);
+}
+
+async fn concurrent_recovery_code_uses_consume_it_once(repository: Arc<dyn UserRepository>) {
+    let mut user = new_user();
+    user.recovery_codes = vec!["code".to_string()];
+    repository.create(user.clone()).await.unwrap();
+
+    let consumes: Vec<_> = (0..CONCURRENT_REQUESTS)
+        .map(|_| {
+            let repository = repository.clone();
+            tokio::spawn(async move {
+                repository
+                    .consume_recovery_code(user.id, "code")
+                    .await
+                    .unwrap()
+            })
+        })
+        .collect();
+    let mut consumed = 0;
+    for consume in consumes {
+        if consume.await.unwrap() {
+            consumed += 1;
+        }
+    }
+    assert_eq!(consumed, 1);
+}
+
+async fn password_hash_is_replaced_only_while_unchanged(repository: Arc<dyn UserRepository>) {
+    let mut user = new_user();
+    user.recovery_codes = vec!["code".to_string()];
+    repository.create(user.clone()).await.unwrap();
+
+    assert!(
+        repository
+            .update_password_hash(user.id, "$argon2id$hash", "$argon2id$new")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repository
+            .update_password_hash(user.id, "$argon2id$hash", "$argon2id$newer")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !repository
+            .update_password_hash(Uuid::new_v4(), "$argon2id$new", "$argon2id$newer")
+            .await
+            .unwrap()
+    );
+
+    let stored = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(stored.password, "$argon2id$new");
+    assert_eq!(stored.username, user.username);
+    assert_eq!(stored.recovery_codes, user.recovery_codes);
+}
+
+async fn signup_maps_conflict_to_username_taken(repository: Arc<dyn UserRepository>) {
+    let auth = AuthComponent::builder(AuthConfig::default())
+        .user_repository(repository)
+        .build()
+        .await
+        .unwrap();
+    let username = new_user().username;
+    let signup = || SignupInput {
+        name: "Ana".to_string(),
+        username: username.clone(),
+        password: "Password123!".to_string(),
+    };
+
+    auth.signup(signup()).await.unwrap();
+    let result = auth.signup(signup()).await;
+    assert!(matches!(result, Err(AuthError::UsernameAlreadyTaken)));
+}
+
+/// One test per behaviour for the repository built by `$repository`, an
+/// `Option<Arc<dyn UserRepository>>` that is `None` when the adapter cannot run here.
+macro_rules! user_repository_tests {
+    ($adapter:ident, $repository:expr) => {
+        mod $adapter {
+            use super::*;
+
+            #[tokio::test]
+            async fn create_and_find() {
+                if let Some(repository) = $repository {
+                    super::create_and_find(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn update_replaces_the_user() {
+                if let Some(repository) = $repository {
+                    super::update_replaces_the_user(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn update_can_rename() {
+                if let Some(repository) = $repository {
+                    super::update_can_rename(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn rename_to_a_taken_username_conflicts() {
+                if let Some(repository) = $repository {
+                    super::rename_to_a_taken_username_conflicts(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn update_of_unknown_user_is_not_found() {
+                if let Some(repository) = $repository {
+                    super::update_of_unknown_user_is_not_found(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn update_of_a_stale_read_is_refused() {
+                if let Some(repository) = $repository {
+                    super::update_of_a_stale_read_is_refused(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn duplicate_username_conflicts() {
+                if let Some(repository) = $repository {
+                    super::duplicate_username_conflicts(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn totp_use_is_recorded_once() {
+                if let Some(repository) = $repository {
+                    super::totp_use_is_recorded_once(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn update_after_a_totp_use_is_refused() {
+                if let Some(repository) = $repository {
+                    super::update_after_a_totp_use_is_refused(repository).await;
+                }
+            }
+
+            #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+            async fn concurrent_totp_uses_record_it_once() {
+                if let Some(repository) = $repository {
+                    super::concurrent_totp_uses_record_it_once(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn recovery_codes_are_consumed_once() {
+                if let Some(repository) = $repository {
+                    super::recovery_codes_are_consumed_once(repository).await;
+                }
+            }
+
+            #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+            async fn concurrent_recovery_code_uses_consume_it_once() {
+                if let Some(repository) = $repository {
+                    super::concurrent_recovery_code_uses_consume_it_once(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn password_hash_is_replaced_only_while_unchanged() {
+                if let Some(repository) = $repository {
+                    super::password_hash_is_replaced_only_while_unchanged(repository).await;
+                }
+            }
+
+            #[tokio::test]
+            async fn signup_maps_conflict_to_username_taken() {
+                if let Some(repository) = $repository {
+                    super::signup_maps_conflict_to_username_taken(repository).await;
+                }
+            }
+        }
+    };
+}
+
+user_repository_tests!(
+    memory,
+    Some(Arc::new(user_repository::MemoryUserRepository::new()))
+);
+
+#[cfg(feature = "sqlite")]
+user_repository_tests!(
+    sqlite,
+    Some(Arc::new(
+        crate::infra::sqlite::user_repository::SqliteUserRepository::new(
+            crate::infra::tests::sqlite_pool().await
+        )
+    ))
+);
+
+#[cfg(feature = "postgres")]
+user_repository_tests!(
+    postgres,
+    crate::infra::tests::postgres_pool().await.map(|pool| {
+        Arc::new(crate::infra::postgres::user_repository::PostgresUserRepository::new(pool))
+    })
+);
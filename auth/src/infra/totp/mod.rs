@@ -1,51 +1,122 @@
-use crate::ports::for_totp::ForTotp;
+use crate::{
+    config::{ConfigError, TotpConfig},
+    entities::totp_credential::{TotpAlgorithm, TotpParameters},
+    ports::for_totp::ForTotp,
+};
 use async_trait::async_trait;
 use contracts::auth::error::TotpError;
 use log::warn;
 use rand::Rng;
+use std::time::{SystemTime, UNIX_EPOCH};
 use totp_rs::{Algorithm, Secret, TOTP};
+use webauthn_rs::prelude::Url;
 
-pub struct Totp {}
+pub struct Totp {
+    config: TotpConfig,
+}
+
+impl Totp {
+    pub fn new(config: TotpConfig) -> Result<Self, ConfigError> {
+        if !(6..=8).contains(&config.digits) {
+            return Err(ConfigError::InvalidTotp(format!(
+                "digits must be between 6 and 8, got {}",
+                config.digits
+            )));
+        }
+        if config.step == 0 {
+            return Err(ConfigError::InvalidTotp(
+                "step must be positive".to_string(),
+            ));
+        }
+        Ok(Self { config })
+    }
+
+    fn configured(&self) -> TotpParameters {
+        TotpParameters {
+            algorithm: self.config.algorithm,
+            digits: self.config.digits,
+            step: self.config.step,
+        }
+    }
+
+    /// Generator for a single step; drift is handled by `verify` so it knows which step matched.
+    fn totp(&self, secret: String, parameters: TotpParameters) -> Result<TOTP, TotpError> {
+        let secret = Secret::Encoded(secret).to_bytes().map_err(|e| {
+            warn!("Invalid TOTP secret encoding: {:?}", e);
+            TotpError::InvalidSecret
+        })?;
+        let algorithm = match parameters.algorithm {
+            TotpAlgorithm::Sha1 => Algorithm::SHA1,
+            TotpAlgorithm::Sha256 => Algorithm::SHA256,
+            TotpAlgorithm::Sha512 => Algorithm::SHA512,
+        };
+
+        TOTP::new(algorithm, parameters.digits, 0, parameters.step, secret).map_err(|e| {
+            warn!("Invalid TOTP secret: {}", e);
+            TotpError::InvalidSecret
+        })
+    }
+
+    /// Step in the skew window around `now` whose code is `token`.
+    fn matching_step(&self, totp: &TOTP, token: &str, step_length: u64, now: u64) -> Option<u64> {
+        let current = now / step_length;
+        let skew = self.config.skew as u64;
+        (current.saturating_sub(skew)..=current + skew)
+            .find(|step| totp.check(token, step * step_length))
+    }
+}
 
 #[async_trait]
 impl ForTotp for Totp {
-    async fn verify(&self, secret: String, token: String) -> Result<bool, TotpError> {
-        let totp = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            1,
-            30,
-            Secret::Encoded(secret).to_bytes().unwrap(),
-        )
-        .unwrap();
-
-        let result = totp.check_current(&token).map_err(|e| {
-            warn!("Error verifying TOTP token: {}", e);
-            TotpError::VerificationFailed
-        })?;
+    async fn verify(
+        &self,
+        secret: String,
+        parameters: Option<TotpParameters>,
+        token: String,
+        last_step: Option<u64>,
+    ) -> Result<Option<u64>, TotpError> {
+        let parameters = parameters.unwrap_or_else(|| self.configured());
+        let totp = self.totp(secret, parameters)?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| {
+                warn!("Error verifying TOTP token: {}", e);
+                TotpError::VerificationFailed
+            })?
+            .as_secs();
 
-        Ok(result)
+        let matched = self.matching_step(&totp, &token, parameters.step, now);
+
+        // A code for an already accepted step is a replay, even if it is still inside the window.
+        Ok(matched.filter(|step| last_step.is_none_or(|last| *step > last)))
     }
 
-    async fn auth_url(&self, issuer: String, email: String) -> Result<(String, String), TotpError> {
+    async fn auth_url(&self, account: String) -> Result<(String, String), TotpError> {
         let mut rng = rand::rng();
         let data_byte: [u8; 21] = rng.random();
         let base32_string =
             base32::encode(base32::Alphabet::Rfc4648 { padding: false }, &data_byte);
 
-        let totp = TOTP::new(
-            Algorithm::SHA1,
-            6,
-            1,
-            30,
-            Secret::Encoded(base32_string).to_bytes().unwrap(),
-        )
-        .unwrap();
-
-        let otp_base32 = totp.get_secret_base32();
-        let otp_auth_url =
-            format!("otpauth://totp/{issuer}:{email}?secret={otp_base32}&issuer={issuer}");
+        let otp_base32 = self
+            .totp(base32_string, self.configured())?
+            .get_secret_base32();
+        let issuer = &self.config.issuer;
+        let mut otp_auth_url = Url::parse("otpauth://totp/").map_err(|e| {
+            warn!("Error building otpauth URL: {}", e);
+            TotpError::AuthUrlGenerationFailed
+        })?;
+        otp_auth_url.set_path(&format!("{issuer}:{account}"));
+        otp_auth_url
+            .query_pairs_mut()
+            .append_pair("secret", &otp_base32)
+            .append_pair("issuer", issuer)
+            .append_pair("algorithm", self.config.algorithm.as_str())
+            .append_pair("digits", &self.config.digits.to_string())
+            .append_pair("period", &self.config.step.to_string());
 
-        Ok((otp_base32, otp_auth_url))
+        Ok((otp_base32, otp_auth_url.to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests;
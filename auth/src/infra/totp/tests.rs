@@ -0,0 +1,229 @@
+use totp_rs::{Algorithm, Secret, TOTP};
+use webauthn_rs::prelude::Url;
+
+use super::Totp;
+use crate::{
+    config::TotpConfig,
+    entities::totp_credential::{TotpAlgorithm, TotpParameters},
+    ports::for_totp::ForTotp,
+};
+
+const NOW: u64 = 1_700_000_000;
+
+fn secret() -> String {
+    let bytes: [u8; 20] = rand::random();
+    base32::encode(base32::Alphabet::Rfc4648 { padding: false }, &bytes)
+}
+
+/// What an authenticator app configured with `parameters` shows at `time`.
+fn code(secret: &str, parameters: TotpParameters, time: u64) -> String {
+    let algorithm = match parameters.algorithm {
+        TotpAlgorithm::Sha1 => Algorithm::SHA1,
+        TotpAlgorithm::Sha256 => Algorithm::SHA256,
+        TotpAlgorithm::Sha512 => Algorithm::SHA512,
+    };
+    let secret = Secret::Encoded(secret.to_string()).to_bytes().unwrap();
+    TOTP::new(algorithm, parameters.digits, 0, parameters.step, secret)
+        .unwrap()
+        .generate(time)
+}
+
+fn totp(config: TotpConfig) -> Totp {
+    Totp::new(config).expect("valid TOTP configuration")
+}
+
+fn matching_step(
+    totp: &Totp,
+    secret: &str,
+    parameters: TotpParameters,
+    token: &str,
+) -> Option<u64> {
+    let generator = totp.totp(secret.to_string(), parameters).unwrap();
+    totp.matching_step(&generator, token, parameters.step, NOW)
+}
+
+#[test]
+fn every_supported_parameter_set_verifies() {
+    let totp = totp(TotpConfig::default());
+    let secret = secret();
+
+    for algorithm in [
+        TotpAlgorithm::Sha1,
+        TotpAlgorithm::Sha256,
+        TotpAlgorithm::Sha512,
+    ] {
+        for digits in 6..=8 {
+            for step in [30, 60] {
+                let parameters = TotpParameters {
+                    algorithm,
+                    digits,
+                    step,
+                };
+                let token = code(&secret, parameters, NOW);
+                assert_eq!(token.len(), digits);
+                assert_eq!(
+                    matching_step(&totp, &secret, parameters, &token),
+                    Some(NOW / step),
+                    "{parameters:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn codes_of_other_parameters_are_rejected() {
+    let totp = totp(TotpConfig::default());
+    let secret = secret();
+    let enrolled = TotpParameters {
+        algorithm: TotpAlgorithm::Sha256,
+        digits: 8,
+        step: 60,
+    };
+
+    let others = [
+        TotpParameters {
+            algorithm: TotpAlgorithm::Sha1,
+            ..enrolled
+        },
+        TotpParameters {
+            digits: 6,
+            ..enrolled
+        },
+    ];
+    for other in others {
+        let token = code(&secret, other, NOW);
+        assert_eq!(
+            matching_step(&totp, &secret, enrolled, &token),
+            None,
+            "{other:?}"
+        );
+    }
+}
+
+#[test]
+fn skew_accepts_only_neighbouring_steps() {
+    let parameters = TotpParameters {
+        algorithm: TotpAlgorithm::Sha1,
+        digits: 6,
+        step: 30,
+    };
+    let secret = secret();
+    let current = NOW / parameters.step;
+    let at = |step: u64| code(&secret, parameters, step * parameters.step);
+
+    let lenient = totp(TotpConfig {
+        skew: 1,
+        ..TotpConfig::default()
+    });
+    for step in [current - 1, current, current + 1] {
+        assert_eq!(
+            matching_step(&lenient, &secret, parameters, &at(step)),
+            Some(step)
+        );
+    }
+    for step in [current - 2, current + 2] {
+        assert_eq!(
+            matching_step(&lenient, &secret, parameters, &at(step)),
+            None
+        );
+    }
+
+    let strict = totp(TotpConfig {
+        skew: 0,
+        ..TotpConfig::default()
+    });
+    assert_eq!(
+        matching_step(&strict, &secret, parameters, &at(current)),
+        Some(current)
+    );
+    assert_eq!(
+        matching_step(&strict, &secret, parameters, &at(current - 1)),
+        None
+    );
+}
+
+#[tokio::test]
+async fn stored_parameters_win_over_the_configuration() {
+    let totp = totp(TotpConfig::default());
+    let secret = secret();
+    let enrolled = TotpParameters {
+        algorithm: TotpAlgorithm::Sha512,
+        digits: 8,
+        step: 60,
+    };
+    let token = code(&secret, enrolled, now());
+
+    let step = totp
+        .verify(secret.clone(), Some(enrolled), token.clone(), None)
+        .await
+        .unwrap();
+    assert!(step.is_some());
+    assert_eq!(
+        totp.verify(secret.clone(), None, token.clone(), None)
+            .await
+            .unwrap(),
+        None
+    );
+    assert_eq!(
+        totp.verify(secret, Some(enrolled), token, step)
+            .await
+            .unwrap(),
+        None,
+        "an accepted step cannot be replayed"
+    );
+}
+
+#[tokio::test]
+async fn credentials_without_parameters_use_the_configuration() {
+    let config = TotpConfig {
+        algorithm: TotpAlgorithm::Sha256,
+        digits: 7,
+        step: 45,
+        ..TotpConfig::default()
+    };
+    let configured = TotpParameters {
+        algorithm: config.algorithm,
+        digits: config.digits,
+        step: config.step,
+    };
+    let totp = totp(config);
+    let secret = secret();
+
+    let token = code(&secret, configured, now());
+    assert!(
+        totp.verify(secret, None, token, None)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn auth_url_carries_the_configured_parameters() {
+    let totp = totp(TotpConfig {
+        algorithm: TotpAlgorithm::Sha512,
+        digits: 8,
+        step: 60,
+        ..TotpConfig::default()
+    });
+
+    let (secret, auth_url) = totp.auth_url("alice".to_string()).await.unwrap();
+    let url = Url::parse(&auth_url).unwrap();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, val)| val.into_owned())
+    };
+    assert_eq!(param("secret"), Some(secret));
+    assert_eq!(param("algorithm").as_deref(), Some("SHA512"));
+    assert_eq!(param("digits").as_deref(), Some("8"));
+    assert_eq!(param("period").as_deref(), Some("60"));
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
@@ -0,0 +1,305 @@
+use std::time::Duration;
+
+use contracts::auth::error::PasskeyError;
+use uuid::Uuid;
+use webauthn_rs::{
+    WebauthnBuilder,
+    prelude::{
+        AttestationCaList, AuthenticationResult, AuthenticationState, CreationChallengeResponse,
+        Credential, CredentialID, Passkey, PublicKeyCredential, RegisterPublicKeyCredential,
+        RequestChallengeResponse, Url, WebauthnError,
+    },
+};
+use webauthn_rs_core::{WebauthnCore, proto::RegistrationState};
+use webauthn_rs_proto::{
+    AttestationConveyancePreference, CredProtect, CredentialProtectionPolicy,
+    RequestAuthenticationExtensions, RequestRegistrationExtensions, ResidentKeyRequirement,
+    UserVerificationPolicy,
+};
+
+use crate::{
+    config::{AttestationPreference, ConfigError, UserVerification, WebauthnConfig},
+    ports::for_passkeys::ForPasskeys,
+};
+
+/// Runs passkey ceremonies with the configured policies.
+///
+/// The passkey API of `Webauthn` always asks for no attestation and required user verification,
+/// so the ceremonies are built with the challenge builders of `webauthn-rs-core` instead, the same
+/// way `Webauthn` builds them with its fixed values. The policy is part of the state, which is
+/// passed around as JSON, so finishing checks what was started.
+pub struct WebauthnPasskeys {
+    core: WebauthnCore,
+    attestation: AttestationConveyancePreference,
+    attestation_cas: Option<AttestationCaList>,
+    user_verification: UserVerificationPolicy,
+    timeout: u64,
+}
+
+impl WebauthnPasskeys {
+    /// Validates the relying party and reads the attestation CAs, which registrations then have
+    /// to be attested by.
+    pub async fn new(config: &WebauthnConfig) -> Result<Self, ConfigError> {
+        Ok(Self {
+            core: build_webauthn(config)?,
+            attestation: attestation(config.attestation),
+            attestation_cas: load_attestation_cas(config).await?,
+            user_verification: user_verification(config.user_verification),
+            timeout: config.timeout,
+        })
+    }
+}
+
+impl ForPasskeys for WebauthnPasskeys {
+    fn timeout(&self) -> u64 {
+        self.timeout
+    }
+
+    fn start_registration(
+        &self,
+        user_id: Uuid,
+        username: &str,
+        display_name: &str,
+        exclude_credentials: Vec<CredentialID>,
+    ) -> Result<(CreationChallengeResponse, String), PasskeyError> {
+        let extensions = RequestRegistrationExtensions {
+            cred_protect: Some(CredProtect {
+                credential_protection_policy: credential_protection(self.user_verification),
+                // Authenticators that cannot satisfy it would fail the registration instead.
+                enforce_credential_protection_policy: Some(false),
+            }),
+            uvm: Some(true),
+            cred_props: Some(true),
+            min_pin_length: None,
+            hmac_create_secret: None,
+        };
+        let builder = self
+            .core
+            .new_challenge_register_builder(user_id.as_bytes(), username, display_name)
+            .map_err(PasskeyError::Webauthn)?
+            .attestation(self.attestation.clone())
+            .user_verification_policy(self.user_verification)
+            .require_resident_key(false)
+            .authenticator_attachment(None)
+            .reject_synchronised_authenticators(false)
+            .exclude_credentials(Some(exclude_credentials))
+            .hints(None)
+            .extensions(Some(extensions));
+        let (mut ccr, state) = self
+            .core
+            .generate_challenge_register(builder)
+            .map_err(PasskeyError::Webauthn)?;
+
+        // Ask for a discoverable credential so the passkey also works for usernameless login. This
+        // is only a hint to the authenticator and is not part of the state.
+        if let Some(selection) = ccr.public_key.authenticator_selection.as_mut() {
+            selection.resident_key = Some(ResidentKeyRequirement::Preferred);
+        }
+
+        Ok((ccr, to_json(&state)?))
+    }
+
+    fn finish_registration(
+        &self,
+        credential: &RegisterPublicKeyCredential,
+        state: &str,
+    ) -> Result<Passkey, PasskeyError> {
+        let state: RegistrationState =
+            serde_json::from_str(state).map_err(PasskeyError::InvalidState)?;
+        self.core
+            .register_credential(credential, &state, self.attestation_cas.as_ref())
+            .map(Passkey::from)
+            .map_err(PasskeyError::Webauthn)
+    }
+
+    fn start_authentication(
+        &self,
+        passkeys: &[Passkey],
+    ) -> Result<(RequestChallengeResponse, String), PasskeyError> {
+        let credentials = passkeys.iter().cloned().map(Credential::from).collect();
+        let builder = self
+            .core
+            .new_challenge_authenticate_builder(credentials, Some(self.user_verification))
+            .map_err(PasskeyError::Webauthn)?
+            .extensions(None)
+            .allow_backup_eligible_upgrade(true)
+            .hints(None);
+        let (rcr, state) = self
+            .core
+            .generate_challenge_authenticate(builder)
+            .map_err(PasskeyError::Webauthn)?;
+
+        Ok((rcr, to_json(&state)?))
+    }
+
+    fn finish_authentication(
+        &self,
+        credential: &PublicKeyCredential,
+        state: &str,
+    ) -> Result<AuthenticationResult, PasskeyError> {
+        let state: AuthenticationState =
+            serde_json::from_str(state).map_err(PasskeyError::InvalidState)?;
+        self.core
+            .authenticate_credential(credential, &state)
+            .map_err(PasskeyError::Webauthn)
+    }
+
+    /// Always requires user verification, whatever the configured policy, as the `Webauthn`
+    /// discoverable authentication does.
+    fn start_discoverable_authentication(
+        &self,
+    ) -> Result<(RequestChallengeResponse, String), PasskeyError> {
+        let extensions = RequestAuthenticationExtensions {
+            appid: None,
+            uvm: Some(true),
+            hmac_get_secret: None,
+        };
+        let builder = self
+            .core
+            .new_challenge_authenticate_builder(Vec::new(), Some(UserVerificationPolicy::Required))
+            .map_err(PasskeyError::Webauthn)?
+            .extensions(Some(extensions))
+            .allow_backup_eligible_upgrade(false)
+            .hints(None);
+        let (rcr, state) = self
+            .core
+            .generate_challenge_authenticate(builder)
+            .map_err(PasskeyError::Webauthn)?;
+
+        Ok((rcr, to_json(&state)?))
+    }
+
+    /// Reads the user handle stored at registration.
+    fn identify_discoverable_authentication(
+        &self,
+        credential: &PublicKeyCredential,
+    ) -> Result<Uuid, PasskeyError> {
+        credential
+            .get_user_unique_id()
+            .and_then(|id| Uuid::from_slice(id).ok())
+            .ok_or(PasskeyError::Webauthn(WebauthnError::InvalidUserUniqueId))
+    }
+
+    fn finish_discoverable_authentication(
+        &self,
+        credential: &PublicKeyCredential,
+        state: &str,
+        passkeys: &[Passkey],
+    ) -> Result<AuthenticationResult, PasskeyError> {
+        let mut state: AuthenticationState =
+            serde_json::from_str(state).map_err(PasskeyError::InvalidState)?;
+        state.set_allowed_credentials(passkeys.iter().cloned().map(Credential::from).collect());
+        self.core
+            .authenticate_credential(credential, &state)
+            .map_err(PasskeyError::Webauthn)
+    }
+}
+
+fn attestation(preference: AttestationPreference) -> AttestationConveyancePreference {
+    match preference {
+        AttestationPreference::None => AttestationConveyancePreference::None,
+        AttestationPreference::Indirect => AttestationConveyancePreference::Indirect,
+        AttestationPreference::Direct => AttestationConveyancePreference::Direct,
+    }
+}
+
+fn user_verification(policy: UserVerification) -> UserVerificationPolicy {
+    match policy {
+        UserVerification::Required => UserVerificationPolicy::Required,
+        UserVerification::Preferred => UserVerificationPolicy::Preferred,
+        UserVerification::Discouraged => UserVerificationPolicy::Discouraged_DO_NOT_USE,
+    }
+}
+
+/// Without required verification the credential may still be used by id, but is only
+/// discovered after the user is verified, which usernameless login always requires.
+fn credential_protection(policy: UserVerificationPolicy) -> CredentialProtectionPolicy {
+    match policy {
+        UserVerificationPolicy::Required => CredentialProtectionPolicy::UserVerificationRequired,
+        _ => CredentialProtectionPolicy::UserVerificationOptionalWithCredentialIDList,
+    }
+}
+
+fn to_json<T: serde::Serialize>(state: &T) -> Result<String, PasskeyError> {
+    serde_json::to_string(state).map_err(PasskeyError::InvalidState)
+}
+
+/// Builds the relying party, rejecting origins that are not `rp_id` or one of its subdomains.
+fn build_webauthn(config: &WebauthnConfig) -> Result<WebauthnCore, ConfigError> {
+    let invalid = |message: String| ConfigError::InvalidWebauthn(message);
+
+    let origins = config
+        .origins
+        .iter()
+        .map(|origin| {
+            let url =
+                Url::parse(origin).map_err(|err| invalid(format!("origin {}: {}", origin, err)))?;
+            let host = url.host_str().unwrap_or_default();
+            if host != config.rp_id && !host.ends_with(&format!(".{}", config.rp_id)) {
+                return Err(invalid(format!(
+                    "origin {} is not within rp id {}",
+                    origin, config.rp_id
+                )));
+            }
+            Ok(url)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some((first, others)) = origins.split_first() else {
+        return Err(invalid("at least one origin is required".to_string()));
+    };
+    if config.rp_name.is_empty() {
+        return Err(invalid("rp name must not be empty".to_string()));
+    }
+    if config.timeout == 0 {
+        return Err(invalid("timeout must be positive".to_string()));
+    }
+
+    // Same rp id checks as `Webauthn`, whose fixed passkey policies `WebauthnPasskeys` replaces.
+    WebauthnBuilder::new(&config.rp_id, first)
+        .map_err(|err| invalid(format!("rp id {}: {}", config.rp_id, err)))?;
+
+    Ok(WebauthnCore::new_unsafe_experts_only(
+        &config.rp_name,
+        &config.rp_id,
+        std::iter::once(first).chain(others).cloned().collect(),
+        Duration::from_secs(config.timeout),
+        Some(false),
+        Some(false),
+    ))
+}
+
+/// Reads the attestation CAs, which are required exactly when attestation is requested.
+async fn load_attestation_cas(
+    config: &WebauthnConfig,
+) -> Result<Option<AttestationCaList>, ConfigError> {
+    let invalid = |message: String| ConfigError::InvalidWebauthn(message);
+
+    match (config.attestation, config.attestation_ca_files.is_empty()) {
+        (AttestationPreference::None, true) => return Ok(None),
+        (AttestationPreference::None, false) => {
+            return Err(invalid(
+                "attestation CA files need attestation indirect or direct".to_string(),
+            ));
+        }
+        (_, true) => {
+            return Err(invalid(
+                "attestation indirect or direct needs attestation CA files".to_string(),
+            ));
+        }
+        (_, false) => {}
+    }
+
+    let mut cas = AttestationCaList::default();
+    for path in &config.attestation_ca_files {
+        let pem = tokio::fs::read(path)
+            .await
+            .map_err(|err| invalid(format!("attestation CA {}: {}", path, err)))?;
+        let ca = AttestationCaList::try_from(pem.as_slice())
+            .map_err(|err| invalid(format!("attestation CA {}: {}", path, err)))?;
+        cas.union(&ca);
+    }
+    Ok(Some(cas))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,202 @@
+use uuid::Uuid;
+use webauthn_rs_proto::{
+    CredentialProtectionPolicy, ResidentKeyRequirement, UserVerificationPolicy,
+};
+
+use super::WebauthnPasskeys;
+use crate::{
+    AuthComponent, AuthConfig, ConfigError,
+    config::{AttestationPreference, UserVerification, WebauthnConfig},
+    ports::for_passkeys::ForPasskeys,
+};
+
+/// Self-signed P-256 certificate standing in for an authenticator vendor's attestation root.
+const ATTESTATION_CA: &str = "-----BEGIN CERTIFICATE-----
+MIIBkzCCATmgAwIBAgIUDY1ETcCX90CGmOiwtul+13AlZIgwCgYIKoZIzj0EAwIw
+HjEcMBoGA1UEAwwTVGVzdCBBdHRlc3RhdGlvbiBDQTAgFw0yNjEwMTgwNjE4MjRa
+GA8yMTI2MDkyNDA2MTgyNFowHjEcMBoGA1UEAwwTVGVzdCBBdHRlc3RhdGlvbiBD
+QTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABPbybQbYzNV+mMQKozMqfC7mJw8N
+tKCt85oSHs4VGVDM/IwHDD6qzhUjBgcG1kNki/IzrbUu12JZbH6UQfbXiVKjUzBR
+MB0GA1UdDgQWBBSimCOrnEu6FLTHUZIsZxRpgbzbITAfBgNVHSMEGDAWgBSimCOr
+nEu6FLTHUZIsZxRpgbzbITAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0gA
+MEUCIQC+/KcB7DLdiUh8E31yetg6WzbQZsyGtreSJIVjHyFX2gIgShNFK4Gtl/z9
+SRqHOAoa4PZMRVw/RE+2gSFE1kGIGRg=
+-----END CERTIFICATE-----
+";
+
+/// Writes `ATTESTATION_CA` to a file of its own, returning its path.
+fn attestation_ca_file() -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("attestation-ca-{}.pem", Uuid::new_v4()));
+    std::fs::write(&path, ATTESTATION_CA).unwrap();
+    path
+}
+
+async fn passkeys(config: WebauthnConfig) -> WebauthnPasskeys {
+    WebauthnPasskeys::new(&config).await.unwrap()
+}
+
+async fn with_user_verification(user_verification: UserVerification) -> WebauthnPasskeys {
+    passkeys(WebauthnConfig {
+        user_verification,
+        ..WebauthnConfig::default()
+    })
+    .await
+}
+
+/// The policy recorded in a serialized ceremony state, which is what finishing enforces.
+fn state_policy(state: &str) -> UserVerificationPolicy {
+    let state: serde_json::Value = serde_json::from_str(state).unwrap();
+    serde_json::from_value(state["policy"].clone()).unwrap()
+}
+
+const USER_VERIFICATION: [(UserVerification, UserVerificationPolicy); 3] = [
+    (UserVerification::Required, UserVerificationPolicy::Required),
+    (
+        UserVerification::Preferred,
+        UserVerificationPolicy::Preferred,
+    ),
+    (
+        UserVerification::Discouraged,
+        UserVerificationPolicy::Discouraged_DO_NOT_USE,
+    ),
+];
+
+#[tokio::test]
+async fn registration_uses_the_configured_user_verification() {
+    for (configured, expected) in USER_VERIFICATION {
+        let (ccr, state) = with_user_verification(configured)
+            .await
+            .start_registration(Uuid::new_v4(), "alice", "Alice", Vec::new())
+            .unwrap();
+
+        let selection = ccr.public_key.authenticator_selection.unwrap();
+        assert_eq!(selection.user_verification, expected);
+        assert_eq!(
+            selection.resident_key,
+            Some(ResidentKeyRequirement::Preferred)
+        );
+        assert_eq!(state_policy(&state), expected);
+
+        let protection = ccr
+            .public_key
+            .extensions
+            .and_then(|extensions| extensions.cred_protect)
+            .unwrap()
+            .credential_protection_policy;
+        let expected_protection = if expected == UserVerificationPolicy::Required {
+            CredentialProtectionPolicy::UserVerificationRequired
+        } else {
+            CredentialProtectionPolicy::UserVerificationOptionalWithCredentialIDList
+        };
+        assert_eq!(protection, expected_protection);
+    }
+}
+
+#[tokio::test]
+async fn authentication_uses_the_configured_user_verification() {
+    for (configured, expected) in USER_VERIFICATION {
+        let (rcr, state) = with_user_verification(configured)
+            .await
+            .start_authentication(&[])
+            .unwrap();
+
+        assert_eq!(rcr.public_key.user_verification, expected);
+        assert_eq!(state_policy(&state), expected);
+    }
+}
+
+#[tokio::test]
+async fn usernameless_login_always_requires_user_verification() {
+    for (configured, _) in USER_VERIFICATION {
+        let (rcr, state) = with_user_verification(configured)
+            .await
+            .start_discoverable_authentication()
+            .unwrap();
+
+        assert_eq!(
+            rcr.public_key.user_verification,
+            UserVerificationPolicy::Required
+        );
+        assert_eq!(state_policy(&state), UserVerificationPolicy::Required);
+    }
+}
+
+#[tokio::test]
+async fn registration_asks_for_the_configured_attestation() {
+    // As sent to the browser.
+    let expectations = [
+        (AttestationPreference::None, "none"),
+        (AttestationPreference::Indirect, "indirect"),
+        (AttestationPreference::Direct, "direct"),
+    ];
+    let ca_file = attestation_ca_file();
+    for (configured, expected) in expectations {
+        let attestation_ca_files = match configured {
+            AttestationPreference::None => Vec::new(),
+            _ => vec![ca_file.to_string_lossy().into_owned()],
+        };
+        let (ccr, _) = passkeys(WebauthnConfig {
+            attestation: configured,
+            attestation_ca_files,
+            ..WebauthnConfig::default()
+        })
+        .await
+        .start_registration(Uuid::new_v4(), "alice", "Alice", Vec::new())
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(ccr.public_key.attestation).unwrap(),
+            expected
+        );
+    }
+    let _ = std::fs::remove_file(&ca_file);
+}
+
+async fn build(webauthn: WebauthnConfig) -> Result<AuthComponent, ConfigError> {
+    AuthComponent::builder(AuthConfig {
+        webauthn,
+        ..AuthConfig::default()
+    })
+    .build()
+    .await
+}
+
+#[tokio::test]
+async fn attestation_requires_ca_files_and_the_reverse() {
+    let ca_file = attestation_ca_file();
+    let ca_files = vec![ca_file.to_string_lossy().into_owned()];
+
+    let without_cas = build(WebauthnConfig {
+        attestation: AttestationPreference::Direct,
+        ..WebauthnConfig::default()
+    })
+    .await;
+    assert!(matches!(without_cas, Err(ConfigError::InvalidWebauthn(_))));
+
+    let without_attestation = build(WebauthnConfig {
+        attestation_ca_files: ca_files.clone(),
+        ..WebauthnConfig::default()
+    })
+    .await;
+    assert!(matches!(
+        without_attestation,
+        Err(ConfigError::InvalidWebauthn(_))
+    ));
+
+    let unreadable = build(WebauthnConfig {
+        attestation: AttestationPreference::Direct,
+        attestation_ca_files: vec!["/nonexistent/ca.pem".to_string()],
+        ..WebauthnConfig::default()
+    })
+    .await;
+    assert!(matches!(unreadable, Err(ConfigError::InvalidWebauthn(_))));
+
+    let attested = build(WebauthnConfig {
+        attestation: AttestationPreference::Direct,
+        attestation_ca_files: ca_files,
+        ..WebauthnConfig::default()
+    })
+    .await;
+    let _ = std::fs::remove_file(&ca_file);
+    assert!(attested.is_ok());
+}
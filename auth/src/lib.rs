@@ -1,7 +1,13 @@
 mod component;
-mod entities;
+mod config;
+pub mod entities;
 mod infra;
-mod ports;
+pub mod ports;
 mod usecases;
 
-pub use component::AuthComponent;
+pub use component::{AuthComponent, AuthComponentBuilder};
+pub use config::{
+    AttestationPreference, AuthConfig, ConfigError, PasswordAlgorithm, PasswordHashingConfig,
+    PasswordPolicyConfig, ThrottleConfig, TokenConfig, TotpAlgorithm, TotpConfig,
+    UserRepositoryConfig, UserVerification, WebauthnConfig,
+};
@@ -0,0 +1,23 @@
+use async_trait::async_trait;
+use contracts::auth::error::AttemptStoreError;
+
+use crate::entities::failed_attempts::FailedAttempts;
+
+/// Failed attempts per key. Entries are dropped once their `expires_at` has passed.
+#[async_trait]
+pub trait AttemptStore: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<FailedAttempts>, AttemptStoreError>;
+    /// Counts a failure at `at` (unix seconds), starting over if the entry had expired, and
+    /// keeps the entry at least until `expires_at`.
+    async fn record_failure(
+        &self,
+        key: &str,
+        at: u64,
+        expires_at: u64,
+    ) -> Result<FailedAttempts, AttemptStoreError>;
+    /// Takes back one failure counted by `record_failure`, for an attempt that did not fail.
+    async fn release(&self, key: &str) -> Result<(), AttemptStoreError>;
+    /// Locks the key until `until`, keeping the entry at least that long.
+    async fn lock(&self, key: &str, until: u64) -> Result<(), AttemptStoreError>;
+    async fn clear(&self, key: &str) -> Result<(), AttemptStoreError>;
+}
@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+use contracts::auth::error::BreachedPasswordsError;
+
+/// Passwords known from breaches. An adapter backed by a remote service should query it by
+/// range, as in the Pwned Passwords k-anonymity API, so the service never sees the password or
+/// its full hash.
+#[async_trait]
+pub trait BreachedPasswords: Send + Sync {
+    async fn is_breached(&self, password: &str) -> Result<bool, BreachedPasswordsError>;
+}
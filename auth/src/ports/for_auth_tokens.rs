@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use contracts::auth::error::AuthTokenError;
 
-use crate::entities::claims::Claims;
+use crate::entities::{claims::Claims, signing_key_set::KeyRotation};
 
 #[async_trait]
 pub trait ForAuthTokens: Send + Sync {
@@ -12,4 +12,7 @@ pub trait ForAuthTokens: Send + Sync {
         token_type: String,
     ) -> Result<Claims, AuthTokenError>;
     async fn get_jwks(&self) -> Result<String, AuthTokenError>;
+    /// Promotes the next signing key and stages a new one. Retired keys stay verifiable for
+    /// at least `max_token_lifetime` seconds.
+    async fn rotate_keys(&self, max_token_lifetime: u64) -> Result<KeyRotation, AuthTokenError>;
 }
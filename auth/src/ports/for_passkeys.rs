@@ -0,0 +1,59 @@
+use contracts::auth::error::PasskeyError;
+use uuid::Uuid;
+use webauthn_rs::prelude::{
+    AuthenticationResult, CreationChallengeResponse, CredentialID, Passkey, PublicKeyCredential,
+    RegisterPublicKeyCredential, RequestChallengeResponse,
+};
+
+/// Passkey ceremonies of the relying party. Starting one returns the challenge for the browser
+/// and the ceremony state as JSON, which the caller keeps until it finishes the ceremony.
+pub trait ForPasskeys: Send + Sync {
+    /// Seconds a started ceremony stays valid.
+    fn timeout(&self) -> u64;
+
+    /// Registers a passkey for the user, excluding the credentials already registered.
+    fn start_registration(
+        &self,
+        user_id: Uuid,
+        username: &str,
+        display_name: &str,
+        exclude_credentials: Vec<CredentialID>,
+    ) -> Result<(CreationChallengeResponse, String), PasskeyError>;
+
+    fn finish_registration(
+        &self,
+        credential: &RegisterPublicKeyCredential,
+        state: &str,
+    ) -> Result<Passkey, PasskeyError>;
+
+    /// Authenticates with one of `passkeys`, as a second factor.
+    fn start_authentication(
+        &self,
+        passkeys: &[Passkey],
+    ) -> Result<(RequestChallengeResponse, String), PasskeyError>;
+
+    fn finish_authentication(
+        &self,
+        credential: &PublicKeyCredential,
+        state: &str,
+    ) -> Result<AuthenticationResult, PasskeyError>;
+
+    /// Usernameless login with a discoverable passkey, which is the only factor.
+    fn start_discoverable_authentication(
+        &self,
+    ) -> Result<(RequestChallengeResponse, String), PasskeyError>;
+
+    /// The user whose passkey answered a discoverable authentication.
+    fn identify_discoverable_authentication(
+        &self,
+        credential: &PublicKeyCredential,
+    ) -> Result<Uuid, PasskeyError>;
+
+    /// Finishes a discoverable authentication against the passkeys of the identified user.
+    fn finish_discoverable_authentication(
+        &self,
+        credential: &PublicKeyCredential,
+        state: &str,
+        passkeys: &[Passkey],
+    ) -> Result<AuthenticationResult, PasskeyError>;
+}
@@ -1,8 +1,20 @@
 use async_trait::async_trait;
 use contracts::auth::error::TotpError;
 
+use crate::entities::totp_credential::TotpParameters;
+
 #[async_trait]
 pub trait ForTotp: Send + Sync {
-    async fn verify(&self, secret: String, token: String) -> Result<bool, TotpError>;
-    async fn auth_url(&self, issuer: String, email: String) -> Result<(String, String), TotpError>;
+    /// Returns the time step `token` matched, or `None` when it is wrong or its step is not
+    /// after `last_step`. Without `parameters` the configured ones are used.
+    async fn verify(
+        &self,
+        secret: String,
+        parameters: Option<TotpParameters>,
+        token: String,
+        last_step: Option<u64>,
+    ) -> Result<Option<u64>, TotpError>;
+
+    /// Generates a secret and its otpauth URL for `account` with the configured parameters.
+    async fn auth_url(&self, account: String) -> Result<(String, String), TotpError>;
 }
@@ -6,4 +6,15 @@ use uuid::Uuid;
 pub trait HSMStore: Send + Sync {
     fn get(&self, user_id: Uuid, key: &str) -> Result<Option<String>, HSMStoreError>;
     fn set(&self, user_id: Uuid, key: &str, value: &str) -> Result<(), HSMStoreError>;
+    /// Stores a value that is dropped once `expires_at` (unix seconds) has passed. Fails with
+    /// `Full` when the store holds as many values as it is willing to.
+    fn set_expiring(
+        &self,
+        id: Uuid,
+        key: &str,
+        value: &str,
+        expires_at: u64,
+    ) -> Result<(), HSMStoreError>;
+    /// Returns and deletes the value, so it can be used only once.
+    fn take(&self, id: Uuid, key: &str) -> Result<Option<String>, HSMStoreError>;
 }
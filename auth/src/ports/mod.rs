@@ -1,4 +1,10 @@
+pub mod attempt_store;
+pub mod breached_passwords;
 pub mod for_auth_tokens;
+pub mod for_passkeys;
 pub mod for_totp;
 pub mod hsm_store;
+pub mod password_hasher;
+pub mod refresh_token_store;
+pub mod token_revocation_store;
 pub mod user_repository;
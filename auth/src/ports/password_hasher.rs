@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+use contracts::auth::error::PasswordHasherError;
+
+/// Hashes and checks passwords. The work is CPU bound and slow on purpose, so implementations
+/// must not run it on the async executor.
+#[async_trait]
+pub trait PasswordHasher: Send + Sync {
+    /// Hashes with the current algorithm and parameters.
+    async fn hash(&self, password: String) -> Result<String, PasswordHasherError>;
+    /// Whether `password` matches `hash`, whichever supported algorithm produced it.
+    async fn verify(&self, password: String, hash: String) -> Result<bool, PasswordHasherError>;
+    /// Whether `hash` uses another algorithm or other parameters than new hashes.
+    fn needs_rehash(&self, hash: &str) -> bool;
+    /// Hashes of a throwaway password, one with each algorithm stored hashes may use, to verify
+    /// in place of a hash that is missing or uses another algorithm.
+    async fn dummy_hashes(&self) -> Result<Vec<String>, PasswordHasherError>;
+    /// Whether `a` and `b` were made with the same algorithm, so verifying either takes as long.
+    fn same_algorithm(&self, a: &str, b: &str) -> bool;
+}
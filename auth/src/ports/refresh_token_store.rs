@@ -0,0 +1,18 @@
+use async_trait::async_trait;
+use contracts::auth::error::RefreshTokenStoreError;
+
+use crate::entities::refresh_token::RefreshTokenStatus;
+
+#[async_trait]
+pub trait RefreshTokenStore: Send + Sync {
+    async fn register(
+        &self,
+        jti: &str,
+        family_id: &str,
+        expires_at: usize,
+    ) -> Result<(), RefreshTokenStoreError>;
+    /// Marks the token as used. Of concurrent calls with the same `jti` only one gets `Valid`.
+    async fn consume(&self, jti: &str) -> Result<RefreshTokenStatus, RefreshTokenStoreError>;
+    /// Forgets every token of the family, so all of them become `Unknown`.
+    async fn revoke_family(&self, family_id: &str) -> Result<(), RefreshTokenStoreError>;
+}
@@ -0,0 +1,20 @@
+use async_trait::async_trait;
+use contracts::auth::error::TokenRevocationStoreError;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait TokenRevocationStore: Send + Sync {
+    /// Revokes a single token; the entry can be dropped once `expires_at` has passed.
+    async fn revoke(&self, jti: &str, expires_at: usize) -> Result<(), TokenRevocationStoreError>;
+    /// Revokes every token of `user_id` issued so far by bumping the user's generation.
+    async fn revoke_all(&self, user_id: Uuid) -> Result<(), TokenRevocationStoreError>;
+    /// Current generation of `user_id`, stamped on new tokens; `0` until the first `revoke_all`.
+    async fn generation(&self, user_id: Uuid) -> Result<u64, TokenRevocationStoreError>;
+    /// Whether `jti` was revoked or was issued at an older generation than the current one.
+    async fn is_revoked(
+        &self,
+        jti: &str,
+        user_id: Uuid,
+        generation: u64,
+    ) -> Result<bool, TokenRevocationStoreError>;
+}
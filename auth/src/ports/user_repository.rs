@@ -1,10 +1,40 @@
-use crate::entities::user::User;
+use crate::entities::{totp_credential::TotpCredential, user::User};
 use async_trait::async_trait;
 use contracts::auth::error::UserRepositoryError;
 
 #[async_trait]
 pub trait UserRepository: Send + Sync {
-    async fn save(&self, credential: User) -> Result<(), UserRepositoryError>;
+    /// Inserts a new user, failing with `Conflict` if the username is already taken.
+    async fn create(&self, user: User) -> Result<(), UserRepositoryError>;
+    /// Replaces the user with the same id, failing with `NotFound` if it does not exist, with
+    /// `Stale` if it was written since `user` was read, and with `Conflict` if it is renamed to a
+    /// username that is taken. Every write, this one and the targeted ones below, bumps the
+    /// user's version.
+    async fn update(&self, user: User) -> Result<(), UserRepositoryError>;
     async fn find_username(&self, username: String) -> Result<Option<User>, UserRepositoryError>;
     async fn find_id(&self, id: uuid::Uuid) -> Result<Option<User>, UserRepositoryError>;
+    /// Replaces the user's TOTP credential that has the same secret as `credential`, but only
+    /// while its last accepted step is still `last_step`. Returns whether it was replaced, so a
+    /// code is accepted by one of several concurrent requests only.
+    async fn record_totp_use(
+        &self,
+        id: uuid::Uuid,
+        credential: &TotpCredential,
+        last_step: Option<u64>,
+    ) -> Result<bool, UserRepositoryError>;
+    /// Removes the recovery code hashed as `code_hash` from the user in one step, returning
+    /// whether it was there, so concurrent requests cannot both use the same code.
+    async fn consume_recovery_code(
+        &self,
+        id: uuid::Uuid,
+        code_hash: &str,
+    ) -> Result<bool, UserRepositoryError>;
+    /// Replaces the user's password hash with `new_hash`, but only while it is still
+    /// `old_hash`, leaving the rest of the user alone. Returns whether it was replaced.
+    async fn update_password_hash(
+        &self,
+        id: uuid::Uuid,
+        old_hash: &str,
+        new_hash: &str,
+    ) -> Result<bool, UserRepositoryError>;
 }
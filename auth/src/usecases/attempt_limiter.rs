@@ -0,0 +1,191 @@
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use contracts::auth::error::AuthError;
+
+use crate::{
+    config::ThrottleConfig, entities::failed_attempts::AttemptKey,
+    ports::attempt_store::AttemptStore,
+};
+
+/// Applies the backoff and lockout of [`ThrottleConfig`] to password and second factor guesses.
+pub struct AttemptLimiter {
+    attempt_store: Arc<dyn AttemptStore>,
+    config: ThrottleConfig,
+}
+
+impl AttemptLimiter {
+    pub fn new(attempt_store: Arc<dyn AttemptStore>, config: ThrottleConfig) -> Self {
+        Self {
+            attempt_store,
+            config,
+        }
+    }
+
+    /// Runs `attempt` unless one of `keys` is locked or backing off. The attempt is counted as a
+    /// failure against every key before it runs, so concurrent guesses cannot all slip through
+    /// the same check; a wrong credential keeps that count, a success clears the keys that reset
+    /// on success and any other outcome takes it back.
+    pub async fn guard<T>(
+        &self,
+        keys: &[AttemptKey],
+        attempt: impl Future<Output = Result<T, AuthError>>,
+    ) -> Result<T, AuthError> {
+        let seen = self.check(keys).await?;
+        let reserved = self.reserve(keys, &seen).await?;
+
+        match attempt.await {
+            Ok(value) => {
+                self.reset(keys).await?;
+                Ok(value)
+            }
+            Err(
+                err @ (AuthError::InvalidUsernameOrPassword
+                | AuthError::InvalidTotpCode
+                | AuthError::InvalidRecoveryCode),
+            ) => {
+                self.lock_exhausted(keys, &reserved).await?;
+                Err(err)
+            }
+            Err(err) => {
+                self.release(keys).await?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Failures of each key, refusing the attempt while one of them is locked or backing off.
+    async fn check(&self, keys: &[AttemptKey]) -> Result<Vec<u32>, AuthError> {
+        let now = now();
+        let mut seen = Vec::with_capacity(keys.len());
+        let mut retry_after = 0;
+        for key in keys {
+            let Some(attempts) = self
+                .attempt_store
+                .get(&key.id())
+                .await
+                .map_err(AuthError::AttemptStoreError)?
+            else {
+                seen.push(0);
+                continue;
+            };
+            seen.push(attempts.failures);
+
+            let (free_attempts, _) = self.limits(key);
+            let allowed_at = match attempts.locked_until {
+                Some(locked_until) if locked_until > now => locked_until,
+                _ if attempts.failures >= free_attempts => {
+                    attempts.last_failure_at + self.delay(attempts.failures - free_attempts)
+                }
+                _ => continue,
+            };
+            retry_after = retry_after.max(allowed_at.saturating_sub(now));
+        }
+
+        if retry_after > 0 {
+            return Err(AuthError::TooManyAttempts { retry_after });
+        }
+        Ok(seen)
+    }
+
+    /// Counts the attempt against every key. Past the free attempts, only the attempt that
+    /// passed `check` on the failures it counted may run; attempts that other ones overtook in
+    /// the meantime, or that go past the lockout threshold, are refused and taken back.
+    async fn reserve(&self, keys: &[AttemptKey], seen: &[u32]) -> Result<Vec<u32>, AuthError> {
+        let now = now();
+        let mut reserved = Vec::with_capacity(keys.len());
+        let mut retry_after = 0;
+        for (key, &seen) in keys.iter().zip(seen) {
+            let failures = self
+                .attempt_store
+                .record_failure(&key.id(), now, now + self.config.lockout_duration)
+                .await
+                .map_err(AuthError::AttemptStoreError)?
+                .failures;
+            reserved.push(failures);
+
+            let (free_attempts, lockout_threshold) = self.limits(key);
+            if failures > lockout_threshold {
+                retry_after = retry_after.max(self.config.lockout_duration);
+            } else if failures > free_attempts && failures > seen + 1 {
+                retry_after = retry_after.max(self.delay(failures - 1 - free_attempts));
+            }
+        }
+
+        if retry_after > 0 {
+            self.release(keys).await?;
+            return Err(AuthError::TooManyAttempts { retry_after });
+        }
+        Ok(reserved)
+    }
+
+    /// Locks the keys whose failures, counting this attempt, reached the lockout threshold.
+    async fn lock_exhausted(&self, keys: &[AttemptKey], reserved: &[u32]) -> Result<(), AuthError> {
+        let until = now() + self.config.lockout_duration;
+        for (key, &failures) in keys.iter().zip(reserved) {
+            let (_, lockout_threshold) = self.limits(key);
+            if failures >= lockout_threshold {
+                self.attempt_store
+                    .lock(&key.id(), until)
+                    .await
+                    .map_err(AuthError::AttemptStoreError)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Clears the keys that reset on success and takes the attempt back from the others.
+    async fn reset(&self, keys: &[AttemptKey]) -> Result<(), AuthError> {
+        for key in keys {
+            let id = key.id();
+            let cleared = if key.resets_on_success() {
+                self.attempt_store.clear(&id).await
+            } else {
+                self.attempt_store.release(&id).await
+            };
+            cleared.map_err(AuthError::AttemptStoreError)?;
+        }
+        Ok(())
+    }
+
+    async fn release(&self, keys: &[AttemptKey]) -> Result<(), AuthError> {
+        for key in keys {
+            self.attempt_store
+                .release(&key.id())
+                .await
+                .map_err(AuthError::AttemptStoreError)?;
+        }
+        Ok(())
+    }
+
+    /// Free attempts and lockout threshold of `key`.
+    fn limits(&self, key: &AttemptKey) -> (u32, u32) {
+        let allowance = match key {
+            AttemptKey::Ip(_) => self.config.ip_allowance,
+            _ => 1,
+        };
+        (
+            self.config.free_attempts.saturating_mul(allowance),
+            self.config.lockout_threshold.saturating_mul(allowance),
+        )
+    }
+
+    /// Wait after `excess` failures past the free attempts.
+    fn delay(&self, excess: u32) -> u64 {
+        let factor = 1u64.checked_shl(excess).unwrap_or(u64::MAX);
+        self.config
+            .base_delay
+            .saturating_mul(factor)
+            .min(self.config.max_delay)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs()
+}
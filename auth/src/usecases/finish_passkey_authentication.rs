@@ -1,64 +1,72 @@
 use crate::entities::totp::WEBAUTHN_AUTH_STATE;
+use crate::ports::for_passkeys::ForPasskeys;
 use crate::ports::hsm_store::HSMStore;
 use crate::ports::user_repository::UserRepository;
+use crate::usecases::modify_user::modify_user;
+use crate::usecases::session_tokens::SessionTokenIssuer;
 use contracts::auth::error::AuthError;
-use contracts::auth::passkey::PasskeyFinishAuthenticationInput;
+use contracts::auth::passkey::{
+    PasskeyFinishAuthenticationInput, PasskeyFinishAuthenticationOutput,
+};
 use std::sync::Arc;
-use webauthn_rs::Webauthn;
-use webauthn_rs::prelude::PasskeyAuthentication;
 
 pub struct FinishPasskeyAuthenticationUseCase {
     user_repository: Arc<dyn UserRepository>,
     hsm_store: Arc<dyn HSMStore>,
-    webauthn: Arc<Webauthn>,
+    for_passkeys: Arc<dyn ForPasskeys>,
+    session_token_issuer: Arc<SessionTokenIssuer>,
 }
 
 impl FinishPasskeyAuthenticationUseCase {
     pub fn new(
         user_repository: Arc<dyn UserRepository>,
         hsm_store: Arc<dyn HSMStore>,
-        webauthn: Arc<Webauthn>,
+        for_passkeys: Arc<dyn ForPasskeys>,
+        session_token_issuer: Arc<SessionTokenIssuer>,
     ) -> Self {
         Self {
             user_repository,
             hsm_store,
-            webauthn,
+            for_passkeys,
+            session_token_issuer,
         }
     }
 
-    pub async fn execute(&self, input: PasskeyFinishAuthenticationInput) -> Result<(), AuthError> {
+    pub async fn execute(
+        &self,
+        input: PasskeyFinishAuthenticationInput,
+    ) -> Result<PasskeyFinishAuthenticationOutput, AuthError> {
+        // Taking the state consumes it, so a replayed finish finds nothing. A start that failed
+        // after clearing the previous state leaves an empty value.
         let auth_state_str = self
             .hsm_store
-            .get(input.user_id, WEBAUTHN_AUTH_STATE)
+            .take(input.user_id, WEBAUTHN_AUTH_STATE)
             .map_err(AuthError::GetHsmStoreError)?
-            .ok_or_else(|| AuthError::WebAuthnAuthenticationNotFound)?;
-
-        self.hsm_store
-            .set(input.user_id, WEBAUTHN_AUTH_STATE, "")
-            .map_err(AuthError::SetHsmStoreError)?;
-        let auth_state: PasskeyAuthentication =
-            serde_json::from_str(&auth_state_str).map_err(AuthError::SerdeError)?;
-
+            .filter(|state| !state.is_empty())
+            .ok_or(AuthError::WebAuthnAuthenticationNotFound)?;
         let auth_result = self
-            .webauthn
-            .finish_passkey_authentication(&input.public_key_credential, &auth_state)
-            .map_err(AuthError::WebauthnError)?;
+            .for_passkeys
+            .finish_authentication(&input.public_key_credential, &auth_state_str)
+            .map_err(AuthError::from)?;
 
-        let mut user = self
-            .user_repository
-            .find_id(input.user_id)
-            .await
-            .map_err(AuthError::FindUserError)?
-            .ok_or_else(|| AuthError::UserNotFound)?;
+        let user_id = input.user_id;
+        modify_user(self.user_repository.as_ref(), user_id, |user| {
+            let credential = user
+                .pass_keys
+                .iter_mut()
+                .find(|k| k.passkey.cred_id() == auth_result.cred_id())
+                .ok_or(AuthError::PasskeyNotFound)?;
+            credential.passkey.update_credential(&auth_result);
+            credential.mark_used();
+            Ok(())
+        })
+        .await?;
 
-        user.pass_keys.iter_mut().for_each(|k| {
-            k.update_credential(&auth_result);
-        });
-        self.user_repository
-            .save(user)
-            .await
-            .map_err(AuthError::SaveUserError)?;
+        let tokens = self.session_token_issuer.issue(user_id, None).await?;
 
-        Ok(())
+        Ok(PasskeyFinishAuthenticationOutput {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+        })
     }
 }
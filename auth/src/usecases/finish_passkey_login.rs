@@ -0,0 +1,84 @@
+use crate::entities::totp::WEBAUTHN_LOGIN_STATE;
+use crate::ports::for_passkeys::ForPasskeys;
+use crate::ports::hsm_store::HSMStore;
+use crate::ports::user_repository::UserRepository;
+use crate::usecases::modify_user::modify_user;
+use crate::usecases::session_tokens::SessionTokenIssuer;
+use contracts::auth::error::AuthError;
+use contracts::auth::passkey::{PasskeyFinishAuthenticationOutput, PasskeyFinishLoginInput};
+use std::sync::Arc;
+use webauthn_rs::prelude::Passkey;
+
+pub struct FinishPasskeyLoginUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    hsm_store: Arc<dyn HSMStore>,
+    for_passkeys: Arc<dyn ForPasskeys>,
+    session_token_issuer: Arc<SessionTokenIssuer>,
+}
+
+impl FinishPasskeyLoginUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        hsm_store: Arc<dyn HSMStore>,
+        for_passkeys: Arc<dyn ForPasskeys>,
+        session_token_issuer: Arc<SessionTokenIssuer>,
+    ) -> Self {
+        Self {
+            user_repository,
+            hsm_store,
+            for_passkeys,
+            session_token_issuer,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: PasskeyFinishLoginInput,
+    ) -> Result<PasskeyFinishAuthenticationOutput, AuthError> {
+        let auth_state_str = self
+            .hsm_store
+            .take(input.login_id, WEBAUTHN_LOGIN_STATE)
+            .map_err(AuthError::GetHsmStoreError)?
+            .ok_or(AuthError::WebAuthnAuthenticationNotFound)?;
+        // The user handle stored at registration is the user id.
+        let user_id = self
+            .for_passkeys
+            .identify_discoverable_authentication(&input.public_key_credential)
+            .map_err(AuthError::from)?;
+        let user = self
+            .user_repository
+            .find_id(user_id)
+            .await
+            .map_err(AuthError::FindUserError)?
+            .ok_or(AuthError::UserNotFound)?;
+
+        let passkeys: Vec<Passkey> = user.pass_keys.iter().map(|k| k.passkey.clone()).collect();
+        let auth_result = self
+            .for_passkeys
+            .finish_discoverable_authentication(
+                &input.public_key_credential,
+                &auth_state_str,
+                &passkeys,
+            )
+            .map_err(AuthError::from)?;
+
+        modify_user(self.user_repository.as_ref(), user_id, |user| {
+            let credential = user
+                .pass_keys
+                .iter_mut()
+                .find(|k| k.passkey.cred_id() == auth_result.cred_id())
+                .ok_or(AuthError::PasskeyNotFound)?;
+            credential.passkey.update_credential(&auth_result);
+            credential.mark_used();
+            Ok(())
+        })
+        .await?;
+
+        let tokens = self.session_token_issuer.issue(user_id, None).await?;
+
+        Ok(PasskeyFinishAuthenticationOutput {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+        })
+    }
+}
@@ -1,62 +1,79 @@
+use crate::entities::passkey_credential::PasskeyCredential;
 use crate::entities::totp::WEBAUTHN_REG_STATE;
+use crate::ports::for_passkeys::ForPasskeys;
 use crate::ports::hsm_store::HSMStore;
 use crate::ports::user_repository::UserRepository;
+use crate::usecases::modify_user::modify_user;
 use contracts::auth::error::AuthError;
-use contracts::auth::passkey::PasskeyFinishRegistrationInput;
+use contracts::auth::passkey::{PasskeyFinishRegistrationInput, PasskeyFinishRegistrationOutput};
+use serde_cbor_2::Value;
+use std::collections::BTreeMap;
 use std::sync::Arc;
-use webauthn_rs::Webauthn;
-use webauthn_rs::prelude::PasskeyRegistration;
+use uuid::Uuid;
+use webauthn_rs::prelude::RegisterPublicKeyCredential;
 
 pub struct FinishPasskeyRegistrationUseCase {
     user_repository: Arc<dyn UserRepository>,
-    webauthn: Arc<Webauthn>,
+    for_passkeys: Arc<dyn ForPasskeys>,
     hsm_store: Arc<dyn HSMStore>,
 }
 
 impl FinishPasskeyRegistrationUseCase {
     pub fn new(
         user_repository: Arc<dyn UserRepository>,
-        webauthn: Arc<Webauthn>,
+        for_passkeys: Arc<dyn ForPasskeys>,
         hsm_store: Arc<dyn HSMStore>,
     ) -> Self {
         Self {
             user_repository,
-            webauthn,
+            for_passkeys,
             hsm_store,
         }
     }
 
-    pub async fn execute(&self, input: PasskeyFinishRegistrationInput) -> Result<(), AuthError> {
+    pub async fn execute(
+        &self,
+        input: PasskeyFinishRegistrationInput,
+    ) -> Result<PasskeyFinishRegistrationOutput, AuthError> {
+        // Taking the state consumes it, so a replayed finish finds nothing. A start that failed
+        // after clearing the previous state leaves an empty value.
         let reg_state_str = self
             .hsm_store
-            .get(input.user_id, WEBAUTHN_REG_STATE)
+            .take(input.user_id, WEBAUTHN_REG_STATE)
             .map_err(AuthError::GetHsmStoreError)?
-            .ok_or_else(|| AuthError::WebAuthnRegistrationNotFound)?;
-
-        let reg_state: PasskeyRegistration =
-            serde_json::from_str(&reg_state_str).map_err(AuthError::SerdeError)?;
-        self.hsm_store
-            .set(input.user_id, WEBAUTHN_REG_STATE, "")
-            .map_err(AuthError::SetHsmStoreError)?;
+            .filter(|state| !state.is_empty())
+            .ok_or(AuthError::WebAuthnRegistrationNotFound)?;
 
         let sk = self
-            .webauthn
-            .finish_passkey_registration(&input.register_public_key_credential, &reg_state)
-            .map_err(AuthError::WebauthnError)?;
-
-        let mut user = self
-            .user_repository
-            .find_id(input.user_id)
-            .await
-            .map_err(AuthError::FindUserError)?
-            .ok_or_else(|| AuthError::UserNotFound)?;
+            .for_passkeys
+            .finish_registration(&input.register_public_key_credential, &reg_state_str)
+            .map_err(AuthError::from)?;
 
-        user.pass_keys.push(sk);
-        self.user_repository
-            .save(user)
-            .await
-            .map_err(AuthError::SaveUserError)?;
+        let aaguid = aaguid(&input.register_public_key_credential);
+        let recovery_codes = modify_user(self.user_repository.as_ref(), input.user_id, |user| {
+            let name = input
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("Passkey {}", user.pass_keys.len() + 1));
+            user.pass_keys
+                .push(PasskeyCredential::new(name, sk.clone(), aaguid));
+            Ok(user.issue_missing_recovery_codes())
+        })
+        .await?;
 
-        Ok(())
+        Ok(PasskeyFinishRegistrationOutput { recovery_codes })
     }
 }
+
+/// Reads the AAGUID from the attested credential data in `authData`, which follows the rp id hash
+/// (32 bytes), flags (1) and sign counter (4). Browsers may zero it when attestation is not
+/// requested, which is reported as unknown.
+fn aaguid(credential: &RegisterPublicKeyCredential) -> Option<Uuid> {
+    let attestation: BTreeMap<String, Value> =
+        serde_cbor_2::from_slice(&credential.response.attestation_object).ok()?;
+    let Some(Value::Bytes(auth_data)) = attestation.get("authData") else {
+        return None;
+    };
+    let aaguid = Uuid::from_slice(auth_data.get(37..53)?).ok()?;
+    (!aaguid.is_nil()).then_some(aaguid)
+}
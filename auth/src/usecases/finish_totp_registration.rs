@@ -1,7 +1,4 @@
-use std::{
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::sync::Arc;
 
 use contracts::auth::{
     error::AuthError,
@@ -10,11 +7,19 @@ use contracts::auth::{
 use webauthn_rs::prelude::Url;
 
 use crate::{
-    entities::{claims::Claims, totp::TOTP_REG_STATE},
+    entities::{
+        failed_attempts::AttemptKey,
+        totp::TOTP_REG_STATE,
+        totp_credential::{TotpCredential, TotpParameters},
+    },
     ports::{
         for_auth_tokens::ForAuthTokens, for_totp::ForTotp, hsm_store::HSMStore,
         user_repository::UserRepository,
     },
+    usecases::{
+        attempt_limiter::AttemptLimiter, modify_user::modify_user,
+        session_tokens::SessionTokenIssuer,
+    },
 };
 
 pub struct FinishTOTPRegistrationUseCase {
@@ -22,6 +27,8 @@ pub struct FinishTOTPRegistrationUseCase {
     for_auth_tokens: Arc<dyn ForAuthTokens>,
     for_totp: Arc<dyn ForTotp>,
     hsm_store: Arc<dyn HSMStore>,
+    session_token_issuer: Arc<SessionTokenIssuer>,
+    attempt_limiter: Arc<AttemptLimiter>,
 }
 
 impl FinishTOTPRegistrationUseCase {
@@ -30,12 +37,16 @@ impl FinishTOTPRegistrationUseCase {
         for_auth_tokens: Arc<dyn ForAuthTokens>,
         for_totp: Arc<dyn ForTotp>,
         hsm_store: Arc<dyn HSMStore>,
+        session_token_issuer: Arc<SessionTokenIssuer>,
+        attempt_limiter: Arc<AttemptLimiter>,
     ) -> Self {
         Self {
             user_repository,
             for_auth_tokens,
             for_totp,
             hsm_store,
+            session_token_issuer,
+            attempt_limiter,
         }
     }
 
@@ -47,11 +58,11 @@ impl FinishTOTPRegistrationUseCase {
             .for_auth_tokens
             .validate_token(input.mfa_token, "mfa_registration".to_string())
             .await
-            .map_err(|_| AuthError::TokenValidationFailed)?;
+            .map_err(AuthError::from)?;
 
         let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
 
-        let Some(mut user) = self
+        let Some(user) = self
             .user_repository
             .find_id(user_id)
             .await
@@ -64,67 +75,62 @@ impl FinishTOTPRegistrationUseCase {
             .hsm_store
             .get(user.id, TOTP_REG_STATE)
             .map_err(AuthError::GetHsmStoreError)?
-            .ok_or_else(|| AuthError::TotpRegistrationNotFound)?;
+            .ok_or(AuthError::TotpRegistrationNotFound)?;
 
         self.hsm_store
             .set(user.id, TOTP_REG_STATE, "")
             .map_err(AuthError::SetHsmStoreError)?;
 
-        let secret = Url::parse(&reg_state_str)
-            .ok()
-            .and_then(|u| {
-                u.query_pairs()
-                    .find(|(key, _)| key == "secret")
-                    .map(|(_, val)| val.into_owned())
-            })
-            .ok_or_else(|| AuthError::TotpRegistrationNotFound)?;
-
-        let result = self
-            .for_totp
-            .verify(secret.clone(), input.code)
-            .await
-            .map_err(AuthError::TotpError)?;
-
-        if !result {
-            return Err(AuthError::MFATokenCreationFailed);
-        }
-
-        let start = SystemTime::now();
-        let since_the_epoch = start
-            .duration_since(UNIX_EPOCH)
-            .expect("time should go forward");
-
-        let exp = (since_the_epoch.as_secs() + 604800) as usize; // 7 days from now
-        let refresh_token = self
-            .for_auth_tokens
-            .create_token(Claims {
-                token_type: "refresh".to_string(),
-                sub: user.id.to_string(),
-                exp,
+        // The enrolment URL is the source of truth: the app was configured from it, even if the
+        // configuration changed since.
+        let url = Url::parse(&reg_state_str).map_err(|_| AuthError::TotpRegistrationNotFound)?;
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, val)| val.into_owned())
+        };
+        let secret = param("secret").ok_or(AuthError::TotpRegistrationNotFound)?;
+        let parameters = (|| {
+            Some(TotpParameters {
+                algorithm: param("algorithm")?.parse().ok()?,
+                digits: param("digits")?.parse().ok()?,
+                step: param("period")?.parse().ok()?,
             })
-            .await
-            .map_err(|_| AuthError::RefreshTokenCreationFailed)?;
-
-        let exp = (since_the_epoch.as_secs() + 600) as usize; // 10 minutes from now
-        let access_token = self
-            .for_auth_tokens
-            .create_token(Claims {
-                token_type: "access".to_string(),
-                sub: user.id.to_string(),
-                exp,
+        })()
+        .ok_or(AuthError::TotpRegistrationNotFound)?;
+
+        let step = self
+            .attempt_limiter
+            .guard(&[AttemptKey::SecondFactor(user_id)], async {
+                self.for_totp
+                    .verify(secret.clone(), Some(parameters), input.code, None)
+                    .await
+                    .map_err(AuthError::TotpError)?
+                    .ok_or(AuthError::InvalidTotpCode)
             })
-            .await
-            .map_err(|_| AuthError::AccessTokenCreationFailed)?;
+            .await?;
+
+        let recovery_codes = modify_user(self.user_repository.as_ref(), user_id, |user| {
+            let label = input
+                .label
+                .clone()
+                .unwrap_or_else(|| format!("Autenticador {}", user.totp_credentials.len() + 1));
+            user.totp_credentials.push(TotpCredential::new(
+                label,
+                secret.clone(),
+                parameters,
+                step,
+            ));
+            Ok(user.issue_missing_recovery_codes())
+        })
+        .await?;
 
-        user.otp_secret = Some(secret);
-        self.user_repository
-            .save(user)
-            .await
-            .map_err(AuthError::SaveUserError)?;
+        let tokens = self.session_token_issuer.issue(user_id, None).await?;
 
         Ok(TOTPFinishRegistrationOutput {
-            access_token,
-            refresh_token,
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+            recovery_codes,
         })
     }
 }
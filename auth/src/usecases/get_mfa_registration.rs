@@ -1,7 +1,4 @@
-use std::{
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::sync::Arc;
 
 use contracts::auth::{
     error::AuthError,
@@ -9,23 +6,26 @@ use contracts::auth::{
 };
 
 use crate::{
-    entities::claims::Claims,
+    entities::claims::{Claims, MFA_MANAGE_SCOPE},
     ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository},
 };
 
 pub struct GetMfaRegistrationUseCase {
     user_repository: Arc<dyn UserRepository>,
     for_auth_tokens: Arc<dyn ForAuthTokens>,
+    mfa_registration_ttl: u64,
 }
 
 impl GetMfaRegistrationUseCase {
     pub fn new(
         user_repository: Arc<dyn UserRepository>,
         for_auth_tokens: Arc<dyn ForAuthTokens>,
+        mfa_registration_ttl: u64,
     ) -> Self {
         Self {
             user_repository,
             for_auth_tokens,
+            mfa_registration_ttl,
         }
     }
 
@@ -37,7 +37,7 @@ impl GetMfaRegistrationUseCase {
             .for_auth_tokens
             .validate_token(input.access_token, "access".to_string())
             .await
-            .map_err(|_| AuthError::TokenValidationFailed)?;
+            .map_err(AuthError::from)?;
 
         let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
         let user = self
@@ -45,21 +45,22 @@ impl GetMfaRegistrationUseCase {
             .find_id(user_id)
             .await
             .map_err(AuthError::FindUserError)?
-            .ok_or_else(|| AuthError::UserNotFound)?;
+            .ok_or(AuthError::UserNotFound)?;
+
+        // The first factor can be enrolled with a plain session; after that, changing MFA
+        // requires a stepped-up token.
+        if user.has_mfa() && !claims.has_scope(MFA_MANAGE_SCOPE) {
+            return Err(AuthError::InsufficientScope);
+        }
 
-        let start = SystemTime::now();
-        let since_the_epoch = start
-            .duration_since(UNIX_EPOCH)
-            .expect("time should go forward");
-        let expires_in = 180;
-        let exp = (since_the_epoch.as_secs() + expires_in) as usize;
+        let expires_in = self.mfa_registration_ttl;
         let mfa_registration = self
             .for_auth_tokens
-            .create_token(Claims {
-                token_type: "mfa_registration".to_string(),
-                sub: user.id.to_string(),
-                exp,
-            })
+            .create_token(Claims::new(
+                "mfa_registration",
+                user.id.to_string(),
+                expires_in,
+            ))
             .await
             .map_err(|_| AuthError::MFATokenCreationFailed)?;
 
@@ -0,0 +1,53 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    error::AuthError,
+    passkey::{PasskeyListInput, PasskeyOutput},
+};
+
+use crate::ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository};
+
+pub struct ListPasskeysUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+}
+
+impl ListPasskeysUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+        }
+    }
+
+    pub async fn execute(&self, input: PasskeyListInput) -> Result<Vec<PasskeyOutput>, AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.access_token, "access".to_string())
+            .await
+            .map_err(AuthError::from)?;
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        let user = self
+            .user_repository
+            .find_id(user_id)
+            .await
+            .map_err(AuthError::FindUserError)?
+            .ok_or(AuthError::UserNotFound)?;
+
+        Ok(user
+            .pass_keys
+            .into_iter()
+            .map(|credential| PasskeyOutput {
+                id: credential.id,
+                name: credential.name,
+                created_at: credential.created_at,
+                last_used_at: credential.last_used_at,
+                aaguid: credential.aaguid,
+            })
+            .collect())
+    }
+}
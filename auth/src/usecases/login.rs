@@ -1,106 +1,170 @@
-use std::{
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use std::sync::Arc;
 
 use crate::{
-    entities::claims::Claims,
-    ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository},
+    entities::{claims::Claims, failed_attempts::AttemptKey, user::User},
+    ports::{
+        for_auth_tokens::ForAuthTokens, password_hasher::PasswordHasher,
+        user_repository::UserRepository,
+    },
+    usecases::{attempt_limiter::AttemptLimiter, session_tokens::SessionTokenIssuer},
 };
-use bcrypt::verify;
 use contracts::auth::{
-    error::AuthError,
+    error::{AuthError, PasswordHasherError},
     login::{LoginInput, LoginOutput},
 };
+use log::warn;
 
 pub struct LoginUseCase {
     user_repository: Arc<dyn UserRepository>,
     for_auth_tokens: Arc<dyn ForAuthTokens>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    session_token_issuer: Arc<SessionTokenIssuer>,
+    attempt_limiter: Arc<AttemptLimiter>,
+    mfa_verification_ttl: u64,
+    /// One per algorithm stored hashes may use, verified in place of the user's hash when the
+    /// username is unknown or the hash uses another algorithm.
+    dummy_hashes: Vec<String>,
 }
 
 impl LoginUseCase {
-    pub fn new(
+    /// Computes the dummy hashes up front, so the first login of an unknown username is not the
+    /// one that pays for them.
+    pub async fn new(
         user_repository: Arc<dyn UserRepository>,
         for_auth_tokens: Arc<dyn ForAuthTokens>,
-    ) -> Self {
-        Self {
+        password_hasher: Arc<dyn PasswordHasher>,
+        session_token_issuer: Arc<SessionTokenIssuer>,
+        attempt_limiter: Arc<AttemptLimiter>,
+        mfa_verification_ttl: u64,
+    ) -> Result<Self, PasswordHasherError> {
+        let dummy_hashes = password_hasher.dummy_hashes().await?;
+        Ok(Self {
             user_repository,
             for_auth_tokens,
-        }
+            password_hasher,
+            session_token_issuer,
+            attempt_limiter,
+            mfa_verification_ttl,
+            dummy_hashes,
+        })
     }
 
     pub async fn execute(&self, input: LoginInput) -> Result<LoginOutput, AuthError> {
+        let mut keys = vec![AttemptKey::Username(input.username.clone())];
+        keys.extend(input.client_ip.map(AttemptKey::Ip));
         let credential = self
-            .user_repository
-            .find_username(input.username.to_string())
-            .await
-            .map_err(AuthError::FindUserError)?
-            .ok_or_else(|| AuthError::UserNotFound)?;
-
-        if !verify(&input.password, &credential.password).map_err(AuthError::BcryptError)? {
-            return Err(AuthError::InvalidUsernameOrPassword);
+            .attempt_limiter
+            .guard(&keys, self.verify_password(input.username, &input.password))
+            .await?;
+        if self.password_hasher.needs_rehash(&credential.password) {
+            self.rehash(&credential, input.password).await;
         }
 
-        let start = SystemTime::now();
-        let since_the_epoch = start
-            .duration_since(UNIX_EPOCH)
-            .expect("time should go forward");
-
         let mut allowed_methods: Vec<String> = Vec::new();
-        if credential.otp_secret.is_some() {
+        if !credential.totp_credentials.is_empty() {
             allowed_methods.push("otp".to_string());
         }
         if !credential.pass_keys.is_empty() {
             allowed_methods.push("passkey".to_string());
         }
+        if !allowed_methods.is_empty() && !credential.recovery_codes.is_empty() {
+            allowed_methods.push("recovery".to_string());
+        }
 
         if !allowed_methods.is_empty() {
-            let exp = (since_the_epoch.as_secs() + 300) as usize; // 5 minutes from now
             let mfa_token = self
                 .for_auth_tokens
-                .create_token(Claims {
-                    token_type: "mfa_verification".to_string(),
-                    sub: credential.id.to_string(),
-                    exp,
-                })
+                .create_token(Claims::new(
+                    "mfa_verification",
+                    credential.id.to_string(),
+                    self.mfa_verification_ttl,
+                ))
                 .await
                 .map_err(|_| AuthError::MFATokenCreationFailed)?;
 
-            return Ok(LoginOutput {
+            Ok(LoginOutput {
                 mfa_verification_token: Some(mfa_token),
                 access_token: None,
                 refresh_token: None,
                 allowed_methods: Some(allowed_methods),
-            });
+            })
         } else {
-            let exp = (since_the_epoch.as_secs() + 604800) as usize; // 7 days from now
-            let refresh_token = self
-                .for_auth_tokens
-                .create_token(Claims {
-                    token_type: "refresh".to_string(),
-                    sub: credential.id.to_string(),
-                    exp,
-                })
-                .await
-                .map_err(|_| AuthError::RefreshTokenCreationFailed)?;
-
-            let exp = (since_the_epoch.as_secs() + 600) as usize; // 10 minutes from now
-            let access_token = self
-                .for_auth_tokens
-                .create_token(Claims {
-                    token_type: "access".to_string(),
-                    sub: credential.id.to_string(),
-                    exp,
-                })
-                .await
-                .map_err(|_| AuthError::AccessTokenCreationFailed)?;
+            let tokens = self.session_token_issuer.issue(credential.id, None).await?;
 
-            return Ok(LoginOutput {
+            Ok(LoginOutput {
                 mfa_verification_token: None,
-                access_token: Some(access_token),
-                refresh_token: Some(refresh_token),
+                access_token: Some(tokens.access_token),
+                refresh_token: Some(tokens.refresh_token),
                 allowed_methods: None,
-            });
+            })
+        }
+    }
+
+    /// Upgrades the stored hash to the current algorithm and parameters. Only the hash is
+    /// written, and only if it did not change since it was verified, so a concurrent update of
+    /// the user is not undone. The login goes on with the old hash if this fails; it is retried
+    /// on the next one.
+    async fn rehash(&self, user: &User, password: String) {
+        let password_hash = match self.password_hasher.hash(password).await {
+            Ok(password_hash) => password_hash,
+            Err(err) => {
+                warn!("Failed to rehash the password of user {}: {}", user.id, err);
+                return;
+            }
+        };
+        if let Err(err) = self
+            .user_repository
+            .update_password_hash(user.id, &user.password, &password_hash)
+            .await
+        {
+            warn!(
+                "Failed to save the rehashed password of user {}: {}",
+                user.id, err
+            );
         }
     }
+
+    /// Unknown usernames fail with the same error as a wrong password, after the same work, so
+    /// neither the response nor its timing reveals which accounts exist. Every login verifies one
+    /// hash per algorithm stored hashes may use, the user's own for its algorithm and a dummy for
+    /// the others, so users with older hashes do not stand out either.
+    async fn verify_password(&self, username: String, password: &str) -> Result<User, AuthError> {
+        let credential = self
+            .user_repository
+            .find_username(username)
+            .await
+            .map_err(AuthError::FindUserError)?;
+        let stored_hash = credential.as_ref().map(|credential| &credential.password);
+
+        let mut matches = None;
+        for dummy_hash in &self.dummy_hashes {
+            match stored_hash {
+                Some(stored_hash)
+                    if matches.is_none()
+                        && self.password_hasher.same_algorithm(stored_hash, dummy_hash) =>
+                {
+                    matches = Some(self.verify(password, stored_hash).await?);
+                }
+                _ => {
+                    self.verify(password, dummy_hash).await?;
+                }
+            }
+        }
+        // A hash of an algorithm without a dummy is still verified, only not evenly.
+        if let (Some(stored_hash), None) = (stored_hash, matches) {
+            matches = Some(self.verify(password, stored_hash).await?);
+        }
+
+        match credential {
+            Some(credential) if matches == Some(true) => Ok(credential),
+            _ => Err(AuthError::InvalidUsernameOrPassword),
+        }
+    }
+
+    async fn verify(&self, password: &str, hash: &str) -> Result<bool, AuthError> {
+        self.password_hasher
+            .verify(password.to_string(), hash.to_string())
+            .await
+            .map_err(AuthError::PasswordHasherError)
+    }
 }
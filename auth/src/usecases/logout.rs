@@ -0,0 +1,72 @@
+use std::sync::Arc;
+
+use contracts::auth::{error::AuthError, logout::LogoutInput};
+use log::info;
+
+use crate::ports::{
+    for_auth_tokens::ForAuthTokens, refresh_token_store::RefreshTokenStore,
+    token_revocation_store::TokenRevocationStore,
+};
+
+pub struct LogoutUseCase {
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+    token_revocation_store: Arc<dyn TokenRevocationStore>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+}
+
+impl LogoutUseCase {
+    pub fn new(
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+        token_revocation_store: Arc<dyn TokenRevocationStore>,
+        refresh_token_store: Arc<dyn RefreshTokenStore>,
+    ) -> Self {
+        Self {
+            for_auth_tokens,
+            token_revocation_store,
+            refresh_token_store,
+        }
+    }
+
+    /// Revokes the access token and, when given, the refresh token and its whole family.
+    pub async fn execute(&self, input: LogoutInput) -> Result<(), AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.access_token, "access".to_string())
+            .await
+            .map_err(AuthError::from)?;
+
+        self.token_revocation_store
+            .revoke(&claims.jti, claims.exp)
+            .await
+            .map_err(AuthError::TokenRevocationStoreError)?;
+
+        let Some(refresh_token) = input.refresh_token else {
+            return Ok(());
+        };
+        // An expired or already revoked refresh token can no longer be used anyway.
+        let Ok(refresh_claims) = self
+            .for_auth_tokens
+            .validate_token(refresh_token, "refresh".to_string())
+            .await
+        else {
+            return Ok(());
+        };
+        if refresh_claims.sub != claims.sub {
+            info!("Logout ignored a refresh token belonging to another user");
+            return Ok(());
+        }
+
+        self.token_revocation_store
+            .revoke(&refresh_claims.jti, refresh_claims.exp)
+            .await
+            .map_err(AuthError::TokenRevocationStoreError)?;
+        if let Some(family_id) = refresh_claims.family {
+            self.refresh_token_store
+                .revoke_family(&family_id)
+                .await
+                .map_err(AuthError::RefreshTokenStoreError)?;
+        }
+
+        Ok(())
+    }
+}
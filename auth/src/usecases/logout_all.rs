@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use contracts::auth::{error::AuthError, logout::LogoutAllInput};
+
+use crate::ports::{for_auth_tokens::ForAuthTokens, token_revocation_store::TokenRevocationStore};
+
+pub struct LogoutAllUseCase {
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+    token_revocation_store: Arc<dyn TokenRevocationStore>,
+}
+
+impl LogoutAllUseCase {
+    pub fn new(
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+        token_revocation_store: Arc<dyn TokenRevocationStore>,
+    ) -> Self {
+        Self {
+            for_auth_tokens,
+            token_revocation_store,
+        }
+    }
+
+    /// Revokes every token issued to the user so far, on every device.
+    pub async fn execute(&self, input: LogoutAllInput) -> Result<(), AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.access_token, "access".to_string())
+            .await
+            .map_err(AuthError::from)?;
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+
+        self.token_revocation_store
+            .revoke_all(user_id)
+            .await
+            .map_err(AuthError::TokenRevocationStoreError)
+    }
+}
@@ -1,10 +1,35 @@
+pub mod attempt_limiter;
 pub mod finish_passkey_authentication;
+pub mod finish_passkey_login;
 pub mod finish_passkey_registration;
 pub mod finish_totp_registration;
-pub mod get_mfa_registration;
 pub mod get_jwks;
+pub mod get_mfa_registration;
+pub mod list_passkeys;
+pub mod list_totp_credentials;
 pub mod login;
+pub mod logout;
+pub mod logout_all;
+pub mod modify_user;
+pub mod password_policy;
+pub mod refresh_token;
+pub mod regenerate_recovery_codes;
+pub mod remove_passkey;
+pub mod remove_totp;
+pub mod rename_passkey;
+pub mod rename_totp_credential;
+pub mod rotate_signing_keys;
+pub mod session_tokens;
 pub mod signup;
 pub mod start_passkey_authentication;
+pub mod start_passkey_login;
 pub mod start_passkey_registration;
 pub mod start_totp_registration;
+pub mod step_up_mfa;
+#[cfg(test)]
+mod tests;
+pub mod totp_verifier;
+pub mod unlock_attempts;
+pub mod validate_token;
+pub mod verify_recovery_code;
+pub mod verify_totp_login;
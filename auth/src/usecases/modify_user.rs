@@ -0,0 +1,30 @@
+use contracts::auth::error::{AuthError, UserRepositoryError};
+use uuid::Uuid;
+
+use crate::{entities::user::User, ports::user_repository::UserRepository};
+
+/// Writes a user keeps being refused as stale before the change gives up.
+const MAX_ATTEMPTS: usize = 5;
+
+/// Reads the user, lets `change` edit it and writes it back. When another request wrote the user
+/// in between, say consuming a recovery code or accepting a TOTP code, the change is applied again
+/// to a fresh read instead of overwriting that write.
+pub async fn modify_user<T>(
+    user_repository: &dyn UserRepository,
+    id: Uuid,
+    mut change: impl FnMut(&mut User) -> Result<T, AuthError> + Send,
+) -> Result<T, AuthError> {
+    let mut attempts = 1;
+    loop {
+        let mut user = user_repository
+            .find_id(id)
+            .await
+            .map_err(AuthError::FindUserError)?
+            .ok_or(AuthError::UserNotFound)?;
+        let output = change(&mut user)?;
+        match user_repository.update(user).await {
+            Err(UserRepositoryError::Stale) if attempts < MAX_ATTEMPTS => attempts += 1,
+            result => return result.map(|()| output).map_err(AuthError::SaveUserError),
+        }
+    }
+}
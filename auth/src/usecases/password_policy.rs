@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use contracts::auth::error::{AuthError, PasswordPolicyViolation};
+
+use crate::{config::PasswordPolicyConfig, ports::breached_passwords::BreachedPasswords};
+
+/// Shorter parts of a name or e-mail, like "Li", would reject too many passwords by chance.
+const MIN_PERSONAL_INFO_LEN: usize = 3;
+
+/// Checks new passwords against the configured rules, for every use case that sets one.
+pub struct PasswordPolicy {
+    config: PasswordPolicyConfig,
+    breached_passwords: Option<Arc<dyn BreachedPasswords>>,
+}
+
+impl PasswordPolicy {
+    pub fn new(
+        config: PasswordPolicyConfig,
+        breached_passwords: Option<Arc<dyn BreachedPasswords>>,
+    ) -> Self {
+        Self {
+            config,
+            breached_passwords,
+        }
+    }
+
+    /// Fails with every rule `password` breaks, for a user with the given name and e-mail.
+    pub async fn check(&self, password: &str, name: &str, username: &str) -> Result<(), AuthError> {
+        let config = &self.config;
+        let mut violations = Vec::new();
+
+        let length = password.chars().count();
+        if length < config.min_length {
+            violations.push(PasswordPolicyViolation::TooShort {
+                min_length: config.min_length,
+            });
+        }
+        if length > config.max_length {
+            violations.push(PasswordPolicyViolation::TooLong {
+                max_length: config.max_length,
+            });
+        }
+        let classes = [
+            (
+                config.require_uppercase,
+                char::is_uppercase as fn(char) -> bool,
+                PasswordPolicyViolation::MissingUppercase,
+            ),
+            (
+                config.require_lowercase,
+                char::is_lowercase,
+                PasswordPolicyViolation::MissingLowercase,
+            ),
+            (
+                config.require_digit,
+                char::is_numeric,
+                PasswordPolicyViolation::MissingDigit,
+            ),
+            (
+                config.require_special,
+                |c: char| !c.is_alphanumeric(),
+                PasswordPolicyViolation::MissingSpecial,
+            ),
+        ];
+        for (required, matches, violation) in classes {
+            if required && !password.chars().any(matches) {
+                violations.push(violation);
+            }
+        }
+        if config.reject_personal_info && contains_personal_info(password, name, username) {
+            violations.push(PasswordPolicyViolation::ContainsPersonalInfo);
+        }
+        if self.is_breached(password).await? {
+            violations.push(PasswordPolicyViolation::Breached);
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(AuthError::WeakPassword(violations))
+        }
+    }
+
+    async fn is_breached(&self, password: &str) -> Result<bool, AuthError> {
+        let Some(breached_passwords) = &self.breached_passwords else {
+            return Ok(false);
+        };
+        breached_passwords
+            .is_breached(password)
+            .await
+            .map_err(AuthError::BreachedPasswordsError)
+    }
+}
+
+fn contains_personal_info(password: &str, name: &str, username: &str) -> bool {
+    let password = password.to_lowercase();
+    let local_part = username.split('@').next().unwrap_or_default();
+    name.split_whitespace()
+        .chain(std::iter::once(local_part))
+        .filter(|part| part.chars().count() >= MIN_PERSONAL_INFO_LEN)
+        .any(|part| password.contains(&part.to_lowercase()))
+}
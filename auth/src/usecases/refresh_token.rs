@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    error::AuthError,
+    token::{RefreshTokenInput, RefreshTokenOutput},
+};
+use log::warn;
+
+use crate::{
+    entities::refresh_token::RefreshTokenStatus,
+    ports::{
+        for_auth_tokens::ForAuthTokens, refresh_token_store::RefreshTokenStore,
+        user_repository::UserRepository,
+    },
+    usecases::session_tokens::SessionTokenIssuer,
+};
+
+pub struct RefreshTokenUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    session_token_issuer: Arc<SessionTokenIssuer>,
+}
+
+impl RefreshTokenUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+        refresh_token_store: Arc<dyn RefreshTokenStore>,
+        session_token_issuer: Arc<SessionTokenIssuer>,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+            refresh_token_store,
+            session_token_issuer,
+        }
+    }
+
+    pub async fn execute(&self, input: RefreshTokenInput) -> Result<RefreshTokenOutput, AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.refresh_token, "refresh".to_string())
+            .await
+            .map_err(AuthError::from)?;
+
+        let jti = claims.jti;
+        let family_id = match self
+            .refresh_token_store
+            .consume(&jti)
+            .await
+            .map_err(AuthError::RefreshTokenStoreError)?
+        {
+            RefreshTokenStatus::Valid { family_id } => family_id,
+            RefreshTokenStatus::Reused { family_id } => {
+                warn!(
+                    "Refresh token {} was reused; revoking token family {}",
+                    jti, family_id
+                );
+                self.refresh_token_store
+                    .revoke_family(&family_id)
+                    .await
+                    .map_err(AuthError::RefreshTokenStoreError)?;
+                return Err(AuthError::RefreshTokenReused);
+            }
+            RefreshTokenStatus::Unknown => return Err(AuthError::InvalidRefreshToken),
+        };
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        let user = self
+            .user_repository
+            .find_id(user_id)
+            .await
+            .map_err(AuthError::FindUserError)?
+            .ok_or(AuthError::UserNotFound)?;
+
+        let tokens = self
+            .session_token_issuer
+            .issue(user.id, Some(family_id))
+            .await?;
+
+        Ok(RefreshTokenOutput {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+        })
+    }
+}
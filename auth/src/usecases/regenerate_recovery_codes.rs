@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    error::AuthError,
+    recovery::{RecoveryCodesOutput, RecoveryRegenerateCodesInput},
+};
+
+use crate::{
+    entities::claims::MFA_MANAGE_SCOPE,
+    ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository},
+    usecases::modify_user::modify_user,
+};
+
+pub struct RegenerateRecoveryCodesUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+}
+
+impl RegenerateRecoveryCodesUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+        }
+    }
+
+    /// Replaces all recovery codes, invalidating the previous batch.
+    pub async fn execute(
+        &self,
+        input: RecoveryRegenerateCodesInput,
+    ) -> Result<RecoveryCodesOutput, AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.access_token, "access".to_string())
+            .await
+            .map_err(AuthError::from)?;
+        if !claims.has_scope(MFA_MANAGE_SCOPE) {
+            return Err(AuthError::InsufficientScope);
+        }
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        let recovery_codes = modify_user(self.user_repository.as_ref(), user_id, |user| {
+            if !user.has_mfa() {
+                return Err(AuthError::MfaNotEnabled);
+            }
+            Ok(user.regenerate_recovery_codes())
+        })
+        .await?;
+
+        Ok(RecoveryCodesOutput { recovery_codes })
+    }
+}
@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use contracts::auth::{error::AuthError, passkey::PasskeyRemoveInput};
+
+use crate::{
+    entities::claims::MFA_MANAGE_SCOPE,
+    ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository},
+    usecases::modify_user::modify_user,
+};
+
+pub struct RemovePasskeyUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+}
+
+impl RemovePasskeyUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+        }
+    }
+
+    pub async fn execute(&self, input: PasskeyRemoveInput) -> Result<(), AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.access_token, "access".to_string())
+            .await
+            .map_err(AuthError::from)?;
+        if !claims.has_scope(MFA_MANAGE_SCOPE) {
+            return Err(AuthError::InsufficientScope);
+        }
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        modify_user(self.user_repository.as_ref(), user_id, |user| {
+            let before = user.pass_keys.len();
+            user.pass_keys.retain(|k| k.id != input.passkey_id);
+            if user.pass_keys.len() == before {
+                return Err(AuthError::PasskeyNotFound);
+            }
+            if !user.has_mfa() {
+                return Err(AuthError::LastMfaFactor);
+            }
+            Ok(())
+        })
+        .await
+    }
+}
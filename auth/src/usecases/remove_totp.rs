@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use contracts::auth::{error::AuthError, mfa::MfaRemoveTotpInput};
+
+use crate::{
+    entities::claims::MFA_MANAGE_SCOPE,
+    ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository},
+    usecases::modify_user::modify_user,
+};
+
+pub struct RemoveTotpUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+}
+
+impl RemoveTotpUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+        }
+    }
+
+    pub async fn execute(&self, input: MfaRemoveTotpInput) -> Result<(), AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.access_token, "access".to_string())
+            .await
+            .map_err(AuthError::from)?;
+        if !claims.has_scope(MFA_MANAGE_SCOPE) {
+            return Err(AuthError::InsufficientScope);
+        }
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        modify_user(self.user_repository.as_ref(), user_id, |user| {
+            match input.credential_id {
+                Some(id) => {
+                    let before = user.totp_credentials.len();
+                    user.totp_credentials.retain(|c| c.id != id);
+                    if user.totp_credentials.len() == before {
+                        return Err(AuthError::TotpCredentialNotFound);
+                    }
+                }
+                None => {
+                    if user.totp_credentials.is_empty() {
+                        return Err(AuthError::TotpNotEnabled);
+                    }
+                    user.totp_credentials.clear();
+                }
+            }
+            if !user.has_mfa() {
+                return Err(AuthError::LastMfaFactor);
+            }
+            Ok(())
+        })
+        .await
+    }
+}
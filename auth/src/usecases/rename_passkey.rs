@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use contracts::auth::{error::AuthError, passkey::PasskeyRenameInput};
+
+use crate::{
+    entities::claims::MFA_MANAGE_SCOPE,
+    ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository},
+    usecases::modify_user::modify_user,
+};
+
+pub struct RenamePasskeyUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+}
+
+impl RenamePasskeyUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+        }
+    }
+
+    pub async fn execute(&self, input: PasskeyRenameInput) -> Result<(), AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.access_token, "access".to_string())
+            .await
+            .map_err(AuthError::from)?;
+        if !claims.has_scope(MFA_MANAGE_SCOPE) {
+            return Err(AuthError::InsufficientScope);
+        }
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        modify_user(self.user_repository.as_ref(), user_id, |user| {
+            let credential = user
+                .pass_keys
+                .iter_mut()
+                .find(|k| k.id == input.passkey_id)
+                .ok_or(AuthError::PasskeyNotFound)?;
+            credential.name = input.name.clone();
+            Ok(())
+        })
+        .await
+    }
+}
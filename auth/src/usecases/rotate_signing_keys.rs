@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use contracts::auth::{error::AuthError, token::RotateSigningKeysOutput};
+
+use crate::ports::for_auth_tokens::ForAuthTokens;
+
+pub struct RotateSigningKeysUseCase {
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+    max_token_lifetime: u64,
+}
+
+impl RotateSigningKeysUseCase {
+    pub fn new(for_auth_tokens: Arc<dyn ForAuthTokens>, max_token_lifetime: u64) -> Self {
+        Self {
+            for_auth_tokens,
+            max_token_lifetime,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<RotateSigningKeysOutput, AuthError> {
+        let rotation = self
+            .for_auth_tokens
+            .rotate_keys(self.max_token_lifetime)
+            .await
+            .map_err(|_| AuthError::SigningKeyRotationFailed)?;
+
+        Ok(RotateSigningKeysOutput {
+            active_kid: rotation.key_set.active,
+            next_kid: rotation.key_set.next,
+            retired_kids: rotation
+                .key_set
+                .retired
+                .into_iter()
+                .map(|key| key.kid)
+                .collect(),
+            removed_kids: rotation.removed,
+        })
+    }
+}
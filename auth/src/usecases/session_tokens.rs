@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use contracts::auth::error::AuthError;
+use uuid::Uuid;
+
+use crate::{
+    entities::claims::Claims,
+    ports::{for_auth_tokens::ForAuthTokens, refresh_token_store::RefreshTokenStore},
+};
+
+pub struct SessionTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// Issues access + refresh pairs, registering every refresh token so it can be rotated.
+pub struct SessionTokenIssuer {
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+    refresh_token_store: Arc<dyn RefreshTokenStore>,
+    access_ttl: u64,
+    refresh_ttl: u64,
+}
+
+impl SessionTokenIssuer {
+    pub fn new(
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+        refresh_token_store: Arc<dyn RefreshTokenStore>,
+        access_ttl: u64,
+        refresh_ttl: u64,
+    ) -> Self {
+        Self {
+            for_auth_tokens,
+            refresh_token_store,
+            access_ttl,
+            refresh_ttl,
+        }
+    }
+
+    /// Starts a new token family, or continues `family_id` when rotating.
+    pub async fn issue(
+        &self,
+        user_id: Uuid,
+        family_id: Option<String>,
+    ) -> Result<SessionTokens, AuthError> {
+        let family_id = family_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        let refresh_claims = Claims {
+            family: Some(family_id.clone()),
+            ..Claims::new("refresh", user_id.to_string(), self.refresh_ttl)
+        };
+        let (jti, exp) = (refresh_claims.jti.clone(), refresh_claims.exp);
+        let refresh_token = self
+            .for_auth_tokens
+            .create_token(refresh_claims)
+            .await
+            .map_err(|_| AuthError::RefreshTokenCreationFailed)?;
+
+        self.refresh_token_store
+            .register(&jti, &family_id, exp)
+            .await
+            .map_err(AuthError::RefreshTokenStoreError)?;
+
+        let access_token = self
+            .for_auth_tokens
+            .create_token(Claims::new("access", user_id.to_string(), self.access_ttl))
+            .await
+            .map_err(|_| AuthError::AccessTokenCreationFailed)?;
+
+        Ok(SessionTokens {
+            access_token,
+            refresh_token,
+        })
+    }
+}
@@ -1,30 +1,51 @@
 use crate::entities::user::User;
-use crate::ports::user_repository::UserRepository;
-use bcrypt::{DEFAULT_COST, hash};
+use crate::ports::{password_hasher::PasswordHasher, user_repository::UserRepository};
+use crate::usecases::password_policy::PasswordPolicy;
 use contracts::auth::{
-    error::AuthError,
+    error::{AuthError, UserRepositoryError},
     signup::{SignupInput, SignupOutput},
 };
 use std::sync::Arc;
 
 pub struct SignupUseCase {
     user_repository: Arc<dyn UserRepository>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    password_policy: Arc<PasswordPolicy>,
 }
 
 impl SignupUseCase {
-    pub fn new(user_repository: Arc<dyn UserRepository>) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        password_hasher: Arc<dyn PasswordHasher>,
+        password_policy: Arc<PasswordPolicy>,
+    ) -> Self {
+        Self {
+            user_repository,
+            password_hasher,
+            password_policy,
+        }
     }
 
     pub async fn execute(&self, input: SignupInput) -> Result<SignupOutput, AuthError> {
-        let password_hash = hash(&input.password, DEFAULT_COST).map_err(AuthError::BcryptError)?;
+        self.password_policy
+            .check(&input.password, &input.name, &input.username)
+            .await?;
+
+        let password_hash = self
+            .password_hasher
+            .hash(input.password)
+            .await
+            .map_err(AuthError::PasswordHasherError)?;
 
         let user = User::new(&input.username, &input.name, &password_hash);
 
         self.user_repository
-            .save(user.clone())
+            .create(user.clone())
             .await
-            .map_err(AuthError::SaveUserError)?;
+            .map_err(|err| match err {
+                UserRepositoryError::Conflict(_) => AuthError::UsernameAlreadyTaken,
+                err => AuthError::SaveUserError(err),
+            })?;
         Ok(SignupOutput { user_id: user.id })
     }
 }
@@ -1,26 +1,27 @@
 use crate::entities::totp::WEBAUTHN_AUTH_STATE;
+use crate::ports::for_passkeys::ForPasskeys;
 use crate::ports::hsm_store::HSMStore;
 use crate::ports::user_repository::UserRepository;
 use contracts::auth::error::AuthError;
 use contracts::auth::passkey::{PasskeyStartAuthenticationInput, PasskeyStartAuthenticationOutput};
 use std::sync::Arc;
-use webauthn_rs::Webauthn;
+use webauthn_rs::prelude::Passkey;
 
 pub struct StartPasskeyAuthenticationUseCase {
     user_repository: Arc<dyn UserRepository>,
     hsm_store: Arc<dyn HSMStore>,
-    webauthn: Arc<Webauthn>,
+    for_passkeys: Arc<dyn ForPasskeys>,
 }
 impl StartPasskeyAuthenticationUseCase {
     pub fn new(
         user_repository: Arc<dyn UserRepository>,
         hsm_store: Arc<dyn HSMStore>,
-        webauthn: Arc<Webauthn>,
+        for_passkeys: Arc<dyn ForPasskeys>,
     ) -> Self {
         Self {
             user_repository,
             hsm_store,
-            webauthn,
+            for_passkeys,
         }
     }
 
@@ -36,14 +37,14 @@ impl StartPasskeyAuthenticationUseCase {
             .find_id(input.user_id)
             .await
             .map_err(AuthError::FindUserError)?
-            .ok_or_else(|| AuthError::UserNotFound)?;
+            .ok_or(AuthError::UserNotFound)?;
 
-        let (rcr, auth_state) = self
-            .webauthn
-            .start_passkey_authentication(&user.pass_keys)
-            .map_err(AuthError::WebauthnError)?;
+        let passkeys: Vec<Passkey> = user.pass_keys.into_iter().map(|k| k.passkey).collect();
+        let (rcr, json_auth_state) = self
+            .for_passkeys
+            .start_authentication(&passkeys)
+            .map_err(AuthError::from)?;
 
-        let json_auth_state = serde_json::to_string(&auth_state).map_err(AuthError::SerdeError)?;
         self.hsm_store
             .set(input.user_id, WEBAUTHN_AUTH_STATE, &json_auth_state)
             .map_err(AuthError::SetHsmStoreError)?;
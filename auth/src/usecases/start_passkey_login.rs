@@ -0,0 +1,47 @@
+use crate::entities::totp::WEBAUTHN_LOGIN_STATE;
+use crate::ports::for_passkeys::ForPasskeys;
+use crate::ports::hsm_store::HSMStore;
+use contracts::auth::error::AuthError;
+use contracts::auth::passkey::PasskeyStartLoginOutput;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+pub struct StartPasskeyLoginUseCase {
+    hsm_store: Arc<dyn HSMStore>,
+    for_passkeys: Arc<dyn ForPasskeys>,
+}
+
+impl StartPasskeyLoginUseCase {
+    pub fn new(hsm_store: Arc<dyn HSMStore>, for_passkeys: Arc<dyn ForPasskeys>) -> Self {
+        Self {
+            hsm_store,
+            for_passkeys,
+        }
+    }
+
+    pub async fn execute(&self) -> Result<PasskeyStartLoginOutput, AuthError> {
+        let (rcr, json_auth_state) = self
+            .for_passkeys
+            .start_discoverable_authentication()
+            .map_err(AuthError::from)?;
+
+        // Nobody is identified yet, so the state is keyed by a fresh id that expires with the
+        // challenge instead of by user.
+        let login_id = Uuid::new_v4();
+        let expires_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should go forward")
+            .as_secs()
+            + self.for_passkeys.timeout();
+        self.hsm_store
+            .set_expiring(login_id, WEBAUTHN_LOGIN_STATE, &json_auth_state, expires_at)
+            .map_err(AuthError::SetHsmStoreError)?;
+
+        let rcr = serde_json::to_string(&rcr).map_err(AuthError::SerdeError)?;
+        Ok(PasskeyStartLoginOutput {
+            login_id,
+            challenge: rcr,
+        })
+    }
+}
@@ -37,7 +37,7 @@ impl StartTOTPRegistrationUseCase {
             .for_auth_tokens
             .validate_token(input.mfa_token, "mfa_registration".to_string())
             .await
-            .map_err(|_| AuthError::TokenValidationFailed)?;
+            .map_err(AuthError::from)?;
 
         let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
 
@@ -56,7 +56,7 @@ impl StartTOTPRegistrationUseCase {
 
         let (_, auth_url) = self
             .for_totp
-            .auth_url(user.username.clone(), "TODO_ISSUER".to_string())
+            .auth_url(user.username.clone())
             .await
             .map_err(AuthError::TotpError)?;
 
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    error::AuthError,
+    mfa::{MfaStepUpInput, MfaStepUpOutput},
+};
+
+use crate::{
+    entities::{
+        claims::{Claims, MFA_MANAGE_SCOPE},
+        failed_attempts::AttemptKey,
+    },
+    ports::{
+        for_auth_tokens::ForAuthTokens, password_hasher::PasswordHasher,
+        user_repository::UserRepository,
+    },
+    usecases::{attempt_limiter::AttemptLimiter, totp_verifier::TotpVerifier},
+};
+
+pub struct StepUpMfaUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+    password_hasher: Arc<dyn PasswordHasher>,
+    totp_verifier: Arc<TotpVerifier>,
+    attempt_limiter: Arc<AttemptLimiter>,
+    elevated_ttl: u64,
+}
+
+impl StepUpMfaUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+        password_hasher: Arc<dyn PasswordHasher>,
+        totp_verifier: Arc<TotpVerifier>,
+        attempt_limiter: Arc<AttemptLimiter>,
+        elevated_ttl: u64,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+            password_hasher,
+            totp_verifier,
+            attempt_limiter,
+            elevated_ttl,
+        }
+    }
+
+    /// Trades a plain access token plus a fresh proof (password or TOTP code) for a
+    /// short-lived `mfa:manage` access token.
+    pub async fn execute(&self, input: MfaStepUpInput) -> Result<MfaStepUpOutput, AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.access_token, "access".to_string())
+            .await
+            .map_err(AuthError::from)?;
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        let user = self
+            .user_repository
+            .find_id(user_id)
+            .await
+            .map_err(AuthError::FindUserError)?
+            .ok_or(AuthError::UserNotFound)?;
+
+        let keys = [AttemptKey::SecondFactor(user_id)];
+        match (input.password, input.totp_code) {
+            (_, Some(code)) => {
+                self.attempt_limiter
+                    .guard(&keys, self.totp_verifier.verify(&user, code))
+                    .await?;
+            }
+            (Some(password), None) => {
+                self.attempt_limiter
+                    .guard(&keys, async {
+                        let matches = self
+                            .password_hasher
+                            .verify(password, user.password.clone())
+                            .await
+                            .map_err(AuthError::PasswordHasherError)?;
+                        if matches {
+                            Ok(())
+                        } else {
+                            Err(AuthError::InvalidUsernameOrPassword)
+                        }
+                    })
+                    .await?;
+            }
+            (None, None) => return Err(AuthError::StepUpCredentialRequired),
+        }
+
+        let access_token = self
+            .for_auth_tokens
+            .create_token(Claims {
+                scope: vec![MFA_MANAGE_SCOPE.to_string()],
+                ..Claims::new("access", user.id.to_string(), self.elevated_ttl)
+            })
+            .await
+            .map_err(|_| AuthError::AccessTokenCreationFailed)?;
+
+        Ok(MfaStepUpOutput {
+            access_token,
+            expires_in: self.elevated_ttl as usize,
+        })
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    net::{IpAddr, Ipv4Addr},
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use contracts::auth::error::{AttemptStoreError, AuthError};
+use tokio::task::JoinSet;
+
+use super::CONCURRENT_REQUESTS;
+use crate::{
+    config::ThrottleConfig,
+    entities::failed_attempts::{AttemptKey, FailedAttempts},
+    infra::memory::attempt_store::MemoryAttemptStore,
+    ports::attempt_store::AttemptStore,
+    usecases::attempt_limiter::AttemptLimiter,
+};
+
+const ANA: &str = "ana@exemplo.com";
+const BIA: &str = "bia@exemplo.com";
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("time should go forward")
+        .as_secs()
+}
+
+fn ip(last: u8) -> AttemptKey {
+    AttemptKey::Ip(IpAddr::V4(Ipv4Addr::new(203, 0, 113, last)))
+}
+
+fn user(username: &str) -> AttemptKey {
+    AttemptKey::Username(username.to_string())
+}
+
+async fn guess(
+    limiter: &AttemptLimiter,
+    keys: &[AttemptKey],
+    right: bool,
+) -> Result<(), AuthError> {
+    limiter
+        .guard(keys, async move {
+            if right {
+                Ok(())
+            } else {
+                Err(AuthError::InvalidUsernameOrPassword)
+            }
+        })
+        .await
+}
+
+fn retry_after(result: Result<(), AuthError>) -> u64 {
+    match result {
+        Err(AuthError::TooManyAttempts { retry_after }) => retry_after,
+        other => panic!("expected TooManyAttempts, got {:?}", other),
+    }
+}
+
+/// Each failure past the free attempts doubles the wait, up to the maximum.
+#[tokio::test]
+async fn backoff_grows_with_each_failure() {
+    let store = Arc::new(MemoryAttemptStore::new());
+    let limiter = AttemptLimiter::new(
+        store.clone(),
+        ThrottleConfig {
+            free_attempts: 1,
+            base_delay: 10,
+            max_delay: 100,
+            lockout_threshold: 100,
+            ..ThrottleConfig::default()
+        },
+    );
+    let keys = [user(ANA)];
+
+    let mut waits = Vec::new();
+    for _ in 0..4 {
+        assert!(matches!(
+            guess(&limiter, &keys, false).await,
+            Err(AuthError::InvalidUsernameOrPassword)
+        ));
+        waits.push(retry_after(guess(&limiter, &keys, true).await));
+        // A failure long ago, so the next guess is past its wait and adds one more.
+        store
+            .record_failure(&user(ANA).id(), now() - 1000, now() + 1000)
+            .await
+            .unwrap();
+    }
+
+    for (wait, delay) in waits.into_iter().zip([10, 40, 100, 100]) {
+        assert!(
+            (delay - 1..=delay).contains(&wait),
+            "{} for {}",
+            wait,
+            delay
+        );
+    }
+}
+
+/// Reaching the threshold locks the key; not even the right credential gets through.
+#[tokio::test]
+async fn the_threshold_locks_the_key() {
+    let limiter = AttemptLimiter::new(
+        Arc::new(MemoryAttemptStore::new()),
+        ThrottleConfig {
+            free_attempts: 10,
+            lockout_threshold: 3,
+            lockout_duration: 900,
+            ..ThrottleConfig::default()
+        },
+    );
+    let keys = [user(ANA)];
+
+    for _ in 0..3 {
+        assert!(matches!(
+            guess(&limiter, &keys, false).await,
+            Err(AuthError::InvalidUsernameOrPassword)
+        ));
+    }
+
+    let wait = retry_after(guess(&limiter, &keys, true).await);
+    assert!((899..=900).contains(&wait), "{}", wait);
+}
+
+/// A success clears the user's failures but not the address's, and is not counted itself.
+#[tokio::test]
+async fn a_success_resets_the_user_but_not_the_address() {
+    let store = Arc::new(MemoryAttemptStore::new());
+    let limiter = AttemptLimiter::new(store.clone(), ThrottleConfig::default());
+    let keys = [user(ANA), ip(1)];
+
+    for _ in 0..2 {
+        assert!(guess(&limiter, &keys, false).await.is_err());
+    }
+    guess(&limiter, &keys, true).await.unwrap();
+
+    assert_eq!(store.get(&user(ANA).id()).await.unwrap(), None);
+    assert_eq!(
+        store.get(&ip(1).id()).await.unwrap().map(|a| a.failures),
+        Some(2)
+    );
+}
+
+/// Locking a user from one address leaves other users on that address and the address itself,
+/// which gets a larger allowance, free to go on.
+#[tokio::test]
+async fn users_and_addresses_are_counted_apart() {
+    let limiter = AttemptLimiter::new(
+        Arc::new(MemoryAttemptStore::new()),
+        ThrottleConfig {
+            free_attempts: 10,
+            lockout_threshold: 3,
+            ip_allowance: 10,
+            ..ThrottleConfig::default()
+        },
+    );
+
+    for _ in 0..3 {
+        assert!(guess(&limiter, &[user(ANA), ip(1)], false).await.is_err());
+    }
+
+    retry_after(guess(&limiter, &[user(ANA), ip(2)], true).await);
+    guess(&limiter, &[user(BIA), ip(1)], true).await.unwrap();
+}
+
+/// Hands out the stored failures only after letting the other tasks read them too, so every
+/// concurrent guess passes `check` on the same count.
+struct InterleavedGets(MemoryAttemptStore);
+
+#[async_trait]
+impl AttemptStore for InterleavedGets {
+    async fn get(&self, key: &str) -> Result<Option<FailedAttempts>, AttemptStoreError> {
+        let attempts = self.0.get(key).await;
+        tokio::task::yield_now().await;
+        attempts
+    }
+
+    async fn record_failure(
+        &self,
+        key: &str,
+        at: u64,
+        expires_at: u64,
+    ) -> Result<FailedAttempts, AttemptStoreError> {
+        self.0.record_failure(key, at, expires_at).await
+    }
+
+    async fn release(&self, key: &str) -> Result<(), AttemptStoreError> {
+        self.0.release(key).await
+    }
+
+    async fn lock(&self, key: &str, until: u64) -> Result<(), AttemptStoreError> {
+        self.0.lock(key, until).await
+    }
+
+    async fn clear(&self, key: &str) -> Result<(), AttemptStoreError> {
+        self.0.clear(key).await
+    }
+}
+
+/// Guesses that pass the check together still take turns: past the free attempts only one of
+/// them runs, and the refused ones are not counted.
+#[tokio::test]
+async fn concurrent_guesses_past_the_free_attempts_run_one_at_a_time() {
+    let store = Arc::new(InterleavedGets(MemoryAttemptStore::new()));
+    let limiter = Arc::new(AttemptLimiter::new(
+        store.clone(),
+        ThrottleConfig {
+            free_attempts: 1,
+            ..ThrottleConfig::default()
+        },
+    ));
+    let ran = Arc::new(AtomicUsize::new(0));
+
+    let mut guesses = JoinSet::new();
+    for _ in 0..CONCURRENT_REQUESTS {
+        let (limiter, ran) = (limiter.clone(), ran.clone());
+        guesses.spawn(async move {
+            limiter
+                .guard(&[user(ANA)], async {
+                    ran.fetch_add(1, Ordering::SeqCst);
+                    Err::<(), _>(AuthError::InvalidUsernameOrPassword)
+                })
+                .await
+        });
+    }
+    let mut refused = 0;
+    while let Some(result) = guesses.join_next().await {
+        match result.unwrap() {
+            Err(AuthError::TooManyAttempts { .. }) => refused += 1,
+            Err(AuthError::InvalidUsernameOrPassword) => {}
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    assert_eq!(ran.load(Ordering::SeqCst), 1);
+    assert_eq!(refused, CONCURRENT_REQUESTS - 1);
+    assert_eq!(
+        store
+            .get(&user(ANA).id())
+            .await
+            .unwrap()
+            .map(|a| a.failures),
+        Some(1)
+    );
+}
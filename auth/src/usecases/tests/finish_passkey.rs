@@ -0,0 +1,189 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    error::{AuthError, PasskeyError},
+    passkey::{PasskeyFinishAuthenticationInput, PasskeyFinishRegistrationInput},
+};
+use uuid::Uuid;
+use webauthn_rs::prelude::{
+    AuthenticationResult, CreationChallengeResponse, CredentialID, Passkey, PublicKeyCredential,
+    RegisterPublicKeyCredential, RequestChallengeResponse, WebauthnError,
+};
+
+use super::session_token_issuer;
+use crate::{
+    entities::totp::{WEBAUTHN_AUTH_STATE, WEBAUTHN_REG_STATE},
+    infra::memory::{
+        hsm_store::MemoryHsmStore, refresh_token_store::MemoryRefreshTokenStore,
+        user_repository::MemoryUserRepository,
+    },
+    ports::{for_passkeys::ForPasskeys, hsm_store::HSMStore},
+    usecases::{
+        finish_passkey_authentication::FinishPasskeyAuthenticationUseCase,
+        finish_passkey_registration::FinishPasskeyRegistrationUseCase,
+    },
+};
+
+/// Rejects every response, as a browser answering a stale challenge would get.
+struct RejectingPasskeys;
+
+impl ForPasskeys for RejectingPasskeys {
+    fn timeout(&self) -> u64 {
+        60
+    }
+
+    fn start_registration(
+        &self,
+        _user_id: Uuid,
+        _username: &str,
+        _display_name: &str,
+        _exclude_credentials: Vec<CredentialID>,
+    ) -> Result<(CreationChallengeResponse, String), PasskeyError> {
+        unimplemented!()
+    }
+
+    fn finish_registration(
+        &self,
+        _credential: &RegisterPublicKeyCredential,
+        _state: &str,
+    ) -> Result<Passkey, PasskeyError> {
+        Err(PasskeyError::Webauthn(WebauthnError::MismatchedChallenge))
+    }
+
+    fn start_authentication(
+        &self,
+        _passkeys: &[Passkey],
+    ) -> Result<(RequestChallengeResponse, String), PasskeyError> {
+        unimplemented!()
+    }
+
+    fn finish_authentication(
+        &self,
+        _credential: &PublicKeyCredential,
+        _state: &str,
+    ) -> Result<AuthenticationResult, PasskeyError> {
+        Err(PasskeyError::Webauthn(WebauthnError::MismatchedChallenge))
+    }
+
+    fn start_discoverable_authentication(
+        &self,
+    ) -> Result<(RequestChallengeResponse, String), PasskeyError> {
+        unimplemented!()
+    }
+
+    fn identify_discoverable_authentication(
+        &self,
+        _credential: &PublicKeyCredential,
+    ) -> Result<Uuid, PasskeyError> {
+        unimplemented!()
+    }
+
+    fn finish_discoverable_authentication(
+        &self,
+        _credential: &PublicKeyCredential,
+        _state: &str,
+        _passkeys: &[Passkey],
+    ) -> Result<AuthenticationResult, PasskeyError> {
+        unimplemented!()
+    }
+}
+
+fn registration_input(user_id: Uuid) -> PasskeyFinishRegistrationInput {
+    PasskeyFinishRegistrationInput {
+        user_id,
+        register_public_key_credential: serde_json::from_value(serde_json::json!({
+            "id": "AAAA",
+            "rawId": "AAAA",
+            "response": { "attestationObject": "AAAA", "clientDataJSON": "AAAA" },
+            "type": "public-key",
+        }))
+        .unwrap(),
+        name: None,
+    }
+}
+
+fn authentication_input(user_id: Uuid) -> PasskeyFinishAuthenticationInput {
+    PasskeyFinishAuthenticationInput {
+        user_id,
+        public_key_credential: serde_json::from_value(serde_json::json!({
+            "id": "AAAA",
+            "rawId": "AAAA",
+            "response": {
+                "authenticatorData": "AAAA",
+                "clientDataJSON": "AAAA",
+                "signature": "AAAA",
+                "userHandle": null,
+            },
+            "type": "public-key",
+        }))
+        .unwrap(),
+    }
+}
+
+/// The first finish consumes the ceremony whatever its outcome, so a retry has to start over.
+#[tokio::test]
+async fn a_registration_finishes_once() {
+    let hsm_store = Arc::new(MemoryHsmStore::new());
+    let usecase = FinishPasskeyRegistrationUseCase::new(
+        Arc::new(MemoryUserRepository::new()),
+        Arc::new(RejectingPasskeys),
+        hsm_store.clone(),
+    );
+    let user_id = Uuid::new_v4();
+    hsm_store
+        .set(user_id, WEBAUTHN_REG_STATE, r#"{"state":"started"}"#)
+        .unwrap();
+
+    assert!(matches!(
+        usecase.execute(registration_input(user_id)).await,
+        Err(AuthError::WebauthnError(_))
+    ));
+    assert!(matches!(
+        usecase.execute(registration_input(user_id)).await,
+        Err(AuthError::WebAuthnRegistrationNotFound)
+    ));
+}
+
+#[tokio::test]
+async fn an_authentication_finishes_once() {
+    let hsm_store = Arc::new(MemoryHsmStore::new());
+    let usecase = FinishPasskeyAuthenticationUseCase::new(
+        Arc::new(MemoryUserRepository::new()),
+        hsm_store.clone(),
+        Arc::new(RejectingPasskeys),
+        Arc::new(session_token_issuer(Arc::new(
+            MemoryRefreshTokenStore::new(),
+        ))),
+    );
+    let user_id = Uuid::new_v4();
+    hsm_store
+        .set(user_id, WEBAUTHN_AUTH_STATE, r#"{"state":"started"}"#)
+        .unwrap();
+
+    assert!(matches!(
+        usecase.execute(authentication_input(user_id)).await,
+        Err(AuthError::WebauthnError(_))
+    ));
+    assert!(matches!(
+        usecase.execute(authentication_input(user_id)).await,
+        Err(AuthError::WebAuthnAuthenticationNotFound)
+    ));
+}
+
+/// A start that failed after clearing the previous ceremony leaves an empty state behind.
+#[tokio::test]
+async fn a_cleared_state_is_not_found() {
+    let hsm_store = Arc::new(MemoryHsmStore::new());
+    let usecase = FinishPasskeyRegistrationUseCase::new(
+        Arc::new(MemoryUserRepository::new()),
+        Arc::new(RejectingPasskeys),
+        hsm_store.clone(),
+    );
+    let user_id = Uuid::new_v4();
+    hsm_store.set(user_id, WEBAUTHN_REG_STATE, "").unwrap();
+
+    assert!(matches!(
+        usecase.execute(registration_input(user_id)).await,
+        Err(AuthError::WebAuthnRegistrationNotFound)
+    ));
+}
@@ -0,0 +1,195 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use contracts::auth::{
+    error::{AuthError, PasswordHasherError},
+    login::LoginInput,
+};
+
+use super::{TTL, attempt_limiter, session_token_issuer};
+use crate::{
+    config::{PasswordAlgorithm, PasswordHashingConfig},
+    entities::user::User,
+    infra::{
+        memory::{
+            refresh_token_store::MemoryRefreshTokenStore, user_repository::MemoryUserRepository,
+        },
+        password_hasher::{PhcPasswordHasher, tests::hashing_config},
+        tests::FakeAuthTokens,
+    },
+    ports::{password_hasher::PasswordHasher, user_repository::UserRepository},
+    usecases::login::LoginUseCase,
+};
+
+const USERNAME: &str = "ana@exemplo.com";
+const PASSWORD: &str = "Password123!";
+
+fn hasher(algorithm: PasswordAlgorithm, cost: u32) -> Arc<PhcPasswordHasher> {
+    Arc::new(PhcPasswordHasher::new(hashing_config(algorithm, cost)).unwrap())
+}
+
+/// Logs in twice with `current` after storing the password hashed by `stored`, and returns the
+/// stored hash before and after.
+async fn hash_after_login(
+    stored: Arc<PhcPasswordHasher>,
+    current: Arc<PhcPasswordHasher>,
+) -> (String, String) {
+    let user_repository = Arc::new(MemoryUserRepository::new());
+    let hash = stored.hash(PASSWORD.to_string()).await.unwrap();
+    let user = User::new(USERNAME, "Ana", &hash);
+    user_repository.create(user.clone()).await.unwrap();
+    let usecase = LoginUseCase::new(
+        user_repository.clone(),
+        Arc::new(FakeAuthTokens),
+        current.clone(),
+        Arc::new(session_token_issuer(Arc::new(
+            MemoryRefreshTokenStore::new(),
+        ))),
+        Arc::new(attempt_limiter()),
+        TTL,
+    )
+    .await
+    .unwrap();
+    let login = || {
+        usecase.execute(LoginInput {
+            username: USERNAME.to_string(),
+            password: PASSWORD.to_string(),
+            client_ip: None,
+        })
+    };
+
+    assert!(login().await.unwrap().access_token.is_some());
+    let rehashed = user_repository
+        .find_id(user.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .password;
+    assert!(!current.needs_rehash(&rehashed), "{}", rehashed);
+    assert!(login().await.unwrap().access_token.is_some());
+    (hash, rehashed)
+}
+
+#[tokio::test]
+async fn a_bcrypt_hash_is_upgraded_to_argon2id_on_login() {
+    let (hash, rehashed) = hash_after_login(
+        hasher(PasswordAlgorithm::Bcrypt, 1),
+        hasher(PasswordAlgorithm::Argon2id, 1),
+    )
+    .await;
+
+    assert!(hash.starts_with("$2b$"), "{}", hash);
+    assert!(rehashed.starts_with("$argon2id$"), "{}", rehashed);
+}
+
+#[tokio::test]
+async fn an_argon2id_hash_with_outdated_parameters_is_upgraded_on_login() {
+    let (hash, rehashed) = hash_after_login(
+        hasher(PasswordAlgorithm::Argon2id, 1),
+        hasher(PasswordAlgorithm::Argon2id, 2),
+    )
+    .await;
+
+    assert_ne!(rehashed, hash);
+    assert!(rehashed.starts_with("$argon2id$"), "{}", rehashed);
+}
+
+#[tokio::test]
+async fn a_current_hash_is_left_untouched_on_login() {
+    let (hash, rehashed) = hash_after_login(
+        hasher(PasswordAlgorithm::Argon2id, 1),
+        hasher(PasswordAlgorithm::Argon2id, 1),
+    )
+    .await;
+
+    assert_eq!(rehashed, hash);
+}
+
+/// Records whether each verified hash is a bcrypt one.
+struct RecordingHasher {
+    hasher: PhcPasswordHasher,
+    verified: Mutex<Vec<bool>>,
+}
+
+#[async_trait]
+impl PasswordHasher for RecordingHasher {
+    async fn hash(&self, password: String) -> Result<String, PasswordHasherError> {
+        self.hasher.hash(password).await
+    }
+
+    async fn verify(&self, password: String, hash: String) -> Result<bool, PasswordHasherError> {
+        self.verified.lock().unwrap().push(hash.starts_with("$2"));
+        self.hasher.verify(password, hash).await
+    }
+
+    fn needs_rehash(&self, hash: &str) -> bool {
+        self.hasher.needs_rehash(hash)
+    }
+
+    async fn dummy_hashes(&self) -> Result<Vec<String>, PasswordHasherError> {
+        self.hasher.dummy_hashes().await
+    }
+
+    fn same_algorithm(&self, a: &str, b: &str) -> bool {
+        self.hasher.same_algorithm(a, b)
+    }
+}
+
+/// With bcrypt hashes left over, users on either algorithm and unknown usernames all cost one
+/// Argon2id and one bcrypt check.
+#[tokio::test]
+async fn every_login_checks_each_stored_algorithm_once() {
+    let user_repository = Arc::new(MemoryUserRepository::new());
+    for (username, algorithm) in [
+        ("argon2@exemplo.com", PasswordAlgorithm::Argon2id),
+        ("bcrypt@exemplo.com", PasswordAlgorithm::Bcrypt),
+    ] {
+        let hash = hasher(algorithm, 1)
+            .hash(PASSWORD.to_string())
+            .await
+            .unwrap();
+        user_repository
+            .create(User::new(username, "Ana", &hash))
+            .await
+            .unwrap();
+    }
+    let password_hasher = Arc::new(RecordingHasher {
+        hasher: PhcPasswordHasher::new(PasswordHashingConfig {
+            legacy_bcrypt_hashes: true,
+            ..hashing_config(PasswordAlgorithm::Argon2id, 1)
+        })
+        .unwrap(),
+        verified: Mutex::new(Vec::new()),
+    });
+    let usecase = LoginUseCase::new(
+        user_repository,
+        Arc::new(FakeAuthTokens),
+        password_hasher.clone(),
+        Arc::new(session_token_issuer(Arc::new(
+            MemoryRefreshTokenStore::new(),
+        ))),
+        Arc::new(attempt_limiter()),
+        TTL,
+    )
+    .await
+    .unwrap();
+
+    for username in [
+        "argon2@exemplo.com",
+        "bcrypt@exemplo.com",
+        "ninguem@exemplo.com",
+    ] {
+        let result = usecase
+            .execute(LoginInput {
+                username: username.to_string(),
+                password: "Wrong123!".to_string(),
+                client_ip: None,
+            })
+            .await;
+
+        assert!(matches!(result, Err(AuthError::InvalidUsernameOrPassword)));
+        let mut verified = std::mem::take(&mut *password_hasher.verified.lock().unwrap());
+        verified.sort();
+        assert_eq!(verified, [false, true], "{}", username);
+    }
+}
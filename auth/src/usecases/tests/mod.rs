@@ -0,0 +1,44 @@
+//! Use cases run directly against the in-memory adapters and unsigned tokens.
+
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{
+    config::ThrottleConfig,
+    entities::claims::Claims,
+    infra::{
+        memory::{attempt_store::MemoryAttemptStore, refresh_token_store::MemoryRefreshTokenStore},
+        tests::FakeAuthTokens,
+    },
+    usecases::{attempt_limiter::AttemptLimiter, session_tokens::SessionTokenIssuer},
+};
+
+mod attempt_limiter;
+mod finish_passkey;
+mod login;
+mod modify_user;
+mod password_policy;
+mod refresh_token;
+mod start_passkey_login;
+mod verify_recovery_code;
+mod verify_totp_login;
+
+const TTL: u64 = 600;
+const CONCURRENT_REQUESTS: usize = 8;
+
+fn session_token_issuer(refresh_token_store: Arc<MemoryRefreshTokenStore>) -> SessionTokenIssuer {
+    SessionTokenIssuer::new(Arc::new(FakeAuthTokens), refresh_token_store, TTL, TTL)
+}
+
+fn attempt_limiter() -> AttemptLimiter {
+    AttemptLimiter::new(
+        Arc::new(MemoryAttemptStore::new()),
+        ThrottleConfig::default(),
+    )
+}
+
+/// What `LoginUseCase` returns to a user with a second factor, as minted by `FakeAuthTokens`.
+fn mfa_token(user_id: Uuid) -> String {
+    serde_json::to_string(&Claims::new("mfa_verification", user_id.to_string(), TTL)).unwrap()
+}
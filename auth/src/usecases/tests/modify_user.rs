@@ -0,0 +1,98 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use async_trait::async_trait;
+use contracts::auth::error::UserRepositoryError;
+use uuid::Uuid;
+
+use crate::{
+    entities::{totp_credential::TotpCredential, user::User},
+    infra::memory::user_repository::MemoryUserRepository,
+    ports::user_repository::UserRepository,
+    usecases::modify_user::modify_user,
+};
+
+/// Uses a recovery code right after the first read, as a concurrent login would.
+struct CodeUsedAfterRead {
+    users: MemoryUserRepository,
+    used: AtomicBool,
+}
+
+#[async_trait]
+impl UserRepository for CodeUsedAfterRead {
+    async fn create(&self, user: User) -> Result<(), UserRepositoryError> {
+        self.users.create(user).await
+    }
+
+    async fn update(&self, user: User) -> Result<(), UserRepositoryError> {
+        self.users.update(user).await
+    }
+
+    async fn find_username(&self, username: String) -> Result<Option<User>, UserRepositoryError> {
+        self.users.find_username(username).await
+    }
+
+    async fn find_id(&self, id: Uuid) -> Result<Option<User>, UserRepositoryError> {
+        let user = self.users.find_id(id).await;
+        if !self.used.swap(true, Ordering::SeqCst) {
+            self.users.consume_recovery_code(id, "used").await?;
+        }
+        user
+    }
+
+    async fn record_totp_use(
+        &self,
+        id: Uuid,
+        credential: &TotpCredential,
+        last_step: Option<u64>,
+    ) -> Result<bool, UserRepositoryError> {
+        self.users.record_totp_use(id, credential, last_step).await
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        id: Uuid,
+        code_hash: &str,
+    ) -> Result<bool, UserRepositoryError> {
+        self.users.consume_recovery_code(id, code_hash).await
+    }
+
+    async fn update_password_hash(
+        &self,
+        id: Uuid,
+        old_hash: &str,
+        new_hash: &str,
+    ) -> Result<bool, UserRepositoryError> {
+        self.users
+            .update_password_hash(id, old_hash, new_hash)
+            .await
+    }
+}
+
+/// The change is applied again on top of the concurrent write instead of undoing it.
+#[tokio::test]
+async fn a_concurrent_write_is_kept() {
+    let repository = Arc::new(CodeUsedAfterRead {
+        users: MemoryUserRepository::new(),
+        used: AtomicBool::new(false),
+    });
+    let mut user = User::new("ana@exemplo.com", "Ana", "$argon2id$hash");
+    user.recovery_codes = vec!["used".to_string(), "kept".to_string()];
+    repository.create(user.clone()).await.unwrap();
+
+    let mut changes = 0;
+    modify_user(repository.as_ref(), user.id, |user| {
+        changes += 1;
+        user.name = "Bia".to_string();
+        Ok(())
+    })
+    .await
+    .unwrap();
+
+    let stored = repository.find_id(user.id).await.unwrap().unwrap();
+    assert_eq!(changes, 2);
+    assert_eq!(stored.name, "Bia");
+    assert_eq!(stored.recovery_codes, vec!["kept".to_string()]);
+}
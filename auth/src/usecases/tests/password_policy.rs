@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use contracts::auth::error::{AuthError, BreachedPasswordsError, PasswordPolicyViolation};
+
+use crate::{
+    config::PasswordPolicyConfig, ports::breached_passwords::BreachedPasswords,
+    usecases::password_policy::PasswordPolicy,
+};
+
+const NAME: &str = "Ana";
+const USERNAME: &str = "ana@exemplo.com";
+const BREACHED: &str = "Senha@123";
+
+/// Knows a single breached password, or fails every lookup.
+struct OneBreachedPassword {
+    available: bool,
+}
+
+#[async_trait]
+impl BreachedPasswords for OneBreachedPassword {
+    async fn is_breached(&self, password: &str) -> Result<bool, BreachedPasswordsError> {
+        if !self.available {
+            return Err(BreachedPasswordsError::LookupFailed("offline".to_string()));
+        }
+        Ok(password == BREACHED)
+    }
+}
+
+fn policy(breached_passwords: Option<OneBreachedPassword>) -> PasswordPolicy {
+    PasswordPolicy::new(
+        PasswordPolicyConfig::default(),
+        breached_passwords.map(|b| Arc::new(b) as Arc<dyn BreachedPasswords>),
+    )
+}
+
+async fn violations(policy: &PasswordPolicy, password: &str) -> Vec<PasswordPolicyViolation> {
+    match policy.check(password, NAME, USERNAME).await {
+        Ok(()) => Vec::new(),
+        Err(AuthError::WeakPassword(violations)) => violations,
+        Err(err) => panic!("unexpected {:?}", err),
+    }
+}
+
+/// Lengths count characters, so accented passwords are not cut short by their bytes.
+#[tokio::test]
+async fn passwords_outside_the_length_limits_are_rejected() {
+    let policy = policy(None);
+
+    assert_eq!(
+        violations(&policy, "Ab@1").await,
+        [PasswordPolicyViolation::TooShort { min_length: 8 }]
+    );
+    assert_eq!(
+        violations(&policy, &format!("A@{}", "x".repeat(127))).await,
+        [PasswordPolicyViolation::TooLong { max_length: 128 }]
+    );
+    assert_eq!(violations(&policy, "Ção@ção!").await, []);
+    assert_eq!(
+        violations(&policy, &format!("A@{}", "é".repeat(126))).await,
+        []
+    );
+}
+
+#[tokio::test]
+async fn breached_passwords_are_rejected() {
+    let policy = policy(Some(OneBreachedPassword { available: true }));
+
+    assert_eq!(
+        violations(&policy, BREACHED).await,
+        [PasswordPolicyViolation::Breached]
+    );
+    assert_eq!(violations(&policy, "Senha@1234").await, []);
+}
+
+#[tokio::test]
+async fn without_a_breached_list_nothing_is_breached() {
+    assert_eq!(violations(&policy(None), BREACHED).await, []);
+}
+
+/// A lookup that fails is reported rather than taken as a pass.
+#[tokio::test]
+async fn a_failed_lookup_is_an_error() {
+    let policy = policy(Some(OneBreachedPassword { available: false }));
+
+    assert!(matches!(
+        policy.check(BREACHED, NAME, USERNAME).await,
+        Err(AuthError::BreachedPasswordsError(_))
+    ));
+}
@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use contracts::auth::{error::AuthError, token::RefreshTokenInput};
+
+use super::session_token_issuer;
+use crate::{
+    entities::user::User,
+    infra::{
+        memory::{
+            refresh_token_store::MemoryRefreshTokenStore, user_repository::MemoryUserRepository,
+        },
+        tests::FakeAuthTokens,
+    },
+    ports::user_repository::UserRepository,
+    usecases::refresh_token::RefreshTokenUseCase,
+};
+
+fn input(refresh_token: &str) -> RefreshTokenInput {
+    RefreshTokenInput {
+        refresh_token: refresh_token.to_string(),
+    }
+}
+
+/// Presenting a refresh token that was already rotated revokes its family, so the token that
+/// replaced it stops working too.
+#[tokio::test]
+async fn reusing_a_rotated_token_revokes_the_family() {
+    let user_repository = Arc::new(MemoryUserRepository::new());
+    let user = User::new("ana@exemplo.com", "Ana", "$argon2id$hash");
+    user_repository.create(user.clone()).await.unwrap();
+    let refresh_token_store = Arc::new(MemoryRefreshTokenStore::new());
+    let issuer = Arc::new(session_token_issuer(refresh_token_store.clone()));
+    let usecase = RefreshTokenUseCase::new(
+        user_repository,
+        Arc::new(FakeAuthTokens),
+        refresh_token_store,
+        issuer.clone(),
+    );
+
+    let old = issuer.issue(user.id, None).await.unwrap().refresh_token;
+    let newest = usecase.execute(input(&old)).await.unwrap().refresh_token;
+    assert_ne!(newest, old);
+
+    assert!(matches!(
+        usecase.execute(input(&old)).await,
+        Err(AuthError::RefreshTokenReused)
+    ));
+    assert!(matches!(
+        usecase.execute(input(&newest)).await,
+        Err(AuthError::InvalidRefreshToken)
+    ));
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use contracts::auth::error::{AuthError, HSMStoreError};
+
+use crate::{
+    config::WebauthnConfig,
+    entities::totp::WEBAUTHN_LOGIN_STATE,
+    infra::{memory::hsm_store::MemoryHsmStore, webauthn_passkeys::WebauthnPasskeys},
+    ports::hsm_store::HSMStore,
+    usecases::start_passkey_login::StartPasskeyLoginUseCase,
+};
+
+/// Past the cap on pending logins a start is refused, until one of them is finished.
+#[tokio::test]
+async fn pending_logins_are_capped() {
+    let hsm_store = Arc::new(MemoryHsmStore::with_max_expiring(2));
+    let usecase = StartPasskeyLoginUseCase::new(
+        hsm_store.clone(),
+        Arc::new(
+            WebauthnPasskeys::new(&WebauthnConfig::default())
+                .await
+                .unwrap(),
+        ),
+    );
+
+    let first = usecase.execute().await.unwrap();
+    usecase.execute().await.unwrap();
+    assert!(matches!(
+        usecase.execute().await,
+        Err(AuthError::SetHsmStoreError(HSMStoreError::Full))
+    ));
+
+    hsm_store
+        .take(first.login_id, WEBAUTHN_LOGIN_STATE)
+        .unwrap()
+        .unwrap();
+    usecase.execute().await.unwrap();
+}
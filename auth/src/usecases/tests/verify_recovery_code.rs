@@ -0,0 +1,115 @@
+use std::{collections::HashSet, sync::Arc};
+
+use contracts::auth::{error::AuthError, recovery::RecoveryVerifyLoginInput};
+
+use super::{CONCURRENT_REQUESTS, attempt_limiter, mfa_token, session_token_issuer};
+use crate::{
+    entities::{
+        recovery_code::{RECOVERY_CODE_COUNT, RecoveryCodes, hash_recovery_code},
+        user::User,
+    },
+    infra::{
+        memory::{
+            refresh_token_store::MemoryRefreshTokenStore, user_repository::MemoryUserRepository,
+        },
+        tests::FakeAuthTokens,
+    },
+    ports::user_repository::UserRepository,
+    usecases::verify_recovery_code::VerifyRecoveryCodeUseCase,
+};
+
+/// A user with a fresh batch of recovery codes, the plain codes and the use case to spend them.
+async fn setup() -> (User, Vec<String>, Arc<VerifyRecoveryCodeUseCase>) {
+    let user_repository = Arc::new(MemoryUserRepository::new());
+    let mut user = User::new("ana@exemplo.com", "Ana", "$argon2id$hash");
+    let codes = user.regenerate_recovery_codes();
+    user_repository.create(user.clone()).await.unwrap();
+
+    let usecase = VerifyRecoveryCodeUseCase::new(
+        user_repository,
+        Arc::new(FakeAuthTokens),
+        Arc::new(session_token_issuer(Arc::new(
+            MemoryRefreshTokenStore::new(),
+        ))),
+        Arc::new(attempt_limiter()),
+    );
+    (user, codes, Arc::new(usecase))
+}
+
+fn input(user: &User, code: &str) -> RecoveryVerifyLoginInput {
+    RecoveryVerifyLoginInput {
+        mfa_token: mfa_token(user.id),
+        code: code.to_string(),
+        client_ip: None,
+    }
+}
+
+#[test]
+fn generated_codes_are_distinct_and_stored_hashed() {
+    let codes = RecoveryCodes::generate();
+
+    assert_eq!(codes.plain.len(), RECOVERY_CODE_COUNT);
+    assert_eq!(
+        codes.plain.iter().collect::<HashSet<_>>().len(),
+        RECOVERY_CODE_COUNT
+    );
+    for (plain, hashed) in codes.plain.iter().zip(&codes.hashed) {
+        let (first, second) = plain.split_once('-').unwrap();
+        assert_eq!((first.len(), second.len()), (5, 5));
+        assert!(!plain.contains(['0', 'O', '1', 'I']));
+        assert_ne!(plain, hashed);
+        assert_eq!(*hashed, hash_recovery_code(plain));
+        // Case, spaces and the separator do not matter when typed back.
+        assert_eq!(
+            *hashed,
+            hash_recovery_code(&format!(" {}{} ", first.to_lowercase(), second))
+        );
+    }
+}
+
+#[tokio::test]
+async fn a_code_logs_in_once() {
+    let (user, codes, usecase) = setup().await;
+
+    let output = usecase.execute(input(&user, &codes[0])).await.unwrap();
+    assert!(!output.access_token.is_empty());
+    assert!(!output.refresh_token.is_empty());
+
+    assert!(matches!(
+        usecase.execute(input(&user, &codes[0])).await,
+        Err(AuthError::InvalidRecoveryCode)
+    ));
+    // The other codes still work.
+    usecase.execute(input(&user, &codes[1])).await.unwrap();
+}
+
+#[tokio::test]
+async fn unknown_codes_are_rejected() {
+    let (user, _, usecase) = setup().await;
+
+    assert!(matches!(
+        usecase.execute(input(&user, "AAAAA-BBBBB")).await,
+        Err(AuthError::InvalidRecoveryCode)
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_uses_of_a_code_log_in_once() {
+    let (user, codes, usecase) = setup().await;
+
+    let logins: Vec<_> = (0..CONCURRENT_REQUESTS)
+        .map(|_| {
+            let (usecase, input) = (usecase.clone(), input(&user, &codes[0]));
+            tokio::spawn(async move { usecase.execute(input).await })
+        })
+        .collect();
+    let mut logged_in = 0;
+    for login in logins {
+        match login.await.unwrap() {
+            Ok(_) => logged_in += 1,
+            Err(AuthError::InvalidRecoveryCode | AuthError::TooManyAttempts { .. }) => {}
+            Err(err) => panic!("unexpected error: {:?}", err),
+        }
+    }
+    assert_eq!(logged_in, 1);
+}
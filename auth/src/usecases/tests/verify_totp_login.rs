@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use contracts::auth::{
+    error::{AuthError, TotpError},
+    totp::TOTPVerifyLoginInput,
+};
+use tokio::sync::Barrier;
+
+use super::{attempt_limiter, mfa_token, session_token_issuer};
+use crate::{
+    entities::{
+        totp_credential::{TotpAlgorithm, TotpCredential, TotpParameters},
+        user::User,
+    },
+    infra::{
+        memory::{
+            refresh_token_store::MemoryRefreshTokenStore, user_repository::MemoryUserRepository,
+        },
+        tests::FakeAuthTokens,
+    },
+    ports::{for_totp::ForTotp, user_repository::UserRepository},
+    usecases::{totp_verifier::TotpVerifier, verify_totp_login::VerifyTotpLoginUseCase},
+};
+
+const CODE: &str = "123456";
+/// Step `CODE` belongs to.
+const STEP: u64 = 2;
+
+/// Accepts `CODE` for `STEP`, once every request in a round has checked it, so concurrent
+/// requests all pass the check before any of them records the step.
+struct RoundTotp {
+    round: Barrier,
+}
+
+#[async_trait]
+impl ForTotp for RoundTotp {
+    async fn verify(
+        &self,
+        _secret: String,
+        _parameters: Option<TotpParameters>,
+        token: String,
+        last_step: Option<u64>,
+    ) -> Result<Option<u64>, TotpError> {
+        let matched = token == CODE && last_step.is_none_or(|last| STEP > last);
+        self.round.wait().await;
+        Ok(matched.then_some(STEP))
+    }
+
+    async fn auth_url(&self, _account: String) -> Result<(String, String), TotpError> {
+        Err(TotpError::AuthUrlGenerationFailed)
+    }
+}
+
+async fn setup(round: usize) -> (User, Arc<VerifyTotpLoginUseCase>) {
+    let user_repository = Arc::new(MemoryUserRepository::new());
+    let mut user = User::new("ana@exemplo.com", "Ana", "$argon2id$hash");
+    user.totp_credentials.push(TotpCredential::new(
+        "Phone".to_string(),
+        "SECRET".to_string(),
+        TotpParameters {
+            algorithm: TotpAlgorithm::Sha1,
+            digits: 6,
+            step: 30,
+        },
+        STEP - 1,
+    ));
+    user_repository.create(user.clone()).await.unwrap();
+
+    let usecase = VerifyTotpLoginUseCase::new(
+        user_repository.clone(),
+        Arc::new(FakeAuthTokens),
+        Arc::new(TotpVerifier::new(
+            Arc::new(RoundTotp {
+                round: Barrier::new(round),
+            }),
+            user_repository,
+        )),
+        Arc::new(session_token_issuer(Arc::new(
+            MemoryRefreshTokenStore::new(),
+        ))),
+        Arc::new(attempt_limiter()),
+    );
+    (user, Arc::new(usecase))
+}
+
+fn input(user: &User) -> TOTPVerifyLoginInput {
+    TOTPVerifyLoginInput {
+        code: CODE.to_string(),
+        mfa_token: mfa_token(user.id),
+        client_ip: None,
+    }
+}
+
+#[tokio::test]
+async fn a_code_is_not_accepted_twice() {
+    let (user, usecase) = setup(1).await;
+
+    usecase.execute(input(&user)).await.unwrap();
+    assert!(matches!(
+        usecase.execute(input(&user)).await,
+        Err(AuthError::InvalidTotpCode)
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn the_same_code_sent_twice_concurrently_logs_in_once() {
+    let (user, usecase) = setup(2).await;
+
+    let logins: Vec<_> = (0..2)
+        .map(|_| {
+            let (usecase, input) = (usecase.clone(), input(&user));
+            tokio::spawn(async move { usecase.execute(input).await })
+        })
+        .collect();
+    let mut results = Vec::new();
+    for login in logins {
+        results.push(login.await.unwrap());
+    }
+
+    assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+    assert!(
+        results
+            .iter()
+            .any(|result| matches!(result, Err(AuthError::InvalidTotpCode)))
+    );
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use contracts::auth::error::AuthError;
+
+use crate::{
+    entities::user::User,
+    ports::{for_totp::ForTotp, user_repository::UserRepository},
+};
+
+/// Checks a code against every TOTP credential of a user.
+pub struct TotpVerifier {
+    for_totp: Arc<dyn ForTotp>,
+    user_repository: Arc<dyn UserRepository>,
+}
+
+impl TotpVerifier {
+    pub fn new(for_totp: Arc<dyn ForTotp>, user_repository: Arc<dyn UserRepository>) -> Self {
+        Self {
+            for_totp,
+            user_repository,
+        }
+    }
+
+    /// Records the step of the matching credential in the repository. A code whose step another
+    /// request recorded first, even concurrently, is rejected as invalid.
+    pub async fn verify(&self, user: &User, code: String) -> Result<(), AuthError> {
+        if user.totp_credentials.is_empty() {
+            return Err(AuthError::TotpNotEnabled);
+        }
+
+        for credential in &user.totp_credentials {
+            let matched = self
+                .for_totp
+                .verify(
+                    credential.secret.clone(),
+                    credential.parameters,
+                    code.clone(),
+                    credential.last_step,
+                )
+                .await
+                .map_err(AuthError::TotpError)?;
+            if let Some(step) = matched {
+                let mut used = credential.clone();
+                used.mark_used(step);
+                let recorded = self
+                    .user_repository
+                    .record_totp_use(user.id, &used, credential.last_step)
+                    .await
+                    .map_err(AuthError::SaveUserError)?;
+                return if recorded {
+                    Ok(())
+                } else {
+                    Err(AuthError::InvalidTotpCode)
+                };
+            }
+        }
+
+        Err(AuthError::InvalidTotpCode)
+    }
+}
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+
+use contracts::auth::{error::AuthError, throttle::UnlockAttemptsInput};
+
+use crate::{
+    entities::failed_attempts::AttemptKey,
+    ports::{attempt_store::AttemptStore, user_repository::UserRepository},
+};
+
+pub struct UnlockAttemptsUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    attempt_store: Arc<dyn AttemptStore>,
+}
+
+impl UnlockAttemptsUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        attempt_store: Arc<dyn AttemptStore>,
+    ) -> Self {
+        Self {
+            user_repository,
+            attempt_store,
+        }
+    }
+
+    /// Forgets the failed attempts of the username, of its user's second factors and of the
+    /// address, whichever are given.
+    pub async fn execute(&self, input: UnlockAttemptsInput) -> Result<(), AuthError> {
+        let mut keys = Vec::new();
+        if let Some(username) = input.username {
+            let user = self
+                .user_repository
+                .find_username(username.clone())
+                .await
+                .map_err(AuthError::FindUserError)?;
+            keys.push(AttemptKey::Username(username));
+            keys.extend(user.map(|user| AttemptKey::SecondFactor(user.id)));
+        }
+        keys.extend(input.client_ip.map(AttemptKey::Ip));
+
+        for key in keys {
+            self.attempt_store
+                .clear(&key.id())
+                .await
+                .map_err(AuthError::AttemptStoreError)?;
+        }
+        Ok(())
+    }
+}
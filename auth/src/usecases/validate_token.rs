@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    error::AuthError,
+    token::{ValidateTokenInput, ValidateTokenOutput},
+};
+
+use crate::ports::for_auth_tokens::ForAuthTokens;
+
+pub struct ValidateTokenUseCase {
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+}
+
+impl ValidateTokenUseCase {
+    pub fn new(for_auth_tokens: Arc<dyn ForAuthTokens>) -> Self {
+        Self { for_auth_tokens }
+    }
+
+    pub async fn execute(
+        &self,
+        input: ValidateTokenInput,
+    ) -> Result<ValidateTokenOutput, AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.token, input.token_type)
+            .await
+            .map_err(AuthError::from)?;
+
+        Ok(ValidateTokenOutput {
+            sub: claims.sub,
+            token_type: claims.token_type,
+            scope: claims.scope,
+            jti: claims.jti,
+            exp: claims.exp,
+        })
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    error::AuthError,
+    recovery::{RecoveryVerifyLoginInput, RecoveryVerifyLoginOutput},
+};
+
+use crate::{
+    entities::{failed_attempts::AttemptKey, recovery_code::hash_recovery_code},
+    ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository},
+    usecases::{attempt_limiter::AttemptLimiter, session_tokens::SessionTokenIssuer},
+};
+
+pub struct VerifyRecoveryCodeUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+    session_token_issuer: Arc<SessionTokenIssuer>,
+    attempt_limiter: Arc<AttemptLimiter>,
+}
+
+impl VerifyRecoveryCodeUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+        session_token_issuer: Arc<SessionTokenIssuer>,
+        attempt_limiter: Arc<AttemptLimiter>,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+            session_token_issuer,
+            attempt_limiter,
+        }
+    }
+
+    /// Completes an MFA login with a recovery code, which is consumed on success.
+    pub async fn execute(
+        &self,
+        input: RecoveryVerifyLoginInput,
+    ) -> Result<RecoveryVerifyLoginOutput, AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.mfa_token, "mfa_verification".to_string())
+            .await
+            .map_err(AuthError::from)?;
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        let user = self
+            .user_repository
+            .find_id(user_id)
+            .await
+            .map_err(AuthError::FindUserError)?
+            .ok_or(AuthError::UserNotFound)?;
+
+        let mut keys = vec![AttemptKey::SecondFactor(user_id)];
+        keys.extend(input.client_ip.map(AttemptKey::Ip));
+        self.attempt_limiter
+            .guard(&keys, async {
+                let consumed = self
+                    .user_repository
+                    .consume_recovery_code(user.id, &hash_recovery_code(&input.code))
+                    .await
+                    .map_err(AuthError::SaveUserError)?;
+                if consumed {
+                    Ok(())
+                } else {
+                    Err(AuthError::InvalidRecoveryCode)
+                }
+            })
+            .await?;
+
+        let tokens = self.session_token_issuer.issue(user.id, None).await?;
+
+        Ok(RecoveryVerifyLoginOutput {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+        })
+    }
+}
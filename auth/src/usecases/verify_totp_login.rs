@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use contracts::auth::{
+    error::AuthError,
+    totp::{TOTPVerifyLoginInput, TOTPVerifyLoginOutput},
+};
+
+use crate::{
+    entities::failed_attempts::AttemptKey,
+    ports::{for_auth_tokens::ForAuthTokens, user_repository::UserRepository},
+    usecases::{
+        attempt_limiter::AttemptLimiter, session_tokens::SessionTokenIssuer,
+        totp_verifier::TotpVerifier,
+    },
+};
+
+pub struct VerifyTotpLoginUseCase {
+    user_repository: Arc<dyn UserRepository>,
+    for_auth_tokens: Arc<dyn ForAuthTokens>,
+    totp_verifier: Arc<TotpVerifier>,
+    session_token_issuer: Arc<SessionTokenIssuer>,
+    attempt_limiter: Arc<AttemptLimiter>,
+}
+
+impl VerifyTotpLoginUseCase {
+    pub fn new(
+        user_repository: Arc<dyn UserRepository>,
+        for_auth_tokens: Arc<dyn ForAuthTokens>,
+        totp_verifier: Arc<TotpVerifier>,
+        session_token_issuer: Arc<SessionTokenIssuer>,
+        attempt_limiter: Arc<AttemptLimiter>,
+    ) -> Self {
+        Self {
+            user_repository,
+            for_auth_tokens,
+            totp_verifier,
+            session_token_issuer,
+            attempt_limiter,
+        }
+    }
+
+    pub async fn execute(
+        &self,
+        input: TOTPVerifyLoginInput,
+    ) -> Result<TOTPVerifyLoginOutput, AuthError> {
+        let claims = self
+            .for_auth_tokens
+            .validate_token(input.mfa_token, "mfa_verification".to_string())
+            .await
+            .map_err(AuthError::from)?;
+
+        let user_id = uuid::Uuid::parse_str(&claims.sub).map_err(|_| AuthError::UserNotFound)?;
+        let user = self
+            .user_repository
+            .find_id(user_id)
+            .await
+            .map_err(AuthError::FindUserError)?
+            .ok_or(AuthError::UserNotFound)?;
+
+        let mut keys = vec![AttemptKey::SecondFactor(user_id)];
+        keys.extend(input.client_ip.map(AttemptKey::Ip));
+        self.attempt_limiter
+            .guard(&keys, self.totp_verifier.verify(&user, input.code))
+            .await?;
+
+        let tokens = self.session_token_issuer.issue(user_id, None).await?;
+
+        Ok(TOTPVerifyLoginOutput {
+            access_token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+        })
+    }
+}
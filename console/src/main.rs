@@ -1,22 +1,80 @@
+use app_config::Config;
 use auth::AuthComponent;
 use contracts::auth::login::LoginInput;
 use contracts::auth::mfa::MfaRegistrationInput;
 use contracts::auth::signup::SignupInput;
+use contracts::auth::throttle::UnlockAttemptsInput;
 use contracts::auth::totp::TOTPFinishRegistrationInput;
 use contracts::auth::{Component, totp::TOTPStartRegistrationInput};
 use env_logger::{Builder, Target};
+use std::path::PathBuf;
 use totp_rs::Secret;
 
+/// Splits `--config <path>` off the command line, leaving the command and its arguments.
+fn parse_args() -> (Option<PathBuf>, Vec<String>) {
+    let mut config = None;
+    let mut command = Vec::new();
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.strip_prefix("--config=") {
+            Some(path) => config = Some(path.into()),
+            None if arg == "--config" => {
+                config = Some(iter.next().expect("--config <path>").into())
+            }
+            None => command.push(arg),
+        }
+    }
+    (config, command)
+}
+
 #[tokio::main]
 async fn main() {
     Builder::new().target(Target::Stdout).init();
 
-    let auth = AuthComponent::new();
+    // The api's configuration, so rotation uses the same keys and retirement window and unlock
+    // the same database.
+    let (config, command) = parse_args();
+    let config = Config::load(config.as_deref()).unwrap();
+    let auth = AuthComponent::builder(config.auth_config().unwrap())
+        .build()
+        .await
+        .unwrap();
+
+    // `console rotate-keys`: promote the next signing key in the keys directory and stage a new one
+    if command.first().map(String::as_str) == Some("rotate-keys") {
+        let result = auth.rotate_signing_keys().await.unwrap();
+        println!("Active key: {}", result.active_kid);
+        println!("Next key: {}", result.next_kid.unwrap_or_default());
+        println!("Retired keys: {}", result.retired_kids.join(", "));
+        println!("Removed keys: {}", result.removed_kids.join(", "));
+        return;
+    }
+
+    // `console unlock user <username>` / `console unlock ip <addr>`: lift a lockout in the database
+    if command.first().map(String::as_str) == Some("unlock") {
+        let input = match &command[1..] {
+            [kind, username] if kind == "user" => UnlockAttemptsInput {
+                username: Some(username.clone()),
+                client_ip: None,
+            },
+            [kind, addr] if kind == "ip" => UnlockAttemptsInput {
+                username: None,
+                client_ip: Some(addr.parse().expect("invalid address")),
+            },
+            _ => {
+                eprintln!("Usage: console unlock user <username> | console unlock ip <addr>");
+                std::process::exit(2);
+            }
+        };
+        auth.unlock_attempts(input).await.unwrap();
+        println!("Unlocked");
+        return;
+    }
 
     auth.signup(SignupInput {
         name: "User 1".to_string(),
         username: "user1".to_string(),
-        password: "password123".to_string(),
+        password: "Password123!".to_string(),
     })
     .await
     .unwrap();
@@ -24,7 +82,8 @@ async fn main() {
     let result = auth
         .login(LoginInput {
             username: "user1".to_string(),
-            password: "password123".to_string(),
+            password: "Password123!".to_string(),
+            client_ip: None,
         })
         .await
         .unwrap();
@@ -71,6 +130,7 @@ async fn main() {
         .finish_totp_registration(TOTPFinishRegistrationInput {
             mfa_token: mfa_registration,
             code,
+            label: None,
         })
         .await
         .unwrap();
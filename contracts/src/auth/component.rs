@@ -1,4 +1,6 @@
-use crate::auth::{error::AuthError, login, mfa, passkey, signup, totp};
+use crate::auth::{
+    error::AuthError, login, logout, mfa, passkey, recovery, signup, throttle, token, totp,
+};
 
 #[async_trait::async_trait]
 pub trait Component {
@@ -6,6 +8,15 @@ pub trait Component {
 
     async fn signup(&self, input: signup::SignupInput) -> Result<signup::SignupOutput, AuthError>;
 
+    async fn logout(&self, input: logout::LogoutInput) -> Result<(), AuthError>;
+
+    async fn logout_all(&self, input: logout::LogoutAllInput) -> Result<(), AuthError>;
+
+    async fn refresh_token(
+        &self,
+        input: token::RefreshTokenInput,
+    ) -> Result<token::RefreshTokenOutput, AuthError>;
+
     async fn start_totp_registration(
         &self,
         input: totp::TOTPStartRegistrationInput,
@@ -16,6 +27,21 @@ pub trait Component {
         input: totp::TOTPFinishRegistrationInput,
     ) -> Result<totp::TOTPFinishRegistrationOutput, AuthError>;
 
+    async fn verify_totp_login(
+        &self,
+        input: totp::TOTPVerifyLoginInput,
+    ) -> Result<totp::TOTPVerifyLoginOutput, AuthError>;
+
+    async fn list_totp_credentials(
+        &self,
+        input: totp::TOTPListCredentialsInput,
+    ) -> Result<Vec<totp::TOTPCredentialOutput>, AuthError>;
+
+    async fn rename_totp_credential(
+        &self,
+        input: totp::TOTPRenameCredentialInput,
+    ) -> Result<(), AuthError>;
+
     async fn start_passkey_registration(
         &self,
         input: passkey::PasskeyStartRegistrationInput,
@@ -24,7 +50,7 @@ pub trait Component {
     async fn finish_passkey_registration(
         &self,
         input: passkey::PasskeyFinishRegistrationInput,
-    ) -> Result<(), AuthError>;
+    ) -> Result<passkey::PasskeyFinishRegistrationOutput, AuthError>;
 
     async fn start_passkey_authentication(
         &self,
@@ -34,12 +60,55 @@ pub trait Component {
     async fn finish_passkey_authentication(
         &self,
         input: passkey::PasskeyFinishAuthenticationInput,
-    ) -> Result<(), AuthError>;
+    ) -> Result<passkey::PasskeyFinishAuthenticationOutput, AuthError>;
+
+    /// Usernameless login: the user is resolved from the discoverable credential.
+    async fn start_passkey_login(&self) -> Result<passkey::PasskeyStartLoginOutput, AuthError>;
+
+    async fn finish_passkey_login(
+        &self,
+        input: passkey::PasskeyFinishLoginInput,
+    ) -> Result<passkey::PasskeyFinishAuthenticationOutput, AuthError>;
+
+    async fn list_passkeys(
+        &self,
+        input: passkey::PasskeyListInput,
+    ) -> Result<Vec<passkey::PasskeyOutput>, AuthError>;
+
+    async fn rename_passkey(&self, input: passkey::PasskeyRenameInput) -> Result<(), AuthError>;
+
+    async fn remove_passkey(&self, input: passkey::PasskeyRemoveInput) -> Result<(), AuthError>;
+
+    async fn verify_recovery_code(
+        &self,
+        input: recovery::RecoveryVerifyLoginInput,
+    ) -> Result<recovery::RecoveryVerifyLoginOutput, AuthError>;
+
+    async fn regenerate_recovery_codes(
+        &self,
+        input: recovery::RecoveryRegenerateCodesInput,
+    ) -> Result<recovery::RecoveryCodesOutput, AuthError>;
+
+    async fn step_up_mfa(
+        &self,
+        input: mfa::MfaStepUpInput,
+    ) -> Result<mfa::MfaStepUpOutput, AuthError>;
+
+    async fn remove_totp(&self, input: mfa::MfaRemoveTotpInput) -> Result<(), AuthError>;
 
     async fn get_mfa_registration(
         &self,
         input: mfa::MfaRegistrationInput,
     ) -> Result<mfa::MfaRegistrationOutput, AuthError>;
 
+    async fn validate_token(
+        &self,
+        input: token::ValidateTokenInput,
+    ) -> Result<token::ValidateTokenOutput, AuthError>;
+
     async fn get_jwks(&self) -> Result<String, AuthError>;
+
+    async fn rotate_signing_keys(&self) -> Result<token::RotateSigningKeysOutput, AuthError>;
+
+    async fn unlock_attempts(&self, input: throttle::UnlockAttemptsInput) -> Result<(), AuthError>;
 }
@@ -5,18 +5,67 @@ use webauthn_rs::prelude::WebauthnError;
 #[derive(Debug)]
 pub enum HSMStoreError {
     StorageError(String),
+    /// Too many expiring values are outstanding to take another one.
+    Full,
 }
 
 impl std::fmt::Display for HSMStoreError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             HSMStoreError::StorageError(msg) => write!(f, "Storage error: {}", msg),
+            HSMStoreError::Full => write!(f, "Store is full"),
         }
     }
 }
 
 impl std::error::Error for HSMStoreError {}
 
+#[derive(Debug, thiserror::Error)]
+pub enum RefreshTokenStoreError {
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TokenRevocationStoreError {
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AttemptStoreError {
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasswordHasherError {
+    #[error("Too many passwords waiting to be hashed.")]
+    Busy,
+
+    #[error("Password hashing or verification failed: {0}")]
+    HashingFailed(String),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BreachedPasswordsError {
+    #[error("Failed to look up breached passwords: {0}")]
+    LookupFailed(String),
+}
+
+/// Rule of the password policy that a new password breaks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordPolicyViolation {
+    TooShort { min_length: usize },
+    TooLong { max_length: usize },
+    MissingUppercase,
+    MissingLowercase,
+    MissingDigit,
+    MissingSpecial,
+    ContainsPersonalInfo,
+    Breached,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum TotpError {
     #[error("Failed to verify TOTP code.")]
@@ -24,6 +73,18 @@ pub enum TotpError {
 
     #[error("Failed to generate authentication URL.")]
     AuthUrlGenerationFailed,
+
+    #[error("Stored TOTP secret is invalid.")]
+    InvalidSecret,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasskeyError {
+    #[error("WebAuthn error: {0}")]
+    Webauthn(WebauthnError),
+
+    #[error("Failed to read or write the ceremony state: {0}")]
+    InvalidState(serde_json::Error),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -42,11 +103,25 @@ pub enum AuthTokenError {
 
     #[error("Failed to fetch JWKs for token validation.")]
     JwksFetchError,
+
+    #[error("Failed to rotate the signing keys.")]
+    KeyRotationFailure,
+
+    #[error("The token has been revoked.")]
+    TokenRevoked,
+
+    #[error("Failed to check whether the token was revoked.")]
+    RevocationCheckFailed,
 }
 
 #[derive(Debug)]
 pub enum UserRepositoryError {
     ConnectionError(String),
+    Conflict(String),
+    NotFound,
+    /// The user was written by someone else since it was read.
+    Stale,
+    SerializationError(String),
 }
 
 impl fmt::Display for UserRepositoryError {
@@ -55,6 +130,12 @@ impl fmt::Display for UserRepositoryError {
             UserRepositoryError::ConnectionError(msg) => {
                 write!(f, "Connection error: {}", msg)
             }
+            UserRepositoryError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            UserRepositoryError::NotFound => write!(f, "User not found"),
+            UserRepositoryError::Stale => write!(f, "User changed since it was read"),
+            UserRepositoryError::SerializationError(msg) => {
+                write!(f, "Serialization error: {}", msg)
+            }
         }
     }
 }
@@ -78,12 +159,45 @@ pub enum AuthError {
     #[error("Token validation failed.")]
     TokenValidationFailed,
 
+    #[error("The access token lacks the required scope.")]
+    InsufficientScope,
+
+    #[error("A password or TOTP code is required to elevate the session.")]
+    StepUpCredentialRequired,
+
+    #[error("Refresh token is invalid or has already been rotated.")]
+    InvalidRefreshToken,
+
+    #[error("Refresh token reuse detected; the token family was revoked.")]
+    RefreshTokenReused,
+
+    #[error("Refresh token store error: {0}")]
+    RefreshTokenStoreError(RefreshTokenStoreError),
+
+    #[error("Token revocation store error: {0}")]
+    TokenRevocationStoreError(TokenRevocationStoreError),
+
+    #[error("Internal error while validating a token: {0}")]
+    InternalError(AuthTokenError),
+
     #[error("Failed to fetch JWKS.")]
     JwksFetchFailed,
 
+    #[error("Failed to rotate the signing keys.")]
+    SigningKeyRotationFailed,
+
     #[error("User not found.")]
     UserNotFound,
 
+    #[error("Username is already taken.")]
+    UsernameAlreadyTaken,
+
+    #[error("Password does not meet the password policy: {0:?}")]
+    WeakPassword(Vec<PasswordPolicyViolation>),
+
+    #[error("Breached passwords error: {0}")]
+    BreachedPasswordsError(BreachedPasswordsError),
+
     #[error("WebAuthn registration state not found.")]
     WebAuthnRegistrationNotFound,
 
@@ -96,8 +210,8 @@ pub enum AuthError {
     #[error("Failed to write to HSM store: {0}")]
     SetHsmStoreError(HSMStoreError),
 
-    #[error("Password hashing or verification failed: {0}")]
-    BcryptError(#[from] bcrypt::BcryptError),
+    #[error("Password hasher error: {0}")]
+    PasswordHasherError(PasswordHasherError),
 
     #[error("Serialization or deserialization failed: {0}")]
     SerdeError(#[from] serde_json::Error),
@@ -114,10 +228,65 @@ pub enum AuthError {
     #[error("TOTP registration state not found.")]
     TotpRegistrationNotFound,
 
+    #[error("TOTP is not enabled for this user.")]
+    TotpNotEnabled,
+
+    #[error("Invalid TOTP code.")]
+    InvalidTotpCode,
+
+    #[error("TOTP credential not found.")]
+    TotpCredentialNotFound,
+
+    #[error("Passkey not found.")]
+    PasskeyNotFound,
+
+    #[error("No second factor is enrolled for this user.")]
+    MfaNotEnabled,
+
+    #[error("Cannot remove the last second factor.")]
+    LastMfaFactor,
+
+    #[error("Invalid recovery code.")]
+    InvalidRecoveryCode,
+
+    #[error("Too many failed attempts; retry in {retry_after} seconds.")]
+    TooManyAttempts { retry_after: u64 },
+
+    #[error("Attempt store error: {0}")]
+    AttemptStoreError(AttemptStoreError),
+
     #[error("WebAuthn error: {0}")]
     WebauthnError(WebauthnError),
 }
 
+/// Rejected tokens become `TokenValidationFailed`; failures to read the keys or the revocation
+/// list say nothing about the token and become `InternalError`.
+impl From<AuthTokenError> for AuthError {
+    fn from(err: AuthTokenError) -> Self {
+        match err {
+            AuthTokenError::InvalidToken
+            | AuthTokenError::TokenExpired
+            | AuthTokenError::InvalidSignature
+            | AuthTokenError::TokenRevoked => AuthError::TokenValidationFailed,
+            AuthTokenError::TokenCreationFailure
+            | AuthTokenError::JwksFetchError
+            | AuthTokenError::KeyRotationFailure
+            | AuthTokenError::RevocationCheckFailed => AuthError::InternalError(err),
+        }
+    }
+}
+
+/// A ceremony the relying party refused is a failed verification; a state it could not read or
+/// write is an internal failure.
+impl From<PasskeyError> for AuthError {
+    fn from(err: PasskeyError) -> Self {
+        match err {
+            PasskeyError::Webauthn(err) => AuthError::WebauthnError(err),
+            PasskeyError::InvalidState(err) => AuthError::SerdeError(err),
+        }
+    }
+}
+
 pub enum InternalAuthError {
     FindUserError(UserRepositoryError),
     SetHsmStoreError(HSMStoreError),
@@ -1,7 +1,11 @@
+use std::net::IpAddr;
+
 #[derive(Debug)]
 pub struct LoginInput {
     pub username: String,
     pub password: String,
+    /// Address the attempt came from, throttled alongside the username.
+    pub client_ip: Option<IpAddr>,
 }
 
 #[derive(Debug)]
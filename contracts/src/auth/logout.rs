@@ -0,0 +1,10 @@
+#[derive(Debug)]
+pub struct LogoutInput {
+    pub access_token: String,
+    pub refresh_token: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct LogoutAllInput {
+    pub access_token: String,
+}
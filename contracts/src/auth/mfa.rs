@@ -1,3 +1,5 @@
+use uuid::Uuid;
+
 #[derive(Debug)]
 pub struct MfaRegistrationInput {
     pub access_token: String,
@@ -9,3 +11,24 @@ pub struct MfaRegistrationOutput {
     pub allowed_methods: Vec<String>,
     pub expires_in: usize,
 }
+
+/// Re-authentication for an `mfa:manage` access token: the password or a current TOTP code.
+#[derive(Debug)]
+pub struct MfaStepUpInput {
+    pub access_token: String,
+    pub password: Option<String>,
+    pub totp_code: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct MfaStepUpOutput {
+    pub access_token: String,
+    pub expires_in: usize,
+}
+
+/// Removes one TOTP credential, or every one when `credential_id` is `None`.
+#[derive(Debug)]
+pub struct MfaRemoveTotpInput {
+    pub access_token: String,
+    pub credential_id: Option<Uuid>,
+}
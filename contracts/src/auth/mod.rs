@@ -1,9 +1,13 @@
 mod component;
 pub mod error;
 pub mod login;
+pub mod logout;
 pub mod mfa;
 pub mod passkey;
+pub mod recovery;
 pub mod signup;
+pub mod throttle;
+pub mod token;
 pub mod totp;
 
 pub use component::Component;
@@ -15,12 +15,18 @@ pub struct PasskeyStartRegistrationOutput {
 pub struct PasskeyFinishRegistrationInput {
     pub user_id: Uuid,
     pub register_public_key_credential: RegisterPublicKeyCredential,
+    pub name: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct PasskeyFinishRegistrationOutput {
+    /// Plain recovery codes, only set when this enrolment generated them.
+    pub recovery_codes: Vec<String>,
 }
 
 #[derive(Debug)]
 pub struct PasskeyStartAuthenticationInput {
     pub user_id: Uuid,
-    pub response: String,
 }
 
 #[derive(Debug)]
@@ -33,3 +39,49 @@ pub struct PasskeyFinishAuthenticationInput {
     pub user_id: Uuid,
     pub public_key_credential: PublicKeyCredential,
 }
+
+#[derive(Debug)]
+pub struct PasskeyFinishAuthenticationOutput {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Debug)]
+pub struct PasskeyStartLoginOutput {
+    /// Identifies this login attempt; must be sent back with the credential.
+    pub login_id: Uuid,
+    pub challenge: String,
+}
+
+#[derive(Debug)]
+pub struct PasskeyFinishLoginInput {
+    pub login_id: Uuid,
+    pub public_key_credential: PublicKeyCredential,
+}
+
+#[derive(Debug)]
+pub struct PasskeyListInput {
+    pub access_token: String,
+}
+
+#[derive(Debug)]
+pub struct PasskeyOutput {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+    pub aaguid: Option<Uuid>,
+}
+
+#[derive(Debug)]
+pub struct PasskeyRenameInput {
+    pub access_token: String,
+    pub passkey_id: Uuid,
+    pub name: String,
+}
+
+#[derive(Debug)]
+pub struct PasskeyRemoveInput {
+    pub access_token: String,
+    pub passkey_id: Uuid,
+}
@@ -0,0 +1,22 @@
+#[derive(Debug)]
+pub struct RecoveryVerifyLoginInput {
+    pub code: String,
+    pub mfa_token: String,
+    pub client_ip: Option<std::net::IpAddr>,
+}
+
+#[derive(Debug)]
+pub struct RecoveryVerifyLoginOutput {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Debug)]
+pub struct RecoveryRegenerateCodesInput {
+    pub access_token: String,
+}
+
+#[derive(Debug)]
+pub struct RecoveryCodesOutput {
+    pub recovery_codes: Vec<String>,
+}
@@ -0,0 +1,8 @@
+use std::net::IpAddr;
+
+/// Lifts the lockout and backoff of a username (including its second factors) and/or an address.
+#[derive(Debug)]
+pub struct UnlockAttemptsInput {
+    pub username: Option<String>,
+    pub client_ip: Option<IpAddr>,
+}
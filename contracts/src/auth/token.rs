@@ -0,0 +1,33 @@
+#[derive(Debug)]
+pub struct RefreshTokenInput {
+    pub refresh_token: String,
+}
+
+#[derive(Debug)]
+pub struct RefreshTokenOutput {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Debug)]
+pub struct ValidateTokenInput {
+    pub token: String,
+    pub token_type: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidateTokenOutput {
+    pub sub: String,
+    pub token_type: String,
+    pub scope: Vec<String>,
+    pub jti: String,
+    pub exp: usize,
+}
+
+#[derive(Debug)]
+pub struct RotateSigningKeysOutput {
+    pub active_kid: String,
+    pub next_kid: Option<String>,
+    pub retired_kids: Vec<String>,
+    pub removed_kids: Vec<String>,
+}
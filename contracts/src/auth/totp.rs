@@ -1,3 +1,5 @@
+use uuid::Uuid;
+
 #[derive(Debug)]
 pub struct TOTPStartRegistrationInput {
     pub mfa_token: String,
@@ -12,10 +14,46 @@ pub struct TOTPStartRegistrationOutput {
 pub struct TOTPFinishRegistrationInput {
     pub code: String,
     pub mfa_token: String,
+    pub label: Option<String>,
 }
 
 #[derive(Debug)]
 pub struct TOTPFinishRegistrationOutput {
     pub access_token: String,
     pub refresh_token: String,
+    /// Plain recovery codes, only set when this enrolment generated them.
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct TOTPVerifyLoginInput {
+    pub code: String,
+    pub mfa_token: String,
+    pub client_ip: Option<std::net::IpAddr>,
+}
+
+#[derive(Debug)]
+pub struct TOTPVerifyLoginOutput {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Debug)]
+pub struct TOTPListCredentialsInput {
+    pub access_token: String,
+}
+
+#[derive(Debug)]
+pub struct TOTPCredentialOutput {
+    pub id: Uuid,
+    pub label: String,
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct TOTPRenameCredentialInput {
+    pub access_token: String,
+    pub credential_id: Uuid,
+    pub label: String,
 }
@@ -11,6 +11,7 @@ use crate::pages::login::LoginPage;
 use crate::pages::not_found::NotFoundPage;
 use crate::pages::signup::SignupPage;
 use crate::pages::totp::TotpPage;
+use crate::pages::totp_verify::TotpVerifyPage;
 use crate::pages::webauthn_register::WebAuthnRegisterPage;
 
 #[derive(Clone, Routable, PartialEq)]
@@ -19,6 +20,8 @@ pub enum Route {
     Home,
     #[at("/login")]
     Login,
+    #[at("/login/totp")]
+    TotpVerify,
     #[at("/signup")]
     Signup,
     #[at("/totp")]
@@ -37,6 +40,11 @@ fn switch(routes: Route) -> Html {
                 <LoginPage />
             </AuthLayout>
         },
+        Route::TotpVerify => html! {
+            <AuthLayout>
+                <TotpVerifyPage />
+            </AuthLayout>
+        },
         Route::Signup => html! {
             <AuthLayout>
                 <SignupPage />
@@ -1,8 +1,21 @@
 use crate::components::ui::darkmode_toggle::DarkmodeToggle;
+use crate::context::user::UserContext;
 use yew::prelude::*;
 
 #[component]
 pub fn HeaderLayout() -> Html {
+    let user_ctx = use_context::<UserContext>().expect("no user ctx found");
+    let is_authenticated = user_ctx
+        .state
+        .user
+        .as_ref()
+        .is_some_and(|user| user.access_token.is_some());
+
+    let on_logout = {
+        let user_ctx = user_ctx.clone();
+        Callback::from(move |_: MouseEvent| user_ctx.logout())
+    };
+
     html! {
         <header class="app-header">
             <div class="header-content">
@@ -12,6 +25,9 @@ pub fn HeaderLayout() -> Html {
                 </div>
                 <nav class="header-nav">
                     // Futuros links de navegação aqui
+                    if is_authenticated {
+                        <button class="logout-button" onclick={on_logout}>{"Sair"}</button>
+                    }
                     <DarkmodeToggle />
                 </nav>
             </div>
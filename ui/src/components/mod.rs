@@ -2,4 +2,5 @@ pub mod layout;
 pub mod ui;
 
 pub mod auth_card;
+pub mod passkey_settings;
 pub mod protected_route;
@@ -0,0 +1,85 @@
+use api_types::webauthn::PasskeyResponse;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::js_sys::Date;
+use yew::prelude::*;
+
+use crate::components::ui::server_error::ServerError;
+use crate::context::user::UserContext;
+use crate::services::auth::list_passkeys;
+
+fn format_date(timestamp: u64) -> String {
+    Date::new(&JsValue::from_f64(timestamp as f64 * 1000.0))
+        .to_locale_date_string("pt-BR", &JsValue::UNDEFINED)
+        .into()
+}
+
+/// Seção de configurações com as passkeys cadastradas no usuário.
+#[component]
+pub fn PasskeySettings() -> Html {
+    let user_ctx = use_context::<UserContext>().expect("no user ctx found");
+    let passkeys = use_state(|| Option::<Vec<PasskeyResponse>>::None);
+    let error_msg = use_state(String::new);
+
+    let access_token = user_ctx
+        .state
+        .user
+        .as_ref()
+        .and_then(|user| user.access_token.clone());
+
+    {
+        let passkeys = passkeys.clone();
+        let error_msg = error_msg.clone();
+
+        use_effect_with(access_token, move |access_token| {
+            if let Some(access_token) = access_token.clone() {
+                spawn_local(async move {
+                    match list_passkeys(&access_token).await {
+                        Ok(list) => passkeys.set(Some(list)),
+                        Err(e) => error_msg.set(e),
+                    }
+                });
+            }
+            || ()
+        });
+    }
+
+    html! {
+        <section class="passkey-settings">
+            <h2>{"Passkeys"}</h2>
+            <ServerError message={(*error_msg).clone()} />
+            {
+                match &*passkeys {
+                    None if error_msg.is_empty() => html! { <p class="passkey-settings__empty">{"Carregando..."}</p> },
+                    None => html! {},
+                    Some(list) if list.is_empty() => html! {
+                        <p class="passkey-settings__empty">{"Nenhuma passkey cadastrada."}</p>
+                    },
+                    Some(list) => html! {
+                        <ul class="passkey-settings__list">
+                            { for list.iter().map(|passkey| html! {
+                                <li key={passkey.id.clone()} class="passkey-settings__item">
+                                    <span class="passkey-settings__name">{ &passkey.name }</span>
+                                    <span class="passkey-settings__meta">
+                                        { format!("Criada em {}", format_date(passkey.created_at)) }
+                                    </span>
+                                    <span class="passkey-settings__meta">
+                                        {
+                                            match passkey.last_used_at {
+                                                Some(ts) => format!("Último uso em {}", format_date(ts)),
+                                                None => "Nunca usada".to_string(),
+                                            }
+                                        }
+                                    </span>
+                                    if let Some(aaguid) = &passkey.aaguid {
+                                        <span class="passkey-settings__meta">{ format!("AAGUID {}", aaguid) }</span>
+                                    }
+                                </li>
+                            }) }
+                        </ul>
+                    },
+                }
+            }
+        </section>
+    }
+}
@@ -20,8 +20,15 @@ pub fn ProtectedRoute(props: &Props) -> Html {
     let navigator = use_navigator().expect("Navigator not found");
     let location = use_location().expect("Location not found");
 
+    // Um usuário só com token de MFA ainda não concluiu o login
+    let is_authenticated = user_ctx
+        .state
+        .user
+        .as_ref()
+        .is_some_and(|user| user.access_token.is_some());
+
     use_effect_with(
-        (user_ctx.state.user.is_some(), location.path().to_string()),
+        (is_authenticated, location.path().to_string()),
         move |(is_auth, path)| {
             if !is_auth {
                 let query = ReturnToQuery {
@@ -33,7 +40,7 @@ pub fn ProtectedRoute(props: &Props) -> Html {
         },
     );
 
-    if user_ctx.state.user.is_some() {
+    if is_authenticated {
         html! {
             { props.children.clone() }
         }
@@ -21,7 +21,7 @@ pub fn Button(props: &ButtonProps) -> Html {
             disabled={props.is_loading}
             class={classes!(
                 "ui-button",
-                props.is_loading.then(|| "btn-loading"),
+                props.is_loading.then_some("btn-loading"),
                 props.class.clone()
             )}
         >
@@ -37,7 +37,7 @@ pub fn InputField(props: &InputFieldProps) -> Html {
                 value={props.field.value.clone()}
                 oninput={oninput}
                 placeholder={props.placeholder}
-                class={classes!("form-input", has_error.then(|| "input-error"))}
+                class={classes!("form-input", has_error.then_some("input-error"))}
             />
             if let Some(msg) = &props.field.error {
                 <span class="error-message">{msg}</span>
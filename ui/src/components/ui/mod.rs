@@ -3,6 +3,7 @@ pub mod button;
 pub mod darkmode_toggle;
 pub mod input_field;
 pub mod loading_spinner;
+pub mod recovery_codes;
 pub mod server_error;
 pub mod totp_manual_key;
 pub mod totp_step;
@@ -0,0 +1,26 @@
+use yew::prelude::*;
+
+use crate::components::ui::button::Button;
+
+#[derive(Properties, PartialEq)]
+pub struct RecoveryCodesProps {
+    pub codes: Vec<String>,
+    /// Called once the user confirms the codes were saved; they are not shown again.
+    pub on_continue: Callback<MouseEvent>,
+}
+
+#[component]
+pub fn RecoveryCodes(props: &RecoveryCodesProps) -> Html {
+    html! {
+        <div class="recovery-codes">
+            <p class="recovery-codes-warning">
+                {"Guarde estes códigos de recuperação em um lugar seguro. Cada um permite entrar uma \
+                  vez sem o segundo fator, e eles não serão exibidos novamente."}
+            </p>
+            <ul>
+                { for props.codes.iter().map(|code| html! { <li><code>{ code }</code></li> }) }
+            </ul>
+            <Button label="Já guardei os códigos" onclick={props.on_continue.clone()} />
+        </div>
+    }
+}
@@ -7,33 +7,31 @@ pub struct ThemeState {
 }
 
 fn detect_system_preference() -> bool {
-    if let Some(window) = web_sys::window() {
-        if let Some(storage) = window.local_storage().ok().flatten() {
-            if let Ok(Some(saved)) = storage.get_item("theme-preference") {
-                return saved == "dark";
-            }
-        }
+    if let Some(window) = web_sys::window()
+        && let Some(storage) = window.local_storage().ok().flatten()
+        && let Ok(Some(saved)) = storage.get_item("theme-preference")
+    {
+        return saved == "dark";
     }
     false
 }
 
 fn set_theme(is_dark: bool) {
-    if let Some(window) = web_sys::window() {
-        if let Some(document) = window.document() {
-            if let Some(root) = document.document_element() {
-                if is_dark {
-                    let _ = root.set_attribute("data-theme", "dark");
-                } else {
-                    let _ = root.remove_attribute("data-theme");
-                }
-            }
+    if let Some(window) = web_sys::window()
+        && let Some(document) = window.document()
+        && let Some(root) = document.document_element()
+    {
+        if is_dark {
+            let _ = root.set_attribute("data-theme", "dark");
+        } else {
+            let _ = root.remove_attribute("data-theme");
         }
     }
 }
 
 #[component]
 pub fn ThemeProvider(props: &ChildrenProps) -> Html {
-    let is_dark = use_state(|| detect_system_preference());
+    let is_dark = use_state(detect_system_preference);
 
     set_theme(*is_dark);
 
@@ -1,10 +1,14 @@
 use std::rc::Rc;
 
+use api_types::logout::LogoutRequest;
 use log::error;
 use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::spawn_local;
 use web_sys::window;
 use yew::{html::ChildrenProps, prelude::*};
 
+use crate::services::auth;
+
 const USER_KEY: &str = "user-context";
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
@@ -27,6 +31,28 @@ pub struct UserContext {
     pub state: UseReducerHandle<UserState>,
 }
 
+impl UserContext {
+    /// Revokes the session on the server and forgets the user locally, even if the call fails.
+    pub fn logout(&self) {
+        let state = self.state.clone();
+        let Some(user) = state.user.clone() else {
+            return;
+        };
+
+        spawn_local(async move {
+            if let Some(access_token) = user.access_token {
+                let req = LogoutRequest {
+                    refresh_token: user.refresh_token,
+                };
+                if let Err(e) = auth::logout(&access_token, req).await {
+                    error!("Logout failed: {}", e);
+                }
+            }
+            state.dispatch(UserAction::Clear);
+        });
+    }
+}
+
 pub enum UserAction {
     Set(User),
     Clear,
@@ -1,8 +1,13 @@
 use yew::prelude::*;
 
+use crate::components::passkey_settings::PasskeySettings;
+
 #[component]
 pub fn HomePage() -> Html {
     html! {
-        <h1>{ "Welcome to the Home Page" }</h1>
+        <>
+            <h1>{ "Welcome to the Home Page" }</h1>
+            <PasskeySettings />
+        </>
     }
 }
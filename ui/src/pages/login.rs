@@ -1,14 +1,18 @@
 use std::collections::HashMap;
 
-use api_types::login::LoginRequest;
+use api_types::{
+    login::LoginRequest,
+    webauthn::{PasskeyLoginFinishRequest, WebauthnAuthenticationFinishResponse},
+};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
-use wasm_bindgen_futures::spawn_local;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::{JsFuture, spawn_local};
+use web_sys::{CredentialRequestOptions, PublicKeyCredential, window};
 use yew::prelude::*;
 use yew_router::{
     Routable,
     hooks::{use_location, use_navigator},
-    prelude::Link,
 };
 
 use crate::{
@@ -23,8 +27,11 @@ use crate::{
         },
     },
     context::user::{User, UserAction, UserContext},
-    services::auth::login,
-    utils::validator::{get_validation_errors, sync_field_error},
+    services::auth::{login, passkey_login_finish, passkey_login_start},
+    utils::{
+        validator::{get_validation_errors, sync_field_error},
+        webauthn::{assertion_to_json, js_value_to_string, prepare_request_options},
+    },
 };
 
 #[derive(Deserialize, Serialize)]
@@ -32,13 +39,45 @@ struct LoginQuery {
     return_to: Option<String>,
 }
 
+fn return_route(query: LoginQuery) -> Route {
+    query
+        .return_to
+        .and_then(|path| <Route as Routable>::recognize(&path))
+        .unwrap_or(Route::Home)
+}
+
+/// Pede uma passkey descoberta pelo navegador, sem email nem senha.
+async fn sign_in_with_passkey() -> Result<WebauthnAuthenticationFinishResponse, String> {
+    let start = passkey_login_start().await?;
+
+    let options = CredentialRequestOptions::new();
+    options.set_public_key(&prepare_request_options(&start.options)?);
+    let promise = window()
+        .ok_or("Navegador indisponível")?
+        .navigator()
+        .credentials()
+        .get_with_options(&options)
+        .map_err(js_value_to_string)?;
+    let cred = JsFuture::from(promise)
+        .await
+        .map_err(js_value_to_string)?
+        .dyn_into::<PublicKeyCredential>()
+        .map_err(|_| "Credencial inválida".to_string())?;
+
+    passkey_login_finish(PasskeyLoginFinishRequest {
+        login_id: start.login_id,
+        credential: assertion_to_json(&cred),
+    })
+    .await
+}
+
 #[component]
 pub fn LoginPage() -> Html {
     let navigator = use_navigator().expect("Navigator not found");
     let location = use_location().expect("Location not found");
     let user_ctx = use_context::<UserContext>().expect("no user ctx found");
     let is_loading = use_state(|| false);
-    let server_error = use_state(|| String::new());
+    let server_error = use_state(String::new);
 
     let email = use_state(Field::default);
     let password = use_state(Field::default);
@@ -59,8 +98,8 @@ pub fn LoginPage() -> Html {
             server_error.set("".to_string());
 
             let req = LoginRequest {
-                email: (*email).value.clone(),
-                password: (*password).value.clone(),
+                email: email.value.clone(),
+                password: password.value.clone(),
             };
 
             let error_map = match req.validate() {
@@ -89,7 +128,7 @@ pub fn LoginPage() -> Html {
                         let user = User {
                             name: "test".to_string(),
                             email: "email".to_string(),
-                            mfa_registration_token: resp.mfa_registration_token,
+                            mfa_registration_token: None,
                             mfa_verification_token: resp.mfa_verification_token,
                             access_token: resp.access_token,
                             refresh_token: resp.refresh_token,
@@ -100,17 +139,11 @@ pub fn LoginPage() -> Html {
                             .query::<LoginQuery>()
                             .unwrap_or(LoginQuery { return_to: None });
 
-                        match (user.mfa_registration_token, user.access_token) {
+                        match (user.mfa_verification_token, user.access_token) {
                             (Some(_), _) => {
-                                let _ = navigator.push_with_query(&Route::Totp, &query);
+                                let _ = navigator.push_with_query(&Route::TotpVerify, &query);
                             }
-                            (None, Some(_)) => match query.return_to {
-                                Some(path) => match <Route as Routable>::recognize(&path) {
-                                    Some(route) => navigator.push(&route),
-                                    None => navigator.push(&Route::Home),
-                                },
-                                None => navigator.push(&Route::Home),
-                            },
+                            (None, Some(_)) => navigator.push(&return_route(query)),
                             _ => server_error.set("Resposta do servidor inválida".to_string()),
                         }
                     }
@@ -123,12 +156,65 @@ pub fn LoginPage() -> Html {
         }
     };
 
+    let handle_passkey_login = {
+        let navigator = navigator.clone();
+        let location = location.clone();
+        let user_ctx = user_ctx.clone();
+        let is_loading = is_loading.clone();
+        let server_error = server_error.clone();
+
+        move |_: MouseEvent| {
+            if *is_loading {
+                return;
+            }
+            server_error.set("".to_string());
+            is_loading.set(true);
+
+            let is_loading = is_loading.clone();
+            let server_error = server_error.clone();
+            let navigator = navigator.clone();
+            let location = location.clone();
+            let user_ctx = user_ctx.clone();
+
+            spawn_local(async move {
+                match sign_in_with_passkey().await {
+                    Ok(resp) => {
+                        let user = User {
+                            name: "test".to_string(),
+                            email: "email".to_string(),
+                            mfa_registration_token: None,
+                            mfa_verification_token: None,
+                            access_token: Some(resp.access_token),
+                            refresh_token: Some(resp.refresh_token),
+                        };
+                        user_ctx.state.dispatch(UserAction::Set(user));
+
+                        let query = location
+                            .query::<LoginQuery>()
+                            .unwrap_or(LoginQuery { return_to: None });
+                        navigator.push(&return_route(query));
+                    }
+                    Err(err_msg) => {
+                        server_error.set(err_msg);
+                    }
+                }
+                is_loading.set(false);
+            });
+        }
+    };
+
     html! {
         <AuthCard title="Login">
             <InputField label="Email:" field={email} input_type="email" placeholder="email@exemplo.com" />
             <InputField label="Senha:" field={password} input_type="password" placeholder="Sua senha" />
 
             <Button label="Entrar" onclick={handle_login} is_loading={*is_loading} />
+            <Button
+                label="Entrar com passkey"
+                onclick={handle_passkey_login}
+                is_loading={*is_loading}
+                class="btn-secondary"
+            />
 
             <ServerError message={(*server_error).clone()} />
 
@@ -3,4 +3,5 @@ pub mod login;
 pub mod not_found;
 pub mod signup;
 pub mod totp;
+pub mod totp_verify;
 pub mod webauthn_register;
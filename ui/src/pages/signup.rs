@@ -16,12 +16,12 @@ use crate::{
     services::auth::signup,
     utils::validator::{get_validation_errors, sync_field_error},
 };
-use api_types::signup::SignupRequest;
+use api_types::{error::ErrorResponse, signup::SignupRequest};
 
 #[component]
 pub fn SignupPage() -> Html {
     let is_loading = use_state(|| false);
-    let server_error = use_state(|| String::new());
+    let server_error = use_state(String::new);
 
     let name = use_state(Field::default);
     let email = use_state(Field::default);
@@ -43,9 +43,9 @@ pub fn SignupPage() -> Html {
             server_error.set("".to_string());
 
             let req = SignupRequest {
-                name: (*name).value.clone(),
-                email: (*email).value.clone(),
-                password: (*password).value.clone(),
+                name: name.value.clone(),
+                email: email.value.clone(),
+                password: password.value.clone(),
             };
 
             let mut error_map = match req.validate() {
@@ -53,7 +53,7 @@ pub fn SignupPage() -> Html {
                 Err(errs) => get_validation_errors(errs),
             };
 
-            if (*password).value != (*confirm).value {
+            if password.value != confirm.value {
                 error_map.insert("confirm".to_string(), "As senhas não conferem".to_string());
             }
 
@@ -73,9 +73,17 @@ pub fn SignupPage() -> Html {
             spawn_local(async move {
                 match signup(req).await {
                     Ok(res) if res.status().is_success() => {
-                        server_error.set(format!("Conta criada com sucesso"));
+                        server_error.set("Conta criada com sucesso".to_string());
+                    }
+                    Ok(res) => {
+                        let status = res.status();
+                        let message = res
+                            .json::<ErrorResponse>()
+                            .await
+                            .map(|e| e.message)
+                            .unwrap_or_else(|_| format!("Erro no servidor: {}", status));
+                        server_error.set(message);
                     }
-                    Ok(res) => server_error.set(format!("Erro no servidor: {}", res.status())),
                     Err(e) => server_error.set(format!("Erro de conexão: {}", e)),
                 }
                 is_loading.set(false);
@@ -2,7 +2,10 @@ use crate::{
     app::Route,
     components::{
         auth_card::AuthCard,
-        ui::{loading_spinner::LoadingSpinner, server_error::ServerError, totp_manual_key::TotpManualKey, totp_step::TotpStep},
+        ui::{
+            loading_spinner::LoadingSpinner, recovery_codes::RecoveryCodes,
+            server_error::ServerError, totp_manual_key::TotpManualKey, totp_step::TotpStep,
+        },
     },
     context::user::{User, UserAction, UserContext},
     services::auth::{totp_registration_finish, totp_registration_start},
@@ -22,7 +25,11 @@ use yew_router::{
 enum TotpState {
     Loading,
     Error(String),
-    Ready { svg_content: String, secret: String },
+    Ready {
+        svg_content: String,
+        secret: String,
+        digits: usize,
+    },
 }
 
 #[derive(Deserialize, Serialize)]
@@ -36,9 +43,11 @@ pub fn TotpPage() -> Html {
     let user_context = use_context::<UserContext>().expect("no user ctx found");
     let location = use_location().expect("Location not found");
     let totp_state = use_state(|| TotpState::Loading);
-    let otp_code = use_state(|| String::new());
+    let otp_code = use_state(String::new);
     let is_submitting = use_state(|| false);
     let error_msg = use_state(|| Option::<String>::None);
+    // Códigos gerados pelo registro, exibidos uma única vez antes de seguir para `Route`.
+    let recovery_codes = use_state(|| Option::<(Vec<String>, Route)>::None);
     let svg_ref = use_node_ref();
 
     let mfa_token = user_context
@@ -58,14 +67,18 @@ pub fn TotpPage() -> Html {
                 let new_state = match totp_registration_start(&mfa_token).await {
                     Ok(res) => {
                         // Usando a lib 'url' para extrair o segredo de forma segura
-                        let secret = Url::parse(&res.qr_code_url)
-                            .ok()
-                            .and_then(|u| {
+                        let url = Url::parse(&res.qr_code_url).ok();
+                        let param = |name: &str| {
+                            url.as_ref().and_then(|u| {
                                 u.query_pairs()
-                                    .find(|(key, _)| key == "secret")
+                                    .find(|(key, _)| key == name)
                                     .map(|(_, val)| val.into_owned())
                             })
-                            .unwrap_or_else(|| "N/A".to_string());
+                        };
+                        let secret = param("secret").unwrap_or_else(|| "N/A".to_string());
+                        let digits = param("digits")
+                            .and_then(|digits| digits.parse().ok())
+                            .unwrap_or(6);
 
                         match qrcode::QrCode::new(res.qr_code_url.as_bytes()) {
                             Ok(code) => {
@@ -78,6 +91,7 @@ pub fn TotpPage() -> Html {
                                 TotpState::Ready {
                                     svg_content,
                                     secret,
+                                    digits,
                                 }
                             }
                             Err(e) => TotpState::Error(format!("Erro ao gerar QR Code: {}", e)),
@@ -96,16 +110,22 @@ pub fn TotpPage() -> Html {
         let totp_state_val = (*totp_state).clone();
         let svg_ref = svg_ref.clone();
         use_effect_with(totp_state_val, move |state| {
-            if let TotpState::Ready { svg_content, .. } = state {
-                if let Some(element) = svg_ref.cast::<Element>() {
-                    element.set_inner_html(svg_content);
-                }
+            if let TotpState::Ready { svg_content, .. } = state
+                && let Some(element) = svg_ref.cast::<Element>()
+            {
+                element.set_inner_html(svg_content);
             }
             || ()
         });
     }
 
     // --- HANDLERS ---
+    // Quantidade de dígitos informada na URL otpauth do registro.
+    let digits = match &*totp_state {
+        TotpState::Ready { digits, .. } => *digits,
+        _ => 6,
+    };
+
     let on_input = {
         let otp_code = otp_code.clone();
         let error_msg = error_msg.clone();
@@ -114,8 +134,8 @@ pub fn TotpPage() -> Html {
             let val = input
                 .value()
                 .chars()
-                .filter(|c| c.is_digit(10))
-                .take(6)
+                .filter(|c| c.is_ascii_digit())
+                .take(digits)
                 .collect::<String>();
             otp_code.set(val);
             error_msg.set(None); // Limpa erro ao digitar
@@ -130,6 +150,7 @@ pub fn TotpPage() -> Html {
         let otp_code = (*otp_code).clone();
         let mfa_token = mfa_token.clone();
         let error_msg = error_msg.clone();
+        let recovery_codes = recovery_codes.clone();
 
         Callback::from(move |_| {
             let navigator = navigator.clone();
@@ -139,10 +160,13 @@ pub fn TotpPage() -> Html {
             let mfa_token = mfa_token.clone();
             let code = otp_code.clone();
             let error_msg = error_msg.clone();
+            let recovery_codes = recovery_codes.clone();
 
             spawn_local(async move {
                 is_submitting.set(true);
-                match totp_registration_finish(&mfa_token, TotpVerifyRequest { code }).await {
+                match totp_registration_finish(&mfa_token, TotpVerifyRequest { code, label: None })
+                    .await
+                {
                     Ok(resp) => {
                         let user = User {
                             name: "test".to_string(),
@@ -159,13 +183,17 @@ pub fn TotpPage() -> Html {
                             .unwrap_or(LoginQuery { return_to: None });
 
                         match (user.refresh_token, user.access_token) {
-                            (Some(_), Some(_)) => match query.return_to {
-                                Some(path) => match <Route as Routable>::recognize(&path) {
-                                    Some(route) => navigator.push(&route),
-                                    None => navigator.push(&Route::Home),
-                                },
-                                None => navigator.push(&Route::Home),
-                            },
+                            (Some(_), Some(_)) => {
+                                let route = query
+                                    .return_to
+                                    .and_then(|path| <Route as Routable>::recognize(&path))
+                                    .unwrap_or(Route::Home);
+                                if resp.recovery_codes.is_empty() {
+                                    navigator.push(&route);
+                                } else {
+                                    recovery_codes.set(Some((resp.recovery_codes, route)));
+                                }
+                            }
                             _ => error_msg.set(Some("Resposta do servidor inválida".to_string())),
                         }
                     }
@@ -178,6 +206,19 @@ pub fn TotpPage() -> Html {
         })
     };
 
+    if let Some((codes, route)) = &*recovery_codes {
+        let on_continue = {
+            let navigator = navigator.clone();
+            let route = route.clone();
+            Callback::from(move |_| navigator.push(&route))
+        };
+        return html! {
+            <AuthCard title="Códigos de Recuperação">
+                <RecoveryCodes codes={codes.clone()} {on_continue} />
+            </AuthCard>
+        };
+    }
+
     html! {
         <AuthCard title="Segurança da Conta">
             <div class="totp-setup-wrapper">
@@ -185,7 +226,7 @@ pub fn TotpPage() -> Html {
                     match &*totp_state {
                         TotpState::Loading => html! { <LoadingSpinner /> },
                         TotpState::Error(msg) => html! { <ServerError message={msg.clone()} /> },
-                        TotpState::Ready { secret, .. } => html! {
+                        TotpState::Ready { secret, digits, .. } => html! {
                             <>
                                 <div class="step-section">
                                     <TotpStep
@@ -205,14 +246,14 @@ pub fn TotpPage() -> Html {
                                     <TotpStep
                                         number="2."
                                         title="Confirmar Registro"
-                                        description="Digite o código de 6 dígitos gerado pelo app."
+                                        description={format!("Digite o código de {} dígitos gerado pelo app.", digits)}
                                     />
                                     <div class="input-group">
                                         <input
                                             type="text"
                                             inputmode="numeric"
-                                            placeholder="000000"
-                                            maxlength="6"
+                                            placeholder={"0".repeat(*digits)}
+                                            maxlength={digits.to_string()}
                                             value={(*otp_code).clone()}
                                             oninput={on_input}
                                             disabled={*is_submitting}
@@ -220,7 +261,7 @@ pub fn TotpPage() -> Html {
                                         <button
                                             class="btn-confirm"
                                             onclick={on_submit}
-                                            disabled={otp_code.len() != 6 || *is_submitting}
+                                            disabled={otp_code.len() != *digits || *is_submitting}
                                         >
                                             { if *is_submitting { "Validando..." } else { "Ativar" } }
                                         </button>
@@ -0,0 +1,155 @@
+use crate::{
+    app::Route,
+    components::{auth_card::AuthCard, ui::server_error::ServerError},
+    context::user::{User, UserAction, UserContext},
+    services::auth::totp_verify,
+};
+use api_types::totp::TotpLoginRequest;
+use serde::{Deserialize, Serialize};
+use web_sys::HtmlInputElement;
+use yew::{platform::spawn_local, prelude::*};
+use yew_router::{
+    Routable,
+    hooks::{use_location, use_navigator},
+};
+
+/// Os autenticadores podem usar de 6 a 8 dígitos, conforme o registro.
+const CODE_DIGITS: std::ops::RangeInclusive<usize> = 6..=8;
+const MAX_CODE_DIGITS: usize = *CODE_DIGITS.end();
+
+#[derive(Deserialize, Serialize)]
+struct LoginQuery {
+    return_to: Option<String>,
+}
+
+#[component]
+pub fn TotpVerifyPage() -> Html {
+    let navigator = use_navigator().expect("Navigator not found");
+    let user_context = use_context::<UserContext>().expect("no user ctx found");
+    let location = use_location().expect("Location not found");
+    let otp_code = use_state(String::new);
+    let is_submitting = use_state(|| false);
+    let error_msg = use_state(String::new);
+
+    let mfa_token = user_context
+        .state
+        .user
+        .as_ref()
+        .and_then(|user| user.mfa_verification_token.clone());
+
+    // Sem token de verificação não há o que confirmar: volta para o login
+    {
+        let navigator = navigator.clone();
+        let has_token = mfa_token.is_some();
+        use_effect_with(has_token, move |has_token| {
+            if !has_token {
+                navigator.push(&Route::Login);
+            }
+            || ()
+        });
+    }
+
+    let on_input = {
+        let otp_code = otp_code.clone();
+        let error_msg = error_msg.clone();
+        Callback::from(move |e: InputEvent| {
+            let input: HtmlInputElement = e.target_unchecked_into();
+            let val = input
+                .value()
+                .chars()
+                .filter(|c| c.is_ascii_digit())
+                .take(MAX_CODE_DIGITS)
+                .collect::<String>();
+            otp_code.set(val);
+            error_msg.set(String::new());
+        })
+    };
+
+    let on_submit = {
+        let navigator = navigator.clone();
+        let location = location.clone();
+        let user_context = user_context.clone();
+        let is_submitting = is_submitting.clone();
+        let otp_code = (*otp_code).clone();
+        let mfa_token = mfa_token.clone().unwrap_or_default();
+        let error_msg = error_msg.clone();
+
+        Callback::from(move |_| {
+            let navigator = navigator.clone();
+            let location = location.clone();
+            let user_context = user_context.clone();
+            let is_submitting = is_submitting.clone();
+            let req = TotpLoginRequest {
+                mfa_verification: mfa_token.clone(),
+                code: otp_code.clone(),
+            };
+            let error_msg = error_msg.clone();
+
+            spawn_local(async move {
+                is_submitting.set(true);
+                match totp_verify(req).await {
+                    Ok(resp) => {
+                        let previous = user_context.state.user.clone();
+                        let user = User {
+                            name: previous
+                                .as_ref()
+                                .map(|u| u.name.clone())
+                                .unwrap_or_default(),
+                            email: previous.map(|u| u.email).unwrap_or_default(),
+                            mfa_registration_token: None,
+                            mfa_verification_token: None,
+                            access_token: Some(resp.access_token),
+                            refresh_token: Some(resp.refresh_token),
+                        };
+                        user_context.state.dispatch(UserAction::Set(user));
+
+                        let query = location
+                            .query::<LoginQuery>()
+                            .unwrap_or(LoginQuery { return_to: None });
+
+                        match query.return_to {
+                            Some(path) => match <Route as Routable>::recognize(&path) {
+                                Some(route) => navigator.push(&route),
+                                None => navigator.push(&Route::Home),
+                            },
+                            None => navigator.push(&Route::Home),
+                        }
+                    }
+                    Err(e) => error_msg.set(e),
+                }
+                is_submitting.set(false);
+            });
+        })
+    };
+
+    html! {
+        <AuthCard title="Verificação em Duas Etapas">
+            <div class="totp-setup-wrapper">
+                <div class="verification-section">
+                    <p>{ "Digite o código gerado pelo seu app autenticador." }</p>
+                    <div class="input-group">
+                        <input
+                            type="text"
+                            inputmode="numeric"
+                            autocomplete="one-time-code"
+                            placeholder="000000"
+                            maxlength={MAX_CODE_DIGITS.to_string()}
+                            value={(*otp_code).clone()}
+                            oninput={on_input}
+                            disabled={*is_submitting}
+                        />
+                        <button
+                            class="btn-confirm"
+                            onclick={on_submit}
+                            disabled={!CODE_DIGITS.contains(&otp_code.len()) || *is_submitting}
+                        >
+                            { if *is_submitting { "Validando..." } else { "Verificar" } }
+                        </button>
+                    </div>
+
+                    <ServerError message={(*error_msg).clone()} />
+                </div>
+            </div>
+        </AuthCard>
+    }
+}
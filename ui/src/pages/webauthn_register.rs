@@ -1,13 +1,19 @@
 use crate::components::ui::button::Button;
+use crate::components::ui::recovery_codes::RecoveryCodes;
 use crate::components::ui::server_error::ServerError;
 use crate::context::user::UserContext;
-use crate::{components::auth_card::AuthCard, services::auth::webauthn_registration_start};
+use crate::{
+    components::auth_card::AuthCard,
+    services::auth::{webauthn_registration_finish, webauthn_registration_start},
+    utils::webauthn::{array_buffer_to_b64, js_value_to_string},
+};
+use api_types::webauthn::WebauthnRegistrationFinishRequest;
 use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
 use wasm_bindgen::{JsCast, JsValue};
 use wasm_bindgen_futures::spawn_local;
 use web_sys::js_sys::{Reflect, Uint8Array};
 use web_sys::{
-    CredentialCreationOptions, PublicKeyCredential, PublicKeyCredentialCreationOptions, js_sys,
+    AuthenticatorAttestationResponse, CredentialCreationOptions, PublicKeyCredential, js_sys,
     window,
 };
 use yew::prelude::*;
@@ -31,27 +37,26 @@ pub fn prepare_pk_options(
     };
 
     // 3. Corrigir o Challenge (de String para Uint8Array)
-    if let Ok(challenge_b64) = Reflect::get(&pk_options_js, &JsValue::from_str("challenge")) {
-        if let Some(s) = challenge_b64.as_string() {
-            let bytes = URL_SAFE_NO_PAD
-                .decode(s)
-                .map_err(|_| "Erro no challenge b64")?;
-            let array = Uint8Array::from(&bytes[..]);
-            Reflect::set(&pk_options_js, &JsValue::from_str("challenge"), &array).unwrap();
-        }
+    if let Ok(challenge_b64) = Reflect::get(&pk_options_js, &JsValue::from_str("challenge"))
+        && let Some(s) = challenge_b64.as_string()
+    {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| "Erro no challenge b64")?;
+        let array = Uint8Array::from(&bytes[..]);
+        Reflect::set(&pk_options_js, &JsValue::from_str("challenge"), &array).unwrap();
     }
 
     // 4. Corrigir o User ID (de String para Uint8Array)
-    if let Ok(user_obj) = Reflect::get(&pk_options_js, &JsValue::from_str("user")) {
-        if let Ok(id_b64) = Reflect::get(&user_obj, &JsValue::from_str("id")) {
-            if let Some(s) = id_b64.as_string() {
-                let bytes = URL_SAFE_NO_PAD
-                    .decode(s)
-                    .map_err(|_| "Erro no user id b64")?;
-                let array = Uint8Array::from(&bytes[..]);
-                Reflect::set(&user_obj, &JsValue::from_str("id"), &array).unwrap();
-            }
-        }
+    if let Ok(user_obj) = Reflect::get(&pk_options_js, &JsValue::from_str("user"))
+        && let Ok(id_b64) = Reflect::get(&user_obj, &JsValue::from_str("id"))
+        && let Some(s) = id_b64.as_string()
+    {
+        let bytes = URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| "Erro no user id b64")?;
+        let array = Uint8Array::from(&bytes[..]);
+        Reflect::set(&user_obj, &JsValue::from_str("id"), &array).unwrap();
     }
 
     // 5. Validar se pubKeyCredParams existe (o erro que você recebeu)
@@ -66,24 +71,31 @@ pub fn prepare_pk_options(
     Ok(pk_options_js.unchecked_into::<web_sys::PublicKeyCredentialCreationOptions>())
 }
 
-fn js_value_to_string(val: JsValue) -> String {
-    // Tenta pegar a propriedade "message" (comum em erros de JS/DOM)
-    if let Ok(msg) = Reflect::get(&val, &JsValue::from_str("message")) {
-        if let Some(s) = msg.as_string() {
-            return s;
-        }
-    }
-    // Caso contrário, tenta converter o objeto inteiro para string
-    val.as_string()
-        .unwrap_or_else(|| "Erro desconhecido na chave de segurança".to_string())
+/// Serializa a credencial no formato JSON esperado pelo `RegisterPublicKeyCredential` do servidor.
+fn credential_to_json(cred: &PublicKeyCredential) -> serde_json::Value {
+    let response = cred
+        .response()
+        .unchecked_into::<AuthenticatorAttestationResponse>();
+
+    serde_json::json!({
+        "id": cred.id(),
+        "rawId": array_buffer_to_b64(&cred.raw_id()),
+        "type": cred.type_(),
+        "response": {
+            "attestationObject": array_buffer_to_b64(&response.attestation_object()),
+            "clientDataJSON": array_buffer_to_b64(&response.client_data_json()),
+        },
+        "extensions": {},
+    })
 }
 
 #[component]
 pub fn WebAuthnRegisterPage() -> Html {
     let user_context = use_context::<UserContext>().expect("no user ctx found");
     let is_loading = use_state(|| false);
-    let status_msg = use_state(|| String::new());
-    let error_msg = use_state(|| String::new());
+    let status_msg = use_state(String::new);
+    let error_msg = use_state(String::new);
+    let recovery_codes = use_state(Vec::<String>::new);
 
     let mfa_token = user_context
         .state
@@ -97,12 +109,14 @@ pub fn WebAuthnRegisterPage() -> Html {
         let is_loading = is_loading.clone();
         let status_msg = status_msg.clone();
         let error_msg = error_msg.clone();
+        let recovery_codes = recovery_codes.clone();
 
         move |_| {
             let mfa_token = mfa_token.clone();
             let is_loading = is_loading.clone();
             let status_msg = status_msg.clone();
             let error_msg = error_msg.clone();
+            let recovery_codes = recovery_codes.clone();
 
             spawn_local(async move {
                 is_loading.set(true);
@@ -146,8 +160,16 @@ pub fn WebAuthnRegisterPage() -> Html {
                         // 3. POST /webauthn/register_finish
                         // Envia a resposta da chave de volta para validação
                         status_msg.set("Finalizando no servidor...".into());
-                        match call_register_finish(cred).await {
-                            Ok(_) => status_msg.set("Dispositivo registrado com sucesso!".into()),
+                        let req = WebauthnRegistrationFinishRequest {
+                            credential: credential_to_json(&cred),
+                            name: None,
+                        };
+                        match webauthn_registration_finish(&mfa_token, req).await {
+                            Ok(resp) => {
+                                status_msg.set("Dispositivo registrado com sucesso!".into());
+                                // Só o primeiro segundo fator gera códigos de recuperação.
+                                recovery_codes.set(resp.recovery_codes);
+                            }
                             Err(e) => error_msg.set(e),
                         }
                     }
@@ -166,6 +188,18 @@ pub fn WebAuthnRegisterPage() -> Html {
         }
     };
 
+    if !recovery_codes.is_empty() {
+        let on_continue = {
+            let recovery_codes = recovery_codes.clone();
+            Callback::from(move |_| recovery_codes.set(Vec::new()))
+        };
+        return html! {
+            <AuthCard title="Códigos de Recuperação">
+                <RecoveryCodes codes={(*recovery_codes).clone()} {on_continue} />
+            </AuthCard>
+        };
+    }
+
     html! {
         <AuthCard title="Segurança">
             <div class="webauthn-container">
@@ -188,14 +222,3 @@ pub fn WebAuthnRegisterPage() -> Html {
         </AuthCard>
     }
 }
-
-// Funções de mock para as chamadas de API (implementar com seu service de auth)
-async fn call_register_start() -> Result<PublicKeyCredentialCreationOptions, String> {
-    /* api_client.post("/webauthn/register_start").await */
-    unimplemented!()
-}
-
-async fn call_register_finish(_cred: PublicKeyCredential) -> Result<(), String> {
-    /* api_client.post("/webauthn/register_finish", _cred).await */
-    unimplemented!()
-}
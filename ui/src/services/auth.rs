@@ -1,8 +1,17 @@
 use api_types::{
     error::ErrorResponse,
     login::{LoginRequest, LoginResponse},
+    logout::LogoutRequest,
+    recovery::RecoveryCodesResponse,
     signup::SignupRequest,
-    totp::{TotpSetupResponse, TotpVerifyRequest, TotpVerifyResponse},
+    totp::{
+        TotpLoginRequest, TotpRegistrationFinishResponse, TotpSetupResponse, TotpVerifyRequest,
+        TotpVerifyResponse,
+    },
+    webauthn::{
+        PasskeyLoginFinishRequest, PasskeyLoginStartResponse, PasskeyResponse,
+        WebauthnAuthenticationFinishResponse, WebauthnRegistrationFinishRequest,
+    },
 };
 use reqwest::Client;
 
@@ -68,7 +77,7 @@ pub async fn totp_registration_start(auth_token: &str) -> Result<TotpSetupRespon
 pub async fn totp_registration_finish(
     auth_token: &str,
     req: TotpVerifyRequest,
-) -> Result<TotpVerifyResponse, String> {
+) -> Result<TotpRegistrationFinishResponse, String> {
     let client = Client::new();
     let response = client
         .post("http://localhost:8080/mfa/registration/totp/finish")
@@ -80,7 +89,7 @@ pub async fn totp_registration_finish(
 
     if response.status().is_success() {
         response
-            .json::<TotpVerifyResponse>()
+            .json::<TotpRegistrationFinishResponse>()
             .await
             .map_err(|_| "Erro ao processar resposta do servidor".to_string())
     } else {
@@ -94,6 +103,31 @@ pub async fn totp_registration_finish(
     }
 }
 
+pub async fn totp_verify(req: TotpLoginRequest) -> Result<TotpVerifyResponse, String> {
+    let client = Client::new();
+    let response = client
+        .post("http://localhost:8080/totp/verify")
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        response
+            .json::<TotpVerifyResponse>()
+            .await
+            .map_err(|_| "Erro ao processar resposta do servidor".to_string())
+    } else {
+        let error_msg = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|e| e.message)
+            .unwrap_or_else(|_| "Falha desconhecida na verificação".to_string());
+
+        Err(error_msg)
+    }
+}
+
 pub async fn webauthn_registration_start(auth_token: &str) -> Result<String, String> {
     let client = Client::new();
     let response = client
@@ -118,3 +152,132 @@ pub async fn webauthn_registration_start(auth_token: &str) -> Result<String, Str
         Err(error_msg)
     }
 }
+
+/// Returns the recovery codes this registration generated, if any.
+pub async fn webauthn_registration_finish(
+    auth_token: &str,
+    req: WebauthnRegistrationFinishRequest,
+) -> Result<RecoveryCodesResponse, String> {
+    let client = Client::new();
+    let response = client
+        .post("http://localhost:8080/mfa/registration/webauthn/finish")
+        .bearer_auth(auth_token)
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        response
+            .json::<RecoveryCodesResponse>()
+            .await
+            .map_err(|_| "Erro ao processar resposta do servidor".to_string())
+    } else {
+        let error_msg = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|e| e.message)
+            .unwrap_or_else(|_| "Falha desconhecida no cadastro".to_string());
+
+        Err(error_msg)
+    }
+}
+
+pub async fn passkey_login_start() -> Result<PasskeyLoginStartResponse, String> {
+    let client = Client::new();
+    let response = client
+        .post("http://localhost:8080/passkey/login/start")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        response
+            .json::<PasskeyLoginStartResponse>()
+            .await
+            .map_err(|_| "Erro ao processar resposta do servidor".to_string())
+    } else {
+        let error_msg = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|e| e.message)
+            .unwrap_or_else(|_| "Falha desconhecida no login".to_string());
+
+        Err(error_msg)
+    }
+}
+
+pub async fn passkey_login_finish(
+    req: PasskeyLoginFinishRequest,
+) -> Result<WebauthnAuthenticationFinishResponse, String> {
+    let client = Client::new();
+    let response = client
+        .post("http://localhost:8080/passkey/login/finish")
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        response
+            .json::<WebauthnAuthenticationFinishResponse>()
+            .await
+            .map_err(|_| "Erro ao processar resposta do servidor".to_string())
+    } else {
+        let error_msg = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|e| e.message)
+            .unwrap_or_else(|_| "Falha desconhecida no login".to_string());
+
+        Err(error_msg)
+    }
+}
+
+pub async fn logout(access_token: &str, req: LogoutRequest) -> Result<(), String> {
+    let client = Client::new();
+    let response = client
+        .post("http://localhost:8080/logout")
+        .bearer_auth(access_token)
+        .json(&req)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        let error_msg = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|e| e.message)
+            .unwrap_or_else(|_| "Falha desconhecida ao sair".to_string());
+
+        Err(error_msg)
+    }
+}
+
+pub async fn list_passkeys(access_token: &str) -> Result<Vec<PasskeyResponse>, String> {
+    let client = Client::new();
+    let response = client
+        .get("http://localhost:8080/mfa/passkeys")
+        .bearer_auth(access_token)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        response
+            .json::<Vec<PasskeyResponse>>()
+            .await
+            .map_err(|_| "Erro ao processar resposta do servidor".to_string())
+    } else {
+        let error_msg = response
+            .json::<ErrorResponse>()
+            .await
+            .map(|e| e.message)
+            .unwrap_or_else(|_| "Falha ao carregar as passkeys".to_string());
+
+        Err(error_msg)
+    }
+}
@@ -1 +1,2 @@
 pub mod validator;
+pub mod webauthn;
@@ -26,7 +26,7 @@ pub fn sync_field_error(
 ) {
     let new_error = error_map.get(key).cloned();
 
-    if (*field).error != new_error {
+    if field.error != new_error {
         let mut f = (**field).clone();
         f.error = new_error; // Aqui f.error vira None
         field.set(f);
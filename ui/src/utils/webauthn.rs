@@ -0,0 +1,81 @@
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::js_sys::{self, Array, ArrayBuffer, Reflect, Uint8Array};
+use web_sys::{AuthenticatorAssertionResponse, PublicKeyCredential};
+
+pub fn array_buffer_to_b64(buffer: &ArrayBuffer) -> String {
+    URL_SAFE_NO_PAD.encode(Uint8Array::new(buffer).to_vec())
+}
+
+fn b64_to_array(value: &JsValue) -> Result<Option<Uint8Array>, String> {
+    let Some(s) = value.as_string() else {
+        return Ok(None);
+    };
+    let bytes = URL_SAFE_NO_PAD
+        .decode(s)
+        .map_err(|_| "Erro ao decodificar b64")?;
+    Ok(Some(Uint8Array::from(&bytes[..])))
+}
+
+pub fn js_value_to_string(val: JsValue) -> String {
+    // Tenta pegar a propriedade "message" (comum em erros de JS/DOM)
+    if let Ok(msg) = Reflect::get(&val, &JsValue::from_str("message"))
+        && let Some(s) = msg.as_string()
+    {
+        return s;
+    }
+    // Caso contrário, tenta converter o objeto inteiro para string
+    val.as_string()
+        .unwrap_or_else(|| "Erro desconhecido na chave de segurança".to_string())
+}
+
+/// Converte as opções de `navigator.credentials.get` vindas do servidor (campos binários em b64).
+pub fn prepare_request_options(
+    options: &serde_json::Value,
+) -> Result<web_sys::PublicKeyCredentialRequestOptions, String> {
+    let js_value =
+        js_sys::JSON::parse(&options.to_string()).map_err(|_| "JSON inválido vindo do servidor")?;
+    let pk_options_js = Reflect::get(&js_value, &JsValue::from_str("publicKey"))
+        .ok()
+        .filter(JsValue::is_object)
+        .unwrap_or(js_value);
+
+    let challenge = Reflect::get(&pk_options_js, &JsValue::from_str("challenge"))
+        .map_err(|_| "Campo challenge ausente")?;
+    if let Some(array) = b64_to_array(&challenge)? {
+        Reflect::set(&pk_options_js, &JsValue::from_str("challenge"), &array).unwrap();
+    }
+
+    if let Ok(allowed) = Reflect::get(&pk_options_js, &JsValue::from_str("allowCredentials"))
+        && Array::is_array(&allowed)
+    {
+        for cred in Array::from(&allowed).iter() {
+            let id = Reflect::get(&cred, &JsValue::from_str("id")).unwrap_or_default();
+            if let Some(array) = b64_to_array(&id)? {
+                Reflect::set(&cred, &JsValue::from_str("id"), &array).unwrap();
+            }
+        }
+    }
+
+    Ok(pk_options_js.unchecked_into::<web_sys::PublicKeyCredentialRequestOptions>())
+}
+
+/// Serializa a asserção no formato JSON esperado pelo `PublicKeyCredential` do servidor.
+pub fn assertion_to_json(cred: &PublicKeyCredential) -> serde_json::Value {
+    let response = cred
+        .response()
+        .unchecked_into::<AuthenticatorAssertionResponse>();
+
+    serde_json::json!({
+        "id": cred.id(),
+        "rawId": array_buffer_to_b64(&cred.raw_id()),
+        "type": cred.type_(),
+        "response": {
+            "authenticatorData": array_buffer_to_b64(&response.authenticator_data()),
+            "clientDataJSON": array_buffer_to_b64(&response.client_data_json()),
+            "signature": array_buffer_to_b64(&response.signature()),
+            "userHandle": response.user_handle().map(|handle| array_buffer_to_b64(&handle)),
+        },
+        "extensions": {},
+    })
+}